@@ -0,0 +1,20 @@
+CREATE TABLE
+    IF NOT EXISTS veeam_job_sessions (
+        id SERIAL PRIMARY KEY NOT NULL,
+        job_kind TEXT NOT NULL,
+        job_uid TEXT NOT NULL,
+        job_name TEXT NOT NULL DEFAULT 'N/A',
+        status TEXT NOT NULL DEFAULT 'N/A',
+        last_run TIMESTAMP
+        WITH
+            TIME ZONE NOT NULL,
+            last_end_time TIMESTAMP
+        WITH
+            TIME ZONE,
+            last_duration BIGINT NOT NULL DEFAULT 0,
+            failure_message TEXT NOT NULL DEFAULT 'N/A',
+            company_name TEXT NOT NULL,
+            UNIQUE (job_kind, job_uid, last_run)
+    );
+
+CREATE INDEX IF NOT EXISTS veeam_job_sessions_company_name_idx ON veeam_job_sessions (company_name);
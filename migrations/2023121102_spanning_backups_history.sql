@@ -0,0 +1,57 @@
+ALTER TABLE spanning_backups
+ADD COLUMN IF NOT EXISTS first_seen TIMESTAMP
+WITH
+    TIME ZONE NOT NULL DEFAULT NOW (),
+ADD COLUMN IF NOT EXISTS last_seen TIMESTAMP
+WITH
+    TIME ZONE NOT NULL DEFAULT NOW (),
+ADD COLUMN IF NOT EXISTS unassigned_at TIMESTAMP
+WITH
+    TIME ZONE;
+
+UPDATE spanning_backups
+SET
+    unassigned_at = NOW ()
+WHERE
+    NOT assigned
+    AND unassigned_at IS NULL;
+
+-- Earlier syncs only ever inserted, so keep the newest copy of each user and summary before adding the keys.
+DELETE FROM spanning_backups_summaries
+WHERE
+    backup IN (
+        SELECT
+            id
+        FROM
+            spanning_backups AS duplicate
+        WHERE
+            EXISTS (
+                SELECT
+                    1
+                FROM
+                    spanning_backups AS newer
+                WHERE
+                    newer.email = duplicate.email
+                    AND newer.company_name = duplicate.company_name
+                    AND newer.id > duplicate.id
+            )
+    );
+
+DELETE FROM spanning_backups AS duplicate USING spanning_backups AS newer
+WHERE
+    newer.email = duplicate.email
+    AND newer.company_name = duplicate.company_name
+    AND newer.id > duplicate.id;
+
+DELETE FROM spanning_backups_summaries AS duplicate USING spanning_backups_summaries AS newer
+WHERE
+    newer.backup = duplicate.backup
+    AND newer.date = duplicate.date
+    AND newer.backup_type = duplicate.backup_type
+    AND newer.id > duplicate.id;
+
+ALTER TABLE spanning_backups
+ADD CONSTRAINT spanning_backups_email_company_name_key UNIQUE (email, company_name);
+
+ALTER TABLE spanning_backups_summaries
+ADD CONSTRAINT spanning_backups_summaries_backup_date_backup_type_key UNIQUE (backup, date, backup_type);
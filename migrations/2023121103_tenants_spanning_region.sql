@@ -0,0 +1,5 @@
+ALTER TABLE tenants
+ADD COLUMN IF NOT EXISTS spanning_region TEXT NOT NULL DEFAULT 'eu';
+
+ALTER TABLE tenants
+ADD CONSTRAINT tenants_spanning_region_check CHECK (spanning_region IN ('us', 'eu', 'ap'));
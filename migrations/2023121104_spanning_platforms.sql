@@ -0,0 +1,26 @@
+ALTER TABLE spanning_backups
+ADD COLUMN IF NOT EXISTS platform TEXT NOT NULL DEFAULT 'o365';
+
+ALTER TABLE spanning_backups
+ADD CONSTRAINT spanning_backups_platform_check CHECK (platform IN ('o365', 'google_workspace', 'salesforce'));
+
+-- Only Microsoft 365 users carry a Microsoft id.
+ALTER TABLE spanning_backups
+ALTER COLUMN ms_id
+SET DEFAULT 'N/A';
+
+ALTER TABLE spanning_backups
+DROP CONSTRAINT IF EXISTS spanning_backups_email_company_name_key;
+
+ALTER TABLE spanning_backups
+ADD CONSTRAINT spanning_backups_email_company_name_platform_key UNIQUE (email, company_name, platform);
+
+CREATE TABLE
+    IF NOT EXISTS tenants_spanning_platforms (
+        id SERIAL PRIMARY KEY NOT NULL,
+        tenant_id INTEGER NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+        platform TEXT NOT NULL CHECK (platform IN ('google_workspace', 'salesforce')),
+        spanning_name TEXT NOT NULL,
+        spanning_key TEXT NOT NULL,
+        UNIQUE (tenant_id, platform)
+    );
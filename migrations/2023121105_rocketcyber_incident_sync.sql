@@ -0,0 +1,16 @@
+CREATE TABLE
+    IF NOT EXISTS rocketcyber_incident_sync (
+        account_id BIGINT PRIMARY KEY NOT NULL,
+        high_water_mark TIMESTAMP
+        WITH
+            TIME ZONE NOT NULL,
+            last_synced_at TIMESTAMP
+        WITH
+            TIME ZONE NOT NULL DEFAULT NOW ()
+    );
+
+ALTER TABLE rocketcyber_incidents
+ALTER COLUMN resolved_at
+DROP DEFAULT;
+
+CREATE INDEX IF NOT EXISTS rocketcyber_incidents_account_id_created_at_idx ON rocketcyber_incidents (account_id, created_at);
@@ -0,0 +1,22 @@
+CREATE TABLE
+    IF NOT EXISTS rocketcyber_incident_events (
+        id BIGINT PRIMARY KEY NOT NULL,
+        incident_id BIGINT NOT NULL REFERENCES rocketcyber_incidents (id) ON DELETE CASCADE,
+        account_id BIGINT NOT NULL,
+        agent_id TEXT,
+        hostname TEXT NOT NULL DEFAULT 'N/A',
+        app TEXT NOT NULL DEFAULT 'N/A',
+        detection TEXT NOT NULL DEFAULT 'N/A',
+        verdict TEXT NOT NULL DEFAULT 'N/A',
+        details JSONB,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE
+    );
+
+CREATE INDEX IF NOT EXISTS rocketcyber_incident_events_incident_id_idx ON rocketcyber_incident_events (incident_id);
+
+-- The `event_count` an incident's events were last fetched for, RocketCyber can return fewer events
+-- than it counts so the stored events are not compared against it.
+ALTER TABLE rocketcyber_incidents
+ADD COLUMN IF NOT EXISTS events_synced_count BIGINT;
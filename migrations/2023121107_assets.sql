@@ -0,0 +1,29 @@
+CREATE TABLE
+    IF NOT EXISTS assets (
+        id SERIAL PRIMARY KEY NOT NULL,
+        tenant_id INTEGER NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+        hostname TEXT NOT NULL,
+        display_name TEXT NOT NULL,
+        serial_number TEXT,
+        ip_address TEXT,
+        first_seen TIMESTAMP
+        WITH
+            TIME ZONE NOT NULL DEFAULT NOW(),
+            last_seen TIMESTAMP
+        WITH
+            TIME ZONE NOT NULL DEFAULT NOW(),
+            UNIQUE (tenant_id, hostname)
+    );
+
+CREATE TABLE
+    IF NOT EXISTS asset_links (
+        id SERIAL PRIMARY KEY NOT NULL,
+        asset_id INTEGER NOT NULL REFERENCES assets (id) ON DELETE CASCADE,
+        source TEXT NOT NULL CHECK (source IN ('vsa', 'cybercns', 'rocketcyber', 'veeam')),
+        source_id TEXT NOT NULL,
+        source_name TEXT NOT NULL,
+        matched_on TEXT NOT NULL CHECK (matched_on IN ('hostname', 'serial_number', 'ip_address')),
+        UNIQUE (asset_id, source, source_id)
+    );
+
+CREATE INDEX IF NOT EXISTS asset_links_source_idx ON asset_links (source, source_id);
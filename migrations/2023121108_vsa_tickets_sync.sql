@@ -0,0 +1,38 @@
+-- Open tickets have no closed, resolution or lock dates, so these can no longer default to now.
+ALTER TABLE vsa_tickets
+ALTER COLUMN closed_date_time
+DROP NOT NULL,
+ALTER COLUMN closed_date_time
+DROP DEFAULT,
+ALTER COLUMN due_date
+DROP NOT NULL,
+ALTER COLUMN due_date
+DROP DEFAULT,
+ALTER COLUMN projected_date
+DROP NOT NULL,
+ALTER COLUMN projected_date
+DROP DEFAULT,
+ALTER COLUMN locked_on_date_time
+DROP NOT NULL,
+ALTER COLUMN locked_on_date_time
+DROP DEFAULT,
+ALTER COLUMN last_public_update_date
+DROP NOT NULL,
+ALTER COLUMN last_public_update_date
+DROP DEFAULT,
+ALTER COLUMN resolution_date
+DROP NOT NULL,
+ALTER COLUMN resolution_date
+DROP DEFAULT;
+
+DELETE FROM vsa_tickets AS duplicate USING vsa_tickets AS newer
+WHERE
+    newer.ticket_id = duplicate.ticket_id
+    AND newer.id > duplicate.id;
+
+ALTER TABLE vsa_tickets
+ADD CONSTRAINT vsa_tickets_ticket_id_key UNIQUE (ticket_id);
+
+CREATE INDEX IF NOT EXISTS vsa_tickets_organization_idx ON vsa_tickets (organization);
+
+CREATE INDEX IF NOT EXISTS vsa_tickets_modification_date_time_idx ON vsa_tickets (modification_date_time);
@@ -0,0 +1,7 @@
+ALTER TABLE vsa_agents
+ADD COLUMN IF NOT EXISTS os_family TEXT,
+ADD COLUMN IF NOT EXISTS os_product TEXT,
+ADD COLUMN IF NOT EXISTS os_edition TEXT,
+ADD COLUMN IF NOT EXISTS os_version TEXT,
+ADD COLUMN IF NOT EXISTS os_build BIGINT,
+ADD COLUMN IF NOT EXISTS os_end_of_support DATE;
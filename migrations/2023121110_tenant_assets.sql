@@ -0,0 +1,16 @@
+CREATE TABLE
+    IF NOT EXISTS tenant_assets (
+        id SERIAL PRIMARY KEY NOT NULL,
+        tenant_id INTEGER NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+        kind TEXT NOT NULL,
+        file_name TEXT NOT NULL DEFAULT 'N/A',
+        mime_type TEXT NOT NULL,
+        size BIGINT NOT NULL,
+        sha256 TEXT NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE NOT NULL DEFAULT NOW (),
+            UNIQUE (tenant_id, kind)
+    );
+
+CREATE INDEX IF NOT EXISTS tenant_assets_sha256_idx ON tenant_assets (sha256);
@@ -0,0 +1,11 @@
+-- Uploaded images are stored as resized variants (full size and thumbnail) of one asset kind.
+ALTER TABLE tenant_assets
+ADD COLUMN IF NOT EXISTS variant TEXT NOT NULL DEFAULT 'original',
+ADD COLUMN IF NOT EXISTS width INTEGER,
+ADD COLUMN IF NOT EXISTS height INTEGER;
+
+ALTER TABLE tenant_assets
+DROP CONSTRAINT IF EXISTS tenant_assets_tenant_id_kind_key;
+
+ALTER TABLE tenant_assets
+ADD CONSTRAINT tenant_assets_tenant_id_kind_variant_key UNIQUE (tenant_id, kind, variant);
@@ -0,0 +1,32 @@
+CREATE TABLE
+    IF NOT EXISTS external_scans (
+        id SERIAL PRIMARY KEY NOT NULL,
+        tenant_id INTEGER NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+        host_name TEXT NOT NULL,
+        company_id TEXT,
+        raw_key TEXT,
+        scanned_at TIMESTAMP
+        WITH
+            TIME ZONE NOT NULL DEFAULT NOW ()
+    );
+
+CREATE INDEX IF NOT EXISTS external_scans_tenant_id_host_name_scanned_at_idx ON external_scans (tenant_id, host_name, scanned_at);
+
+CREATE TABLE
+    IF NOT EXISTS external_scan_findings (
+        id SERIAL PRIMARY KEY NOT NULL,
+        scan_id INTEGER NOT NULL REFERENCES external_scans (id) ON DELETE CASCADE,
+        kind TEXT NOT NULL CHECK (kind IN ('open_port', 'tls', 'vulnerability')),
+        severity TEXT NOT NULL CHECK (
+            severity IN ('critical', 'high', 'medium', 'low', 'info')
+        ),
+        title TEXT NOT NULL,
+        port INTEGER,
+        protocol TEXT,
+        service TEXT,
+        cve TEXT,
+        cvss_score DOUBLE PRECISION,
+        detail TEXT
+    );
+
+CREATE INDEX IF NOT EXISTS external_scan_findings_scan_id_idx ON external_scan_findings (scan_id);
@@ -0,0 +1,7 @@
+-- Filled in after each scan by comparing it with the previous scan of the same host.
+ALTER TABLE external_scans
+ADD COLUMN IF NOT EXISTS previous_scan_id INTEGER REFERENCES external_scans (id) ON DELETE SET NULL,
+ADD COLUMN IF NOT EXISTS opened_ports INTEGER,
+ADD COLUMN IF NOT EXISTS new_vulnerabilities INTEGER,
+ADD COLUMN IF NOT EXISTS new_tls_issues INTEGER,
+ADD COLUMN IF NOT EXISTS resolved_findings INTEGER;
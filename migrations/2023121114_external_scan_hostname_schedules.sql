@@ -0,0 +1,88 @@
+-- Hostnames, IPs and CIDR ranges are stored normalised (lowercase, canonical address) and once per tenant.
+ALTER TABLE tenants_external_scan_host_names
+ALTER COLUMN tenant_id DROP DEFAULT;
+
+DROP SEQUENCE IF EXISTS tenants_external_scan_host_names_tenant_id_seq;
+
+DELETE FROM tenants_external_scan_host_names AS h
+WHERE NOT EXISTS (SELECT 1 FROM tenants AS t WHERE t.id = h.tenant_id);
+
+ALTER TABLE tenants_external_scan_host_names
+ADD COLUMN IF NOT EXISTS target_kind TEXT NOT NULL DEFAULT 'hostname' CHECK (target_kind IN ('hostname', 'ip', 'cidr')),
+ADD COLUMN IF NOT EXISTS enabled BOOLEAN NOT NULL DEFAULT TRUE,
+ADD COLUMN IF NOT EXISTS scan_frequency TEXT NOT NULL DEFAULT 'daily' CHECK (scan_frequency IN ('daily', 'weekly', 'monthly')),
+ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+UPDATE tenants_external_scan_host_names
+SET host_name = RTRIM(LOWER(TRIM(host_name)), '.');
+
+-- Existing entries are classified the way the API parses new ones: an IPv4 or IPv6 address, or a
+-- range of one up to a /24 (/120 for IPv6).
+CREATE TEMPORARY TABLE external_scan_address_patterns (family INTEGER, pattern TEXT, min_prefix INTEGER, max_prefix INTEGER);
+
+INSERT INTO external_scan_address_patterns VALUES
+(4, '^((25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])\.){3}(25[0-5]|2[0-4][0-9]|1[0-9]{2}|[1-9]?[0-9])$', 24, 32),
+(6, '^([0-9a-f]{1,4}:){7}[0-9a-f]{1,4}$', 120, 128),
+-- Compressed IPv6 addresses, a single `::` and no stray leading or trailing colon.
+(6, '^(?=.*::)(?!.*::.*::)(?!:[^:])(?!.*[^:]:$)([0-9a-f]{0,4}:){2,7}[0-9a-f]{0,4}$', 120, 128);
+
+UPDATE tenants_external_scan_host_names AS h
+SET target_kind = 'ip'
+FROM external_scan_address_patterns AS p
+WHERE h.host_name ~ p.pattern;
+
+UPDATE tenants_external_scan_host_names AS h
+SET target_kind = 'cidr'
+FROM external_scan_address_patterns AS p
+WHERE SPLIT_PART(h.host_name, '/', 1) ~ p.pattern
+AND SPLIT_PART(h.host_name, '/', 2) ~ '^[0-9]{1,3}$'
+AND SPLIT_PART(h.host_name, '/', 2)::INTEGER BETWEEN p.min_prefix AND p.max_prefix
+AND h.host_name ~ '^[^/]+/[^/]+$';
+
+DROP TABLE external_scan_address_patterns;
+
+-- `203.0.113.7/24` becomes `203.0.113.0/24`.
+UPDATE tenants_external_scan_host_names
+SET host_name = NETWORK(host_name::INET)::TEXT
+WHERE target_kind = 'cidr';
+
+-- URLs, `host:port` entries, ranges that are too large and malformed names are kept for review but no
+-- longer scanned, as are addresses CyberCNS cannot reach from the internet.
+UPDATE tenants_external_scan_host_names
+SET enabled = FALSE
+WHERE target_kind = 'hostname'
+AND (
+    LENGTH(host_name) > 253
+    OR host_name !~ '^([a-z0-9]([a-z0-9-]{0,61}[a-z0-9])?\.)+[a-z0-9]([a-z0-9-]{0,61}[a-z0-9])?$'
+    OR host_name ~ '\.[0-9]+$'
+);
+
+UPDATE tenants_external_scan_host_names
+SET enabled = FALSE
+WHERE CASE
+    WHEN target_kind IN ('ip', 'cidr') THEN host_name::INET && ANY (ARRAY[
+        '0.0.0.0/32',
+        '10.0.0.0/8',
+        '127.0.0.0/8',
+        '169.254.0.0/16',
+        '172.16.0.0/12',
+        '192.168.0.0/16',
+        '255.255.255.255/32',
+        '::/128',
+        '::1/128',
+        '::ffff:0:0/96',
+        'fc00::/7',
+        'fe80::/10'
+    ]::INET[])
+    ELSE FALSE
+END;
+
+DELETE FROM tenants_external_scan_host_names AS h
+USING tenants_external_scan_host_names AS d
+WHERE d.tenant_id = h.tenant_id
+AND d.host_name = h.host_name
+AND d.id < h.id;
+
+ALTER TABLE tenants_external_scan_host_names
+ADD CONSTRAINT tenants_external_scan_host_names_tenant_id_fkey FOREIGN KEY (tenant_id) REFERENCES tenants (id) ON DELETE CASCADE,
+ADD CONSTRAINT tenants_external_scan_host_names_tenant_id_host_name_key UNIQUE (tenant_id, host_name);
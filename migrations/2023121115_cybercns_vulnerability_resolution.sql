@@ -0,0 +1,14 @@
+-- Vulnerabilities are upserted on every sync, those no longer reported by CyberCNS are marked resolved.
+ALTER TABLE cybercns_vulnerabilities
+ADD COLUMN IF NOT EXISTS vul_id TEXT,
+ADD COLUMN IF NOT EXISTS first_seen TIMESTAMPTZ,
+ADD COLUMN IF NOT EXISTS last_seen TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+ADD COLUMN IF NOT EXISTS resolved_at TIMESTAMPTZ;
+
+-- When vulnerabilities stored before this migration were first found is unknown, so they keep a null
+-- `first_seen` and are left out of remediation times. New ones are stamped when first synced.
+ALTER TABLE cybercns_vulnerabilities
+ALTER COLUMN first_seen SET DEFAULT NOW();
+
+CREATE INDEX IF NOT EXISTS cybercns_vulnerabilities_company_id_severity_idx ON cybercns_vulnerabilities (company_id, severity)
+WHERE resolved_at IS NULL;
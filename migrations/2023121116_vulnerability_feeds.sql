@@ -0,0 +1,28 @@
+ALTER TABLE cybercns_vulnerabilities
+ADD COLUMN IF NOT EXISTS cisa BOOLEAN NOT NULL DEFAULT FALSE,
+ADD COLUMN IF NOT EXISTS cisa_vulnerability_name TEXT;
+
+-- CISA Known Exploited Vulnerabilities catalogue, replaced on every import.
+CREATE TABLE
+    IF NOT EXISTS known_exploited_vulnerabilities (
+        cve_id TEXT PRIMARY KEY NOT NULL,
+        vendor_project TEXT,
+        product TEXT,
+        vulnerability_name TEXT,
+        date_added DATE,
+        short_description TEXT,
+        required_action TEXT,
+        due_date DATE,
+        known_ransomware_campaign_use BOOLEAN NOT NULL DEFAULT FALSE,
+        imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
+
+-- FIRST EPSS scores, the probability of exploitation in the next 30 days, replaced on every import.
+CREATE TABLE
+    IF NOT EXISTS epss_scores (
+        cve_id TEXT PRIMARY KEY NOT NULL,
+        epss DOUBLE PRECISION NOT NULL,
+        percentile DOUBLE PRECISION NOT NULL,
+        score_date DATE,
+        imported_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );
@@ -0,0 +1,3 @@
+-- An incident is open until it has a resolved_at or RocketCyber marks it resolved, some are resolved without a timestamp.
+ALTER TABLE rocketcyber_incidents
+ADD COLUMN IF NOT EXISTS is_open BOOLEAN NOT NULL GENERATED ALWAYS AS (resolved_at IS NULL AND LOWER(status) <> 'resolved') STORED;
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::correlate::{correlate_assets, AssetCandidate};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct AssetSyncResult {
+    pub tenants: i64,
+    pub inserted: i64,
+    pub updated: i64,
+    pub removed: i64,
+    pub links: i64,
+}
+
+/**
+Rebuild the correlated asset inventory for every tenant from the synced vendor tables.
+
+Each vendor is scoped to the tenant by its own name on the tenant, falling back to `tenant_name`.
+Assets keep their id and `first_seen` between syncs, their links are rebuilt every time and assets
+no vendor reports any more are removed.
+*/
+pub async fn sync_asset_inventory(pool: &PgPool) -> Result<AssetSyncResult> {
+    let tenants = sqlx::query!(
+        r#"
+            SELECT
+                id,
+                COALESCE(vsa_name, tenant_name) AS "vsa_name!",
+                COALESCE(cyber_cns_name, tenant_name) AS "cyber_cns_name!",
+                COALESCE(rocket_cyber_name, tenant_name) AS "rocket_cyber_name!",
+                tenant_name
+            FROM tenants
+            ORDER BY id;
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut result = AssetSyncResult::default();
+
+    for tenant in tenants {
+        let mut candidates: Vec<AssetCandidate> = Vec::new();
+
+        let vsa_agents = sqlx::query!(
+            r#"
+                SELECT id, computer_name, system_serial_number, ip_address
+                FROM vsa_agents
+                WHERE similarity(LOWER(organization_name), LOWER($1)) >= 0.6
+                ORDER BY computer_name;
+            "#,
+            tenant.vsa_name
+        )
+        .fetch_all(pool)
+        .await?;
+
+        candidates.extend(vsa_agents.into_iter().map(|agent| AssetCandidate {
+            source: "vsa".to_string(),
+            source_id: agent.id,
+            name: agent.computer_name.unwrap_or_default(),
+            serial_number: agent.system_serial_number,
+            ip_address: agent.ip_address,
+        }));
+
+        let veeam_agents = sqlx::query!(
+            r#"
+                SELECT instance_uid, name
+                FROM veeam_agents
+                WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6
+                ORDER BY name;
+            "#,
+            tenant.tenant_name
+        )
+        .fetch_all(pool)
+        .await?;
+
+        candidates.extend(veeam_agents.into_iter().map(|agent| AssetCandidate {
+            source: "veeam".to_string(),
+            source_id: agent.instance_uid,
+            name: agent.name,
+            serial_number: None,
+            ip_address: None,
+        }));
+
+        let rocketcyber_agents = sqlx::query!(
+            r#"
+                SELECT agent.id, agent.hostname
+                FROM rocketcyber_agents AS agent
+                INNER JOIN rocketcyber_accounts AS account ON account.account_id = agent.customer_id
+                WHERE similarity(LOWER(account.account_name), LOWER($1)) >= 0.6
+                ORDER BY agent.hostname;
+            "#,
+            tenant.rocket_cyber_name
+        )
+        .fetch_all(pool)
+        .await?;
+
+        candidates.extend(rocketcyber_agents.into_iter().map(|agent| AssetCandidate {
+            source: "rocketcyber".to_string(),
+            source_id: agent.id,
+            name: agent.hostname,
+            serial_number: None,
+            ip_address: None,
+        }));
+
+        let cybercns_assets = sqlx::query!(
+            r#"
+                SELECT asset.id, host.host_name
+                FROM cybercns_assets AS asset
+                INNER JOIN cybercns_hosts AS host ON asset.host = host.id
+                INNER JOIN cybercns_companies AS company ON asset.company = company.id
+                WHERE similarity(LOWER(company.name), LOWER($1)) >= 0.6
+                ORDER BY host.host_name;
+            "#,
+            tenant.cyber_cns_name
+        )
+        .fetch_all(pool)
+        .await?;
+
+        candidates.extend(cybercns_assets.into_iter().map(|asset| AssetCandidate {
+            source: "cybercns".to_string(),
+            source_id: asset.id,
+            name: asset.host_name.unwrap_or_default(),
+            serial_number: None,
+            ip_address: None,
+        }));
+
+        let mut transaction = pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM asset_links
+                WHERE asset_id IN (SELECT id FROM assets WHERE tenant_id = $1);
+            "#,
+            tenant.id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        for asset in correlate_assets(candidates) {
+            // NOW() is fixed for the transaction, so every asset touched here shares one last_seen.
+            let upserted = sqlx::query!(
+                r#"
+                    INSERT INTO assets (
+                        tenant_id,
+                        hostname,
+                        display_name,
+                        serial_number,
+                        ip_address,
+                        last_seen
+                    ) VALUES ($1, $2, $3, $4, $5, NOW())
+                    ON CONFLICT (tenant_id, hostname) DO UPDATE SET
+                        display_name = EXCLUDED.display_name,
+                        serial_number = COALESCE(EXCLUDED.serial_number, assets.serial_number),
+                        ip_address = COALESCE(EXCLUDED.ip_address, assets.ip_address),
+                        last_seen = EXCLUDED.last_seen
+                    RETURNING id, (xmax = 0) AS "inserted!";
+                "#,
+                tenant.id,
+                asset.hostname,
+                asset.display_name,
+                asset.serial_number,
+                asset.ip_address
+            )
+            .fetch_one(&mut *transaction)
+            .await?;
+
+            if upserted.inserted {
+                result.inserted += 1;
+            } else {
+                result.updated += 1;
+            }
+
+            for link in asset.links {
+                sqlx::query!(
+                    r#"
+                        INSERT INTO asset_links (asset_id, source, source_id, source_name, matched_on)
+                        VALUES ($1, $2, $3, $4, $5)
+                        ON CONFLICT (asset_id, source, source_id) DO NOTHING;
+                    "#,
+                    upserted.id,
+                    link.source,
+                    link.source_id,
+                    link.source_name,
+                    link.matched_on
+                )
+                .execute(&mut *transaction)
+                .await?;
+
+                result.links += 1;
+            }
+        }
+
+        let removed = sqlx::query!(
+            "DELETE FROM assets WHERE tenant_id = $1 AND last_seen < NOW();",
+            tenant.id
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        result.removed += removed.rows_affected() as i64;
+
+        transaction.commit().await?;
+
+        result.tenants += 1;
+    }
+
+    Ok(result)
+}
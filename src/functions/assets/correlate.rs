@@ -0,0 +1,315 @@
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+pub const ASSET_SOURCES: [&str; 4] = ["vsa", "cybercns", "rocketcyber", "veeam"];
+
+/// Serial numbers that OEMs ship as placeholders and that would merge unrelated machines.
+const PLACEHOLDER_SERIAL_NUMBERS: [&str; 8] = [
+    "N/A",
+    "NONE",
+    "DEFAULT STRING",
+    "SYSTEM SERIAL NUMBER",
+    "TO BE FILLED BY O.E.M.",
+    "0",
+    "0123456789",
+    "123456789",
+];
+
+/// Addresses that say nothing about which machine an agent runs on.
+const PLACEHOLDER_IP_ADDRESSES: [&str; 4] = ["N/A", "0.0.0.0", "127.0.0.1", "::1"];
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AssetCandidate {
+    pub source: String,
+    pub source_id: String,
+    pub name: String,
+    pub serial_number: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CorrelatedAssetLink {
+    pub source: String,
+    pub source_id: String,
+    pub source_name: String,
+    pub matched_on: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CorrelatedAsset {
+    pub hostname: String,
+    pub display_name: String,
+    pub serial_number: Option<String>,
+    pub ip_address: Option<String>,
+    pub hostnames: Vec<String>,
+    pub links: Vec<CorrelatedAssetLink>,
+}
+
+impl CorrelatedAsset {
+    fn has_source(&self, source: &str) -> bool {
+        self.links.iter().any(|link| link.source == source)
+    }
+}
+
+/**
+Normalise a machine name so the same PC matches across vendors.
+
+Lowercases, strips a `DOMAIN\` prefix and a DNS suffix, so `CONTOSO\PC-01` and `pc-01.contoso.local`
+both become `pc-01`. Scanners that only know an address report it as the name, those are kept whole.
+*/
+pub fn normalise_hostname(name: &str) -> String {
+    let name = name.trim();
+
+    if name.parse::<IpAddr>().is_ok() {
+        return name.to_string();
+    }
+
+    let name = name.rsplit('\\').next().unwrap_or(name);
+    let name = name.split('.').next().unwrap_or(name);
+
+    name.trim().to_lowercase()
+}
+
+pub fn normalise_serial_number(serial_number: &str) -> Option<String> {
+    let serial_number = serial_number.trim().to_uppercase();
+
+    if serial_number.is_empty() || PLACEHOLDER_SERIAL_NUMBERS.contains(&serial_number.as_str()) {
+        return None;
+    }
+
+    Some(serial_number)
+}
+
+pub fn normalise_ip_address(ip_address: &str) -> Option<String> {
+    // Agents with several adapters report a comma separated list, the first is the primary.
+    let ip_address = ip_address.split(',').next().unwrap_or("").trim();
+
+    if ip_address.is_empty() || PLACEHOLDER_IP_ADDRESSES.contains(&ip_address) {
+        return None;
+    }
+
+    Some(ip_address.to_string())
+}
+
+/**
+Group vendor rows into machines.
+
+Candidates are matched to an existing asset by serial number first, then normalised hostname, then
+IP address. An IP address only matches an asset that has no row from the same vendor yet, since two
+machines behind DHCP can share an address over time. Pass the richest source first (VSA), so assets
+are keyed on its hostname and pick up its serial number and IP address.
+*/
+pub fn correlate_assets(candidates: Vec<AssetCandidate>) -> Vec<CorrelatedAsset> {
+    let mut assets: Vec<CorrelatedAsset> = Vec::new();
+
+    for candidate in candidates {
+        let hostname = normalise_hostname(&candidate.name);
+
+        if hostname.is_empty() || hostname == "n/a" {
+            continue;
+        }
+
+        let serial_number = candidate
+            .serial_number
+            .as_deref()
+            .and_then(normalise_serial_number);
+
+        let ip_address = candidate
+            .ip_address
+            .as_deref()
+            .and_then(normalise_ip_address)
+            .or_else(|| {
+                hostname
+                    .parse::<IpAddr>()
+                    .ok()
+                    .map(|ip_address| ip_address.to_string())
+            });
+
+        let serial_match = serial_number.as_ref().and_then(|serial_number| {
+            assets
+                .iter()
+                .position(|asset| asset.serial_number.as_ref() == Some(serial_number))
+        });
+
+        let hostname_match = || {
+            assets
+                .iter()
+                .position(|asset| asset.hostnames.contains(&hostname))
+        };
+
+        let ip_match = || {
+            ip_address.as_ref().and_then(|ip_address| {
+                assets.iter().position(|asset| {
+                    asset.ip_address.as_ref() == Some(ip_address)
+                        && !asset.has_source(&candidate.source)
+                })
+            })
+        };
+
+        let matched = match serial_match {
+            Some(index) => Some((index, "serial_number")),
+            None => match hostname_match() {
+                Some(index) => Some((index, "hostname")),
+                None => ip_match().map(|index| (index, "ip_address")),
+            },
+        };
+
+        match matched {
+            Some((index, matched_on)) => {
+                let asset = &mut assets[index];
+
+                if !asset.hostnames.contains(&hostname) {
+                    asset.hostnames.push(hostname);
+                }
+
+                if asset.serial_number.is_none() {
+                    asset.serial_number = serial_number;
+                }
+
+                if asset.ip_address.is_none() {
+                    asset.ip_address = ip_address;
+                }
+
+                asset.links.push(CorrelatedAssetLink {
+                    source: candidate.source,
+                    source_id: candidate.source_id,
+                    source_name: candidate.name,
+                    matched_on: matched_on.to_string(),
+                });
+            }
+            None => assets.push(CorrelatedAsset {
+                hostname: hostname.clone(),
+                display_name: candidate.name.trim().to_string(),
+                serial_number,
+                ip_address,
+                hostnames: vec![hostname],
+                links: vec![CorrelatedAssetLink {
+                    source: candidate.source,
+                    source_id: candidate.source_id,
+                    source_name: candidate.name,
+                    matched_on: "hostname".to_string(),
+                }],
+            }),
+        }
+    }
+
+    assets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        source: &str,
+        name: &str,
+        serial_number: Option<&str>,
+        ip_address: Option<&str>,
+    ) -> AssetCandidate {
+        AssetCandidate {
+            source: source.to_string(),
+            source_id: format!("{}-{}", source, name),
+            name: name.to_string(),
+            serial_number: serial_number.map(str::to_string),
+            ip_address: ip_address.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn normalises_hostnames_serial_numbers_and_addresses() {
+        assert_eq!(normalise_hostname(r"CONTOSO\PC-01"), "pc-01");
+        assert_eq!(normalise_hostname(" pc-01.contoso.local "), "pc-01");
+        assert_eq!(normalise_hostname("PC-01"), "pc-01");
+        assert_eq!(normalise_hostname("192.168.1.20"), "192.168.1.20");
+
+        assert_eq!(
+            normalise_serial_number(" 5cg1234xyz "),
+            Some("5CG1234XYZ".to_string())
+        );
+        assert_eq!(normalise_serial_number("To be filled by O.E.M."), None);
+        assert_eq!(normalise_serial_number("0"), None);
+        assert_eq!(normalise_serial_number(" "), None);
+
+        assert_eq!(
+            normalise_ip_address("10.0.0.5, 172.16.0.5"),
+            Some("10.0.0.5".to_string())
+        );
+        assert_eq!(normalise_ip_address("0.0.0.0"), None);
+        assert_eq!(normalise_ip_address(""), None);
+    }
+
+    #[test]
+    fn matches_on_serial_number_then_hostname_then_address() {
+        let assets = correlate_assets(vec![
+            candidate("vsa", "PC-01", Some("SN-1"), Some("10.0.0.1")),
+            candidate("vsa", "PC-02", Some("SN-2"), Some("10.0.0.2")),
+            // The hostname of PC-02 wins over the address of PC-01.
+            candidate("rocketcyber", r"CONTOSO\pc-02", None, Some("10.0.0.1")),
+            // The serial number of PC-01 wins over the hostname of PC-02.
+            candidate("cybercns", "PC-02", Some("sn-1"), None),
+            candidate("veeam", "backup-name", None, Some("10.0.0.2")),
+        ]);
+
+        assert_eq!(assets.len(), 2);
+
+        let matches = |asset: &CorrelatedAsset| {
+            asset
+                .links
+                .iter()
+                .map(|link| (link.source.clone(), link.matched_on.clone()))
+                .collect::<Vec<(String, String)>>()
+        };
+        let pair = |source: &str, matched_on: &str| (source.to_string(), matched_on.to_string());
+
+        assert_eq!(assets[0].hostname, "pc-01");
+        assert_eq!(
+            matches(&assets[0]),
+            vec![pair("vsa", "hostname"), pair("cybercns", "serial_number")]
+        );
+        assert_eq!(assets[0].hostnames, vec!["pc-01", "pc-02"]);
+
+        assert_eq!(assets[1].hostname, "pc-02");
+        assert_eq!(
+            matches(&assets[1]),
+            vec![
+                pair("vsa", "hostname"),
+                pair("rocketcyber", "hostname"),
+                pair("veeam", "ip_address")
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_placeholder_serial_numbers() {
+        let assets = correlate_assets(vec![
+            candidate("vsa", "PC-01", Some("To be filled by O.E.M."), None),
+            candidate("vsa", "PC-02", Some("To be filled by O.E.M."), None),
+            candidate("cybercns", "PC-03", Some("0"), None),
+        ]);
+
+        assert_eq!(assets.len(), 3);
+        assert!(assets.iter().all(|asset| asset.serial_number.is_none()));
+    }
+
+    #[test]
+    fn does_not_merge_rows_of_one_source_on_a_shared_address() {
+        let assets = correlate_assets(vec![
+            candidate("vsa", "PC-01", None, Some("10.0.0.9")),
+            candidate("vsa", "PC-02", None, Some("10.0.0.9")),
+            candidate("cybercns", "10.0.0.9", None, None),
+            candidate("cybercns", "PC-03", None, Some("10.0.0.9")),
+        ]);
+
+        let hostnames: Vec<&str> = assets.iter().map(|asset| asset.hostname.as_str()).collect();
+
+        assert_eq!(hostnames, vec!["pc-01", "pc-02"]);
+        assert_eq!(assets[0].links.len(), 2);
+        assert_eq!(assets[0].links[1].source_name, "10.0.0.9");
+        assert_eq!(assets[0].links[1].matched_on, "ip_address");
+        // PC-01 already has a CyberCNS row, so the next one on the address joins PC-02.
+        assert_eq!(assets[1].links.len(), 2);
+        assert_eq!(assets[1].links[1].source_name, "PC-03");
+        assert_eq!(assets[1].links[1].matched_on, "ip_address");
+    }
+}
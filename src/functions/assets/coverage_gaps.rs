@@ -0,0 +1,126 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// Shortest hostname matched against Veeam Backup & Replication job names, `pc` or `fs` would match
+/// far too many jobs.
+const MIN_VBR_JOB_HOSTNAME_LENGTH: i32 = 3;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CoverageGapAsset {
+    pub id: i32,
+    pub hostname: String,
+    pub display_name: String,
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CoverageGaps {
+    pub total_assets: i64,
+    pub managed_assets: i64,
+    /// VSA managed machines without a RocketCyber agent.
+    pub missing_edr: Vec<CoverageGapAsset>,
+    /// VSA managed machines without a CyberCNS agent.
+    pub missing_vulnerability_scanning: Vec<CoverageGapAsset>,
+    /// VSA managed machines without a Veeam agent or Backup & Replication job.
+    pub missing_backup: Vec<CoverageGapAsset>,
+    /// RocketCyber or CyberCNS agents on machines VSA does not manage.
+    pub unmanaged: Vec<CoverageGapAsset>,
+}
+
+/**
+Machines a tenant's tools do not fully cover, from the correlated asset inventory.
+
+A machine counts as backed up when its Veeam agent has at least one job, an installed agent with
+nothing scheduled is still a gap, or when an enabled Veeam Backup & Replication job of the tenant
+names it. VBR jobs do not list the machines they protect, so the machine's short hostname has to
+appear as a whole word in the job name (`DC01 Daily` covers `dc01.example.local`). Machines only
+known by their IP address are never matched this way, their first octet would match any job with
+that number in its name.
+*/
+pub async fn coverage_gaps(pool: &PgPool, tenant_id: i32) -> Result<CoverageGaps> {
+    let assets = sqlx::query!(
+        r#"
+            SELECT
+                asset.id,
+                asset.hostname,
+                asset.display_name,
+                ARRAY_AGG(DISTINCT link.source ORDER BY link.source) AS "sources!",
+                BOOL_OR(
+                    link.source = 'veeam' AND EXISTS (
+                        SELECT 1
+                        FROM veeam_agents AS agent
+                        INNER JOIN veeam_agents_jobs AS job ON job.backup_agent_uid = agent.instance_uid
+                        WHERE agent.instance_uid = link.source_id
+                    )
+                )
+                OR EXISTS (
+                    SELECT 1
+                    FROM veeam_servers_jobs AS job
+                    WHERE job.is_enabled
+                    AND similarity(LOWER(job.company_name), LOWER(tenant.tenant_name)) >= 0.6
+                    AND asset.hostname !~ '^[0-9.]+$'
+                    AND asset.hostname NOT LIKE '%:%'
+                    AND LENGTH(SPLIT_PART(asset.hostname, '.', 1)) >= $2
+                    AND LOWER(job.name) ~ (
+                        '(^|[^a-z0-9-])'
+                        || REGEXP_REPLACE(SPLIT_PART(LOWER(asset.hostname), '.', 1), '([^a-z0-9])', '\\\1', 'g')
+                        || '($|[^a-z0-9-])'
+                    )
+                ) AS "backed_up!"
+            FROM assets AS asset
+            INNER JOIN tenants AS tenant ON tenant.id = asset.tenant_id
+            INNER JOIN asset_links AS link ON link.asset_id = asset.id
+            WHERE asset.tenant_id = $1
+            GROUP BY asset.id, asset.hostname, asset.display_name, tenant.tenant_name
+            ORDER BY asset.hostname;
+        "#,
+        tenant_id,
+        MIN_VBR_JOB_HOSTNAME_LENGTH
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut gaps = CoverageGaps::default();
+
+    for asset in assets {
+        let has = |source: &str| asset.sources.iter().any(|found| found == source);
+
+        let managed = has("vsa");
+        let missing_edr = managed && !has("rocketcyber");
+        let missing_vulnerability_scanning = managed && !has("cybercns");
+        let missing_backup = managed && !asset.backed_up;
+        let unmanaged = !managed && (has("rocketcyber") || has("cybercns"));
+
+        gaps.total_assets += 1;
+
+        if managed {
+            gaps.managed_assets += 1;
+        }
+
+        let gap_asset = || CoverageGapAsset {
+            id: asset.id,
+            hostname: asset.hostname.clone(),
+            display_name: asset.display_name.clone(),
+            sources: asset.sources.clone(),
+        };
+
+        if missing_edr {
+            gaps.missing_edr.push(gap_asset());
+        }
+
+        if missing_vulnerability_scanning {
+            gaps.missing_vulnerability_scanning.push(gap_asset());
+        }
+
+        if missing_backup {
+            gaps.missing_backup.push(gap_asset());
+        }
+
+        if unmanaged {
+            gaps.unmanaged.push(gap_asset());
+        }
+    }
+
+    Ok(gaps)
+}
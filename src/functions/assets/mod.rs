@@ -0,0 +1,3 @@
+pub mod assets_sync;
+pub mod correlate;
+pub mod coverage_gaps;
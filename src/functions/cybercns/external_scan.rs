@@ -1,15 +1,17 @@
-use std::{env, fs::File, path::Path, time::Duration};
+use std::{env, time::Duration};
 
-use anyhow::Error;
-use chrono::Local;
+use anyhow::{anyhow, Error};
 use dotenv::dotenv;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use sqlx::postgres::PgPoolOptions;
-use tokio::{
-    fs::{create_dir, try_exists},
-    time::sleep,
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use tokio::time::{sleep, timeout, Instant};
+
+use super::{
+    external_scan_diff::record_external_scan_changes,
+    external_scan_runner::{external_scan_runner, with_retries, ExternalScanRunnerConfig},
+    external_scan_store::store_external_scan,
 };
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -20,6 +22,36 @@ pub struct ExternalScanHostname {
     pub company_id: Option<String>,
 }
 
+/// How often a hostname is scanned, as stored in `tenants_external_scan_host_names.scan_frequency`.
+pub const EXTERNAL_SCAN_FREQUENCIES: [&str; 3] = ["daily", "weekly", "monthly"];
+pub const DEFAULT_EXTERNAL_SCAN_FREQUENCY: &str = "daily";
+
+/// How a scan that did not fail ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExternalScanOutcome {
+    /// Results were stored as the scan with this id.
+    Stored(i32),
+    /// CyberCNS did not start the scan, with its reason.
+    Rejected(String),
+    /// No results within the maximum poll duration.
+    TimedOut,
+}
+
+pub fn parse_external_scan_frequency(frequency: &str) -> Option<&'static str> {
+    let frequency = frequency.trim().to_lowercase();
+
+    EXTERNAL_SCAN_FREQUENCIES
+        .into_iter()
+        .find(|scan_frequency| *scan_frequency == frequency)
+}
+
+/**
+Queue a scan of every enabled tenant hostname that is due on the external scan runner.
+
+A hostname is due when it was never scanned, or its last stored scan was at least a day, week or
+month (its `scan_frequency`) before today. Failed scans are not stored, so they are retried on the
+next run. Hostnames that no longer resolve or now point at a private address are skipped.
+*/
 pub async fn start_external_scan() -> Result<(), Error> {
     println!("Starting new external scan.");
 
@@ -42,10 +74,24 @@ pub async fn start_external_scan() -> Result<(), Error> {
                 h.tenant_id as tenant_id,
                 h.host_name as host_name,
                 t.tenant_name as tenant_name,
-                c.id as company_id
+                c.id as "company_id?"
             FROM tenants_external_scan_host_names as h
             LEFT JOIN tenants as t ON t.id = h.tenant_id
-            LEFT JOIN cybercns_companies AS c ON LOWER(c.name) = LOWER(t.cyber_cns_name);
+            LEFT JOIN cybercns_companies AS c ON LOWER(c.name) = LOWER(t.cyber_cns_name)
+            WHERE h.enabled
+            AND COALESCE(
+                (
+                    SELECT MAX(s.scanned_at)::DATE
+                    FROM external_scans AS s
+                    WHERE s.tenant_id = h.tenant_id
+                    AND LOWER(s.host_name) = h.host_name
+                ) <= CURRENT_DATE - CASE h.scan_frequency
+                    WHEN 'weekly' THEN INTERVAL '7 days'
+                    WHEN 'monthly' THEN INTERVAL '1 month'
+                    ELSE INTERVAL '1 day'
+                END,
+                TRUE
+            );
         "#
     )
     .fetch_all(&pool)
@@ -53,193 +99,186 @@ pub async fn start_external_scan() -> Result<(), Error> {
 
     match tenant_external_scan_hostnames {
         Ok(tenant_external_scan_hostnames) => {
+            println!(
+                "Queueing {} external scans.",
+                tenant_external_scan_hostnames.len()
+            );
+
             for tenant_external_scan_hostname in tenant_external_scan_hostnames {
-                let result = execute_quick_external_scan(tenant_external_scan_hostname).await;
+                let host_name = tenant_external_scan_hostname.host_name.clone();
 
-                match result {
-                    Ok(_) => {}
-                    Err(error) => println!("External Scan Error: {}", error),
+                if let Err(message) = external_scan_runner()
+                    .queue(&pool, tenant_external_scan_hostname)
+                    .await
+                {
+                    println!("Skipping external scan of {}: {}", host_name, message);
                 }
             }
         }
-        Err(_) => {}
+        Err(error) => println!("External Scan Error: {}", error),
     }
 
     Ok(())
 }
 
-pub async fn execute_quick_external_scan(data: ExternalScanHostname) -> Result<(), Error> {
-    println!(
-        "{}",
-        format!(
-            "External Scan for {} on hostname {}.",
-            &data.tenant_name.clone().unwrap(),
-            &data.host_name
-        )
-    );
-
-    dotenv().ok();
+/// One of a tenant's hostnames, ready to be queued on the external scan runner.
+pub async fn external_scan_hostname(
+    pool: &PgPool,
+    tenant_id: i32,
+    hostname_id: i32,
+) -> Result<Option<ExternalScanHostname>, Error> {
+    let hostname = sqlx::query_as!(
+        ExternalScanHostname,
+        r#"
+            SELECT
+                h.tenant_id as tenant_id,
+                h.host_name as host_name,
+                t.tenant_name as tenant_name,
+                c.id as "company_id?"
+            FROM tenants_external_scan_host_names as h
+            LEFT JOIN tenants as t ON t.id = h.tenant_id
+            LEFT JOIN cybercns_companies AS c ON LOWER(c.name) = LOWER(t.cyber_cns_name)
+            WHERE h.id = $1 AND h.tenant_id = $2;
+        "#,
+        hostname_id,
+        tenant_id
+    )
+    .fetch_optional(pool)
+    .await?;
 
-    let company_id = data.company_id.clone().unwrap();
+    Ok(hostname)
+}
 
-    let username = env::var("CYBER_CNS_CLIENT_ID").ok().unwrap();
-    let password = env::var("CYBER_CNS_CLIENT_SECRET").ok().unwrap();
+/**
+Scan a hostname and store the results.
+
+The scan is started once, then its results are polled with exponential backoff until they are
+ready or `max_poll_duration` passed. Failing requests are retried, `attempted` is called before
+every request. The deadline also bounds requests and their retries, so a CyberCNS call that hangs
+times the scan out instead of holding its runner slot.
+*/
+pub async fn execute_quick_external_scan(
+    pool: &PgPool,
+    data: &ExternalScanHostname,
+    config: &ExternalScanRunnerConfig,
+    attempted: impl Fn(),
+) -> Result<ExternalScanOutcome, Error> {
+    println!(
+        "External Scan for {} on hostname {}.",
+        data.tenant_name.as_deref().unwrap_or("unknown tenant"),
+        &data.host_name
+    );
 
-    let client = Client::builder().http1_title_case_headers().build()?;
+    let api = QuickExternalScanApi::new(data, config)?;
+    let deadline = Instant::now() + config.max_poll_duration;
 
     let req_body = json!({
         "hostname": data.host_name,
         "Scannow": true
     });
 
-    let response = client
-    .post(format!(
-        "https://portaleuwest2.mycybercns.com/api/company/{}/quickExternalScan",
-        company_id,
-    ))
-    .header("customerid", "clay")
-    .header("User-Agent", "ra-v1")
-    .header("Content-Type", "application/json")
-    .basic_auth(&username, Some(&password))
-    .json(&req_body)
-    .send()
-    .await?;
+    let start = timeout(
+        config.max_poll_duration,
+        with_retries(config, &attempted, || async {
+            let response = api
+                .post("quickExternalScan")
+                .json(&req_body)
+                .send()
+                .await?
+                .error_for_status()?;
+
+            Ok(response.json::<(bool, String)>().await?)
+        }),
+    )
+    .await;
 
-    println!("{:?}", response.text().await.unwrap());
+    let Ok(start) = start else {
+        return Ok(ExternalScanOutcome::TimedOut);
+    };
+    let (started, message) = start?;
 
-    let response = client
-        .post(format!(
-            "https://portaleuwest2.mycybercns.com/api/company/{}/quickExternalScan",
-            company_id,
-        ))
-        .header("customerid", "clay")
-        .header("User-Agent", "ra-v1")
-        .header("Content-Type", "application/json")
-        .basic_auth(&username, Some(&password))
-        .json(&req_body)
-        .send()
-        .await?;
+    println!("{}", message);
 
-    let body = response.json::<(bool, String)>().await;
+    if !started {
+        return Ok(ExternalScanOutcome::Rejected(message));
+    }
 
-    match body {
-        Ok(body) => {
-            println!("{}", body.1);
-
-            if body.0 {
-                loop {
-                    sleep(Duration::from_secs(5)).await;
-
-                    let results = execute_quick_external_scan_results(ExternalScanHostname {
-                        tenant_id: data.tenant_id.clone(),
-                        tenant_name: data.tenant_name.clone(),
-                        host_name: data.host_name.clone(),
-                        company_id: data.company_id.clone(),
-                    })
-                    .await?;
-
-                    if !results.0 {
-                        continue;
-                    } else {
-                        // Create the reports directory if it doesn't exist.
-                        let directory_exists = try_exists("scans").await;
-
-                        match directory_exists {
-                            Ok(directory) => {
-                                if directory {
-                                    println!("directory found");
-                                } else {
-                                    println!("directory not found. creating");
-
-                                    let create_dir_result = create_dir("scans").await;
-
-                                    match create_dir_result {
-                                        Ok(_) => {
-                                            println!("directory created");
-                                        }
-                                        Err(_) => {
-                                            println!("failed to create directory");
-                                        }
-                                    }
-                                }
-                            }
-                            Err(_) => {
-                                println!("unknown error when checking directory exists")
-                            }
-                        }
-
-                        let reports_dir = Path::new("scans");
-
-                        // Create the current date and time variable.
-                        let current_date_time = Local::now();
-
-                        // Create the file name which includes the tenant name and the current date and time.
-                        let file_name = format!(
-                            "scan-{}-{}-{}.json",
-                            data.tenant_name.clone().unwrap(),
-                            data.host_name.clone(),
-                            current_date_time.format("%Y-%m-%d-%H-%M-%S")
-                        );
-
-                        // Create the file path.
-                        let file_path = reports_dir.join(file_name.clone());
-
-                        // Create the file.
-                        let mut file =
-                            File::create(&file_path).expect("Failed to create the file.");
-
-                        // Write the data to the file.
-                        match serde_json::to_writer(
-                            &mut file,
-                            &json!({
-                                "scan_host_name": data,
-                                "scan_results": results.1
-                            }),
-                        ) {
-                            Ok(_) => println!("Generated new scan results: {:?}", file_path),
-                            Err(error) => {
-                                println!("External Scan Results Generation Error: {}", error)
-                            }
-                        }
-
-                        break;
-                    }
-                }
-            }
+    let mut interval = config.poll_interval;
+
+    let results = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        if remaining.is_zero() {
+            return Ok(ExternalScanOutcome::TimedOut);
         }
-        Err(error) => println!("External Scan Error: {}", error),
+
+        sleep(interval.min(remaining)).await;
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let polled = timeout(
+            remaining,
+            with_retries(config, &attempted, || {
+                execute_quick_external_scan_results(&api, &data.host_name)
+            }),
+        )
+        .await;
+
+        let Ok(polled) = polled else {
+            return Ok(ExternalScanOutcome::TimedOut);
+        };
+        let (finished, results) = polled?;
+
+        if finished {
+            break results;
+        }
+
+        interval = config.next_interval(interval);
+    };
+
+    let scan = store_external_scan(
+        pool,
+        data.tenant_id,
+        &data.host_name,
+        data.company_id.as_deref(),
+        &results,
+    )
+    .await?;
+
+    println!("Stored external scan results: {}", scan.id);
+
+    let scan_id = scan.id;
+
+    match record_external_scan_changes(pool, scan).await {
+        Ok(Some(diff)) => println!(
+            "External scan of {} changed: {} opened ports, {} new vulnerabilities, {} new TLS issues, {} resolved.",
+            diff.host_name,
+            diff.opened_ports.len(),
+            diff.new_vulnerabilities.len(),
+            diff.new_tls_issues.len(),
+            diff.resolved_findings.len()
+        ),
+        Ok(None) => {}
+        Err(error) => println!("External Scan Diff Error: {}", error),
     }
 
-    Ok(())
+    Ok(ExternalScanOutcome::Stored(scan_id))
 }
 
+/// Results of a started scan, `false` while they are not ready yet.
 pub async fn execute_quick_external_scan_results(
-    data: ExternalScanHostname,
+    api: &QuickExternalScanApi,
+    host_name: &str,
 ) -> Result<(bool, Value), Error> {
-    dotenv().ok();
-
-    let company_id = data.company_id.clone().unwrap();
-
-    let username = env::var("CYBER_CNS_CLIENT_ID").ok().unwrap();
-    let password = env::var("CYBER_CNS_CLIENT_SECRET").ok().unwrap();
-
-    let client = Client::builder().http1_title_case_headers().build()?;
     let req_body = json!({
-        "hostname": data.host_name
+        "hostname": host_name
     });
 
-    let response = client
-        .post(format!(
-            "https://portaleuwest2.mycybercns.com/api/company/{}/quickExternalScanResults",
-            company_id
-        ))
-        .header("customerid", "clay")
-        .header("User-Agent", "ra-v1")
-        .header("Content-Type", "application/json")
-        .basic_auth(&username, Some(&password))
+    let response = api
+        .post("quickExternalScanResults")
         .json(&req_body)
         .send()
-        .await?;
+        .await?
+        .error_for_status()?;
 
     let body = response.json::<(bool, Value)>().await;
 
@@ -251,3 +290,57 @@ pub async fn execute_quick_external_scan_results(
         )),
     }
 }
+
+/// A CyberCNS company's quick external scan API, resolved before the first request so missing
+/// configuration fails the scan instead of being retried.
+pub struct QuickExternalScanApi {
+    client: Client,
+    company_id: String,
+    username: String,
+    password: String,
+}
+
+impl QuickExternalScanApi {
+    pub fn new(
+        data: &ExternalScanHostname,
+        config: &ExternalScanRunnerConfig,
+    ) -> Result<Self, Error> {
+        dotenv().ok();
+
+        let company_id = data.company_id.clone().ok_or_else(|| {
+            anyhow!(
+                "{} has no CyberCNS company to scan {} for.",
+                data.tenant_name.as_deref().unwrap_or("The tenant"),
+                data.host_name
+            )
+        })?;
+
+        let username = env::var("CYBER_CNS_CLIENT_ID")
+            .map_err(|_| anyhow!("CYBER_CNS_CLIENT_ID is not set."))?;
+        let password = env::var("CYBER_CNS_CLIENT_SECRET")
+            .map_err(|_| anyhow!("CYBER_CNS_CLIENT_SECRET is not set."))?;
+
+        Ok(QuickExternalScanApi {
+            client: Client::builder()
+                .http1_title_case_headers()
+                .connect_timeout(config.request_timeout)
+                .timeout(config.request_timeout)
+                .build()?,
+            company_id,
+            username,
+            password,
+        })
+    }
+
+    fn post(&self, endpoint: &str) -> RequestBuilder {
+        self.client
+            .post(format!(
+                "https://portaleuwest2.mycybercns.com/api/company/{}/{}",
+                self.company_id, endpoint
+            ))
+            .header("customerid", "clay")
+            .header("User-Agent", "ra-v1")
+            .header("Content-Type", "application/json")
+            .basic_auth(&self.username, Some(&self.password))
+    }
+}
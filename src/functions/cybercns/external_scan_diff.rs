@@ -0,0 +1,344 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::external_scan_store::external_scan_findings;
+use crate::models::cybercns::{
+    external_scan::ExternalScan, external_scan_finding::ExternalScanFinding,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExternalScanDiff {
+    pub host_name: String,
+    /// `None` when the host had no earlier scan, everything found is then new.
+    pub from_scan: Option<ExternalScan>,
+    pub to_scan: ExternalScan,
+    pub opened_ports: Vec<ExternalScanFinding>,
+    pub new_vulnerabilities: Vec<ExternalScanFinding>,
+    pub new_tls_issues: Vec<ExternalScanFinding>,
+    /// Findings of the earlier scan no longer present.
+    pub resolved_findings: Vec<ExternalScanFinding>,
+}
+
+/**
+Compare two scans of a host.
+
+`to` picks the last scan on or before that date (the latest scan without it). `from` picks the
+last scan on or before that date, or the first scan after it when there is none, and without it
+the scan before `to` is used. Findings are matched on port and CVE (vulnerabilities), port and
+protocol (open ports) or port and title (TLS issues).
+*/
+pub async fn diff_external_scans(
+    pool: &PgPool,
+    tenant_id: Option<i32>,
+    host_name: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Option<ExternalScanDiff>> {
+    let to_scan = sqlx::query_as!(
+        ExternalScan,
+        r#"
+            SELECT * FROM external_scans
+            WHERE LOWER(host_name) = LOWER($1)
+            AND ($2::INTEGER IS NULL OR tenant_id = $2)
+            AND ($3::DATE IS NULL OR scanned_at < $3::DATE + 1)
+            ORDER BY scanned_at DESC
+            LIMIT 1;
+        "#,
+        host_name,
+        tenant_id,
+        to
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(to_scan) = to_scan else {
+        return Ok(None);
+    };
+
+    let from_scan = match from {
+        Some(from) => {
+            let last_before = sqlx::query_as!(
+                ExternalScan,
+                r#"
+                    SELECT * FROM external_scans
+                    WHERE LOWER(host_name) = LOWER($1)
+                    AND tenant_id = $2
+                    AND scanned_at < $3
+                    AND scanned_at < $4::DATE + 1
+                    ORDER BY scanned_at DESC
+                    LIMIT 1;
+                "#,
+                host_name,
+                to_scan.tenant_id,
+                to_scan.scanned_at,
+                from
+            )
+            .fetch_optional(pool)
+            .await?;
+
+            match last_before {
+                Some(scan) => Some(scan),
+                None => {
+                    sqlx::query_as!(
+                        ExternalScan,
+                        r#"
+                            SELECT * FROM external_scans
+                            WHERE LOWER(host_name) = LOWER($1)
+                            AND tenant_id = $2
+                            AND scanned_at < $3
+                            AND scanned_at >= $4::DATE
+                            ORDER BY scanned_at
+                            LIMIT 1;
+                        "#,
+                        host_name,
+                        to_scan.tenant_id,
+                        to_scan.scanned_at,
+                        from
+                    )
+                    .fetch_optional(pool)
+                    .await?
+                }
+            }
+        }
+        None => previous_external_scan(pool, &to_scan).await?,
+    };
+
+    Ok(Some(diff_scans(pool, from_scan, to_scan).await?))
+}
+
+/**
+Compare a newly stored scan with the previous scan of its host and record the change counts on it.
+
+Returns `None` for the first scan of a host.
+*/
+pub async fn record_external_scan_changes(
+    pool: &PgPool,
+    scan: ExternalScan,
+) -> Result<Option<ExternalScanDiff>> {
+    let Some(previous_scan) = previous_external_scan(pool, &scan).await? else {
+        return Ok(None);
+    };
+
+    let diff = diff_scans(pool, Some(previous_scan), scan).await?;
+
+    sqlx::query!(
+        r#"
+            UPDATE external_scans SET
+                previous_scan_id = $2,
+                opened_ports = $3,
+                new_vulnerabilities = $4,
+                new_tls_issues = $5,
+                resolved_findings = $6
+            WHERE id = $1;
+        "#,
+        diff.to_scan.id,
+        diff.from_scan.as_ref().map(|scan| scan.id),
+        diff.opened_ports.len() as i32,
+        diff.new_vulnerabilities.len() as i32,
+        diff.new_tls_issues.len() as i32,
+        diff.resolved_findings.len() as i32
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(Some(diff))
+}
+
+async fn previous_external_scan(
+    pool: &PgPool,
+    scan: &ExternalScan,
+) -> Result<Option<ExternalScan>> {
+    let previous_scan = sqlx::query_as!(
+        ExternalScan,
+        r#"
+            SELECT * FROM external_scans
+            WHERE LOWER(host_name) = LOWER($1)
+            AND tenant_id = $2
+            AND scanned_at < $3
+            ORDER BY scanned_at DESC
+            LIMIT 1;
+        "#,
+        scan.host_name,
+        scan.tenant_id,
+        scan.scanned_at
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(previous_scan)
+}
+
+/// Findings of a scan that its earlier scan did not have, and the other way around.
+#[derive(Debug, Default, PartialEq)]
+struct ExternalScanComparison {
+    opened_ports: Vec<ExternalScanFinding>,
+    new_vulnerabilities: Vec<ExternalScanFinding>,
+    new_tls_issues: Vec<ExternalScanFinding>,
+    resolved_findings: Vec<ExternalScanFinding>,
+}
+
+async fn diff_scans(
+    pool: &PgPool,
+    from_scan: Option<ExternalScan>,
+    to_scan: ExternalScan,
+) -> Result<ExternalScanDiff> {
+    let from_findings = match &from_scan {
+        Some(from_scan) => external_scan_findings(pool, from_scan.id).await?,
+        None => Vec::new(),
+    };
+    let to_findings = external_scan_findings(pool, to_scan.id).await?;
+
+    let comparison = compare(&from_findings, &to_findings);
+
+    Ok(ExternalScanDiff {
+        host_name: to_scan.host_name.clone(),
+        from_scan,
+        to_scan,
+        opened_ports: comparison.opened_ports,
+        new_vulnerabilities: comparison.new_vulnerabilities,
+        new_tls_issues: comparison.new_tls_issues,
+        resolved_findings: comparison.resolved_findings,
+    })
+}
+
+fn compare(from: &[ExternalScanFinding], to: &[ExternalScanFinding]) -> ExternalScanComparison {
+    let mut comparison = ExternalScanComparison::default();
+
+    for finding in to {
+        if from
+            .iter()
+            .any(|from_finding| finding_key(from_finding) == finding_key(finding))
+        {
+            continue;
+        }
+
+        match finding.kind.as_str() {
+            "open_port" => comparison.opened_ports.push(finding.clone()),
+            "vulnerability" => comparison.new_vulnerabilities.push(finding.clone()),
+            _ => comparison.new_tls_issues.push(finding.clone()),
+        }
+    }
+
+    comparison.resolved_findings = from
+        .iter()
+        .filter(|from_finding| {
+            !to.iter()
+                .any(|finding| finding_key(finding) == finding_key(from_finding))
+        })
+        .cloned()
+        .collect();
+
+    comparison
+}
+
+/// What identifies a finding across scans, ids and severities change between runs.
+fn finding_key(finding: &ExternalScanFinding) -> (&str, Option<i32>, String) {
+    let identity = match finding.kind.as_str() {
+        "vulnerability" => finding.cve.clone().unwrap_or(finding.title.clone()),
+        "open_port" => finding.protocol.clone().unwrap_or("tcp".to_string()),
+        _ => finding.title.to_lowercase(),
+    };
+
+    (finding.kind.as_str(), finding.port, identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(
+        kind: &str,
+        severity: &str,
+        title: &str,
+        port: Option<i32>,
+        cve: Option<&str>,
+    ) -> ExternalScanFinding {
+        ExternalScanFinding {
+            id: 0,
+            scan_id: 0,
+            kind: kind.to_string(),
+            severity: severity.to_string(),
+            title: title.to_string(),
+            port,
+            protocol: None,
+            service: None,
+            cve: cve.map(str::to_string),
+            cvss_score: None,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn reports_what_changed_between_scans() {
+        let https = finding("open_port", "info", "443/tcp open", Some(443), None);
+        let rdp = finding("open_port", "medium", "3389/tcp open", Some(3389), None);
+        let log4shell = finding(
+            "vulnerability",
+            "critical",
+            "Log4Shell",
+            Some(443),
+            Some("CVE-2021-44228"),
+        );
+        let old_cipher = finding("tls", "low", "Weak Cipher Suites", Some(443), None);
+        let expired = finding("tls", "high", "Certificate Expired", Some(443), None);
+        let ssh = finding("open_port", "info", "22/tcp open", Some(22), None);
+
+        let mut rescored = log4shell.clone();
+        rescored.severity = "high".to_string();
+        rescored.id = 7;
+
+        let mut renamed_cipher = old_cipher.clone();
+        renamed_cipher.title = "WEAK CIPHER SUITES".to_string();
+
+        let heartbleed = finding(
+            "vulnerability",
+            "high",
+            "Heartbleed",
+            Some(443),
+            Some("CVE-2014-0160"),
+        );
+
+        let from = vec![https.clone(), ssh.clone(), log4shell, old_cipher];
+        let to = vec![
+            https,
+            rdp.clone(),
+            rescored,
+            heartbleed.clone(),
+            renamed_cipher,
+            expired.clone(),
+        ];
+
+        assert_eq!(
+            compare(&from, &to),
+            ExternalScanComparison {
+                opened_ports: vec![rdp],
+                new_vulnerabilities: vec![heartbleed],
+                new_tls_issues: vec![expired],
+                resolved_findings: vec![ssh],
+            }
+        );
+    }
+
+    #[test]
+    fn treats_everything_as_new_without_an_earlier_scan() {
+        let to = vec![
+            finding("open_port", "info", "443/tcp open", Some(443), None),
+            finding(
+                "vulnerability",
+                "high",
+                "Heartbleed",
+                Some(443),
+                Some("CVE-2014-0160"),
+            ),
+        ];
+
+        let comparison = compare(&[], &to);
+
+        assert_eq!(comparison.opened_ports, to[..1]);
+        assert_eq!(comparison.new_vulnerabilities, to[1..]);
+        assert!(comparison.new_tls_issues.is_empty());
+        assert!(comparison.resolved_findings.is_empty());
+    }
+}
@@ -0,0 +1,555 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Keys holding a port number.
+const PORT_KEYS: [&str; 3] = ["port", "port_number", "portid"];
+const PROTOCOL_KEYS: [&str; 3] = ["protocol", "proto", "transport"];
+const SERVICE_KEYS: [&str; 4] = ["service", "service_name", "product", "name"];
+/// Keys that may hold a CVE id, checked in order.
+const CVE_KEYS: [&str; 7] = [
+    "cve",
+    "cve_id",
+    "cveid",
+    "vul_id",
+    "vulnerability_id",
+    "id",
+    "name",
+];
+const SEVERITY_KEYS: [&str; 4] = ["severity", "base_severity", "risk", "level"];
+const SCORE_KEYS: [&str; 4] = ["cvss_score", "base_score", "cvss", "score"];
+const TITLE_KEYS: [&str; 6] = [
+    "title",
+    "issue",
+    "finding",
+    "name",
+    "summary",
+    "description",
+];
+/// Keys naming a TLS issue, so certificate details such as `name` are not mistaken for one.
+const TLS_ISSUE_KEYS: [&str; 3] = ["issue", "finding", "title"];
+const DETAIL_KEYS: [&str; 5] = ["description", "detail", "details", "solution", "evidence"];
+/// Keys under which everything found is about the TLS configuration.
+const TLS_KEYWORDS: [&str; 4] = ["ssl", "tls", "cert", "cipher"];
+/// TLS flags that are findings when `true`, e.g. `{"ssl": {"expired": true}}`.
+const TLS_PROBLEM_KEYWORDS: [&str; 14] = [
+    "expired",
+    "self_signed",
+    "selfsigned",
+    "weak",
+    "vulnerable",
+    "deprecated",
+    "insecure",
+    "invalid",
+    "mismatch",
+    "untrusted",
+    "revoked",
+    "heartbleed",
+    "poodle",
+    "sweet32",
+];
+
+/// A finding read from CyberCNS external scan results, before it is stored.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ParsedExternalScanFinding {
+    pub kind: String,
+    pub severity: String,
+    pub title: String,
+    pub port: Option<i32>,
+    pub protocol: Option<String>,
+    pub service: Option<String>,
+    pub cve: Option<String>,
+    pub cvss_score: Option<f64>,
+    pub detail: Option<String>,
+}
+
+/**
+Normalise CyberCNS quick external scan results into findings.
+
+The results are not documented and differ between scans, so the JSON is walked as a whole instead of
+deserialized into a fixed shape:
+- objects with a CVE id are vulnerabilities, with the port of the enclosing object if they have none
+- other objects with a port are open ports
+- objects with an issue or severity, and `true` problem flags such as `expired`, found under an
+  `ssl`/`tls`/`cert`/`cipher` key are TLS issues
+
+Severity is taken from the finding, or its CVSS score, and normalised to `critical`, `high`,
+`medium`, `low` or `info`.
+*/
+pub fn parse_external_scan_findings(results: &Value) -> Vec<ParsedExternalScanFinding> {
+    let mut findings: Vec<ParsedExternalScanFinding> = Vec::new();
+
+    walk(results, None, false, &mut findings);
+
+    let mut unique: Vec<ParsedExternalScanFinding> = Vec::new();
+
+    for finding in findings {
+        match unique
+            .iter_mut()
+            .find(|existing| is_same_finding(existing, &finding))
+        {
+            Some(existing) => merge_finding(existing, finding),
+            None => unique.push(finding),
+        }
+    }
+
+    unique
+}
+
+/// The same CVE is often listed both under a port and in a summary without one.
+fn is_same_finding(
+    existing: &ParsedExternalScanFinding,
+    finding: &ParsedExternalScanFinding,
+) -> bool {
+    if existing.kind != finding.kind {
+        return false;
+    }
+
+    match (&existing.cve, &finding.cve) {
+        (Some(existing_cve), Some(cve)) => {
+            existing_cve == cve
+                && (existing.port == finding.port
+                    || existing.port.is_none()
+                    || finding.port.is_none())
+        }
+        _ => existing.port == finding.port && existing.title == finding.title,
+    }
+}
+
+fn merge_finding(existing: &mut ParsedExternalScanFinding, finding: ParsedExternalScanFinding) {
+    if severity_rank(&finding.severity) < severity_rank(&existing.severity) {
+        existing.severity = finding.severity;
+    }
+
+    if existing.cve.as_ref() == Some(&existing.title)
+        && finding.cve.as_ref() != Some(&finding.title)
+    {
+        existing.title = finding.title;
+    }
+
+    existing.port = existing.port.or(finding.port);
+    existing.protocol = existing.protocol.take().or(finding.protocol);
+    existing.service = existing.service.take().or(finding.service);
+    existing.cvss_score = existing.cvss_score.or(finding.cvss_score);
+    existing.detail = existing.detail.take().or(finding.detail);
+}
+
+fn walk(
+    value: &Value,
+    port: Option<i32>,
+    in_tls: bool,
+    findings: &mut Vec<ParsedExternalScanFinding>,
+) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                match item.as_str().map(str::trim) {
+                    // Bare ids, as in `"cves": ["CVE-2021-44228"]`.
+                    Some(text) if is_cve_id(text) => findings.push(ParsedExternalScanFinding {
+                        kind: "vulnerability".to_string(),
+                        severity: "info".to_string(),
+                        title: text.to_uppercase(),
+                        port,
+                        protocol: None,
+                        service: None,
+                        cve: Some(text.to_uppercase()),
+                        cvss_score: None,
+                        detail: None,
+                    }),
+                    _ => walk(item, port, in_tls, findings),
+                }
+            }
+        }
+        Value::Object(object) => visit_object(object, port, in_tls, findings),
+        _ => {}
+    }
+}
+
+fn visit_object(
+    object: &Map<String, Value>,
+    port: Option<i32>,
+    in_tls: bool,
+    findings: &mut Vec<ParsedExternalScanFinding>,
+) {
+    let own_port = PORT_KEYS
+        .iter()
+        .find_map(|key| number_field(object, key))
+        .filter(|port| (1.0..=65535.0).contains(port))
+        .map(|port| port as i32);
+    let port = own_port.or(port);
+
+    let cve = CVE_KEYS
+        .iter()
+        .filter_map(|key| string_field(object, key))
+        .find(|value| is_cve_id(value))
+        .map(|value| value.to_uppercase());
+
+    // A `score` outside the CVSS range is something else, such as a TLS grade out of 100.
+    let cvss_score = SCORE_KEYS
+        .iter()
+        .find_map(|key| number_field(object, key))
+        .filter(|score| (0.0..=10.0).contains(score));
+    let severity = SEVERITY_KEYS
+        .iter()
+        .filter_map(|key| string_field(object, key))
+        .find_map(normalise_severity)
+        .or_else(|| cvss_score.map(severity_from_score));
+    let title = TITLE_KEYS
+        .iter()
+        .filter_map(|key| string_field(object, key))
+        .find(|title| !is_cve_id(title));
+    let detail = DETAIL_KEYS
+        .iter()
+        .filter_map(|key| string_field(object, key))
+        .find(|detail| Some(*detail) != title);
+    let protocol = PROTOCOL_KEYS
+        .iter()
+        .find_map(|key| string_field(object, key))
+        .map(|protocol| protocol.to_lowercase());
+
+    if let Some(cve) = cve {
+        findings.push(ParsedExternalScanFinding {
+            kind: "vulnerability".to_string(),
+            severity: severity.unwrap_or("info").to_string(),
+            title: title.unwrap_or(&cve).to_string(),
+            port,
+            protocol,
+            service: None,
+            cve: Some(cve),
+            cvss_score,
+            detail: detail.map(str::to_string),
+        });
+    } else if let Some(own_port) = own_port {
+        let service = SERVICE_KEYS
+            .iter()
+            .find_map(|key| string_field(object, key))
+            .map(str::to_string);
+
+        findings.push(ParsedExternalScanFinding {
+            kind: "open_port".to_string(),
+            severity: severity.unwrap_or("info").to_string(),
+            title: format!(
+                "Open port {}/{}",
+                own_port,
+                protocol.as_deref().unwrap_or("tcp")
+            ),
+            port: Some(own_port),
+            protocol,
+            service,
+            cve: None,
+            cvss_score,
+            detail: detail.map(str::to_string),
+        });
+    } else if in_tls {
+        let issue = TLS_ISSUE_KEYS
+            .iter()
+            .find_map(|key| string_field(object, key));
+
+        if let Some(title) = issue.or(title.filter(|_| severity.is_some())) {
+            findings.push(tls_finding(
+                title,
+                severity.unwrap_or("medium"),
+                port,
+                detail.map(str::to_string),
+            ));
+        }
+    }
+
+    for (key, child) in object {
+        let lowercase = key.to_lowercase();
+        let child_in_tls = in_tls
+            || TLS_KEYWORDS
+                .iter()
+                .any(|keyword| lowercase.contains(keyword));
+
+        if child_in_tls
+            && *child == Value::Bool(true)
+            && TLS_PROBLEM_KEYWORDS
+                .iter()
+                .any(|keyword| lowercase.contains(keyword))
+        {
+            findings.push(tls_finding(&humanise_key(key), "medium", port, None));
+        }
+
+        walk(child, port, child_in_tls, findings);
+    }
+}
+
+fn tls_finding(
+    title: &str,
+    severity: &str,
+    port: Option<i32>,
+    detail: Option<String>,
+) -> ParsedExternalScanFinding {
+    ParsedExternalScanFinding {
+        kind: "tls".to_string(),
+        severity: severity.to_string(),
+        title: title.to_string(),
+        port,
+        protocol: None,
+        service: None,
+        cve: None,
+        cvss_score: None,
+        detail,
+    }
+}
+
+fn string_field<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+    object
+        .iter()
+        .find(|(field, _)| field.eq_ignore_ascii_case(key))
+        .and_then(|(_, value)| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn number_field(object: &Map<String, Value>, key: &str) -> Option<f64> {
+    object
+        .iter()
+        .find(|(field, _)| field.eq_ignore_ascii_case(key))
+        .and_then(|(_, value)| match value {
+            Value::Number(number) => number.as_f64(),
+            Value::String(text) => text.trim().parse::<f64>().ok(),
+            _ => None,
+        })
+}
+
+/// `CVE-2021-44228` style ids, case insensitive.
+fn is_cve_id(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+
+    parts.len() == 3
+        && parts[0].eq_ignore_ascii_case("cve")
+        && parts[1].len() == 4
+        && parts[2].len() >= 4
+        && parts[1..]
+            .iter()
+            .all(|part| part.chars().all(|character| character.is_ascii_digit()))
+}
+
+fn normalise_severity(severity: &str) -> Option<&'static str> {
+    match severity.to_lowercase().as_str() {
+        "critical" | "urgent" => Some("critical"),
+        "high" | "important" => Some("high"),
+        "medium" | "moderate" => Some("medium"),
+        "low" => Some("low"),
+        "info" | "informational" | "information" | "none" => Some("info"),
+        _ => None,
+    }
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "critical" => 0,
+        "high" => 1,
+        "medium" => 2,
+        "low" => 3,
+        _ => 4,
+    }
+}
+
+/// CVSS v3 qualitative severity rating.
+fn severity_from_score(score: f64) -> &'static str {
+    match score {
+        score if score >= 9.0 => "critical",
+        score if score >= 7.0 => "high",
+        score if score >= 4.0 => "medium",
+        score if score > 0.0 => "low",
+        _ => "info",
+    }
+}
+
+/// `self_signed` becomes `Self signed`.
+fn humanise_key(key: &str) -> String {
+    let words = key.replace(['_', '-'], " ");
+    let mut characters = words.trim().chars();
+
+    match characters.next() {
+        Some(first) => first.to_uppercase().chain(characters).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn finding<'a>(
+        findings: &'a [ParsedExternalScanFinding],
+        kind: &str,
+        title: &str,
+    ) -> &'a ParsedExternalScanFinding {
+        findings
+            .iter()
+            .find(|finding| finding.kind == kind && finding.title == title)
+            .unwrap_or_else(|| panic!("no {} finding {} in {:#?}", kind, title, findings))
+    }
+
+    /// Shape of a quick external scan result: ports with their service and CVEs, TLS details and a
+    /// summary repeating the CVEs.
+    fn scan_results() -> Value {
+        json!({
+            "hostname": "www.example.com",
+            "ip": "203.0.113.10",
+            "ports": [
+                {
+                    "port": 443,
+                    "protocol": "TCP",
+                    "name": "https",
+                    "product": "nginx",
+                    "version": "1.18.0",
+                    "vulnerabilities": [
+                        {
+                            "name": "CVE-2021-23017",
+                            "title": "nginx resolver off-by-one",
+                            "score": 7.7,
+                            "description": "A security issue in nginx resolver."
+                        },
+                        { "cve": "cve-2019-20372", "score": "5.3" }
+                    ]
+                },
+                { "port": "22", "protocol": "tcp", "service": "ssh", "product": "OpenSSH" },
+                { "port": 70000, "name": "not a port" }
+            ],
+            "ssl": {
+                "port": 443,
+                "grade": { "name": "B", "score": 85 },
+                "certificate": { "name": "www.example.com", "expired": false, "self_signed": true },
+                "issues": [
+                    { "issue": "TLS 1.0 enabled", "severity": "Moderate" },
+                    { "name": "ECDHE-RSA-AES128-SHA", "strength": "strong" }
+                ]
+            },
+            "summary": {
+                "cves": ["CVE-2021-23017", "CVE-2022-41741"],
+                "vulnerabilities": [
+                    { "vul_id": "CVE-2021-23017", "severity": "HIGH" }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn reads_open_ports_with_their_service() {
+        let findings = parse_external_scan_findings(&scan_results());
+        let ports: Vec<&ParsedExternalScanFinding> = findings
+            .iter()
+            .filter(|finding| finding.kind == "open_port")
+            .collect();
+
+        assert_eq!(ports.len(), 2);
+
+        let https = finding(&findings, "open_port", "Open port 443/tcp");
+
+        assert_eq!(https.port, Some(443));
+        assert_eq!(https.protocol.as_deref(), Some("tcp"));
+        assert_eq!(https.service.as_deref(), Some("nginx"));
+        assert_eq!(https.severity, "info");
+
+        let ssh = finding(&findings, "open_port", "Open port 22/tcp");
+
+        assert_eq!(ssh.service.as_deref(), Some("ssh"));
+    }
+
+    #[test]
+    fn reads_cves_under_ports_and_merges_the_summary() {
+        let findings = parse_external_scan_findings(&scan_results());
+        let vulnerabilities: Vec<&ParsedExternalScanFinding> = findings
+            .iter()
+            .filter(|finding| finding.kind == "vulnerability")
+            .collect();
+
+        assert_eq!(vulnerabilities.len(), 3, "{:#?}", vulnerabilities);
+
+        let resolver = finding(&findings, "vulnerability", "nginx resolver off-by-one");
+
+        assert_eq!(resolver.cve.as_deref(), Some("CVE-2021-23017"));
+        assert_eq!(resolver.port, Some(443));
+        assert_eq!(resolver.cvss_score, Some(7.7));
+        assert_eq!(resolver.severity, "high");
+        assert_eq!(
+            resolver.detail.as_deref(),
+            Some("A security issue in nginx resolver.")
+        );
+
+        let null_pointer = finding(&findings, "vulnerability", "CVE-2019-20372");
+
+        assert_eq!(null_pointer.port, Some(443));
+        assert_eq!(null_pointer.severity, "medium");
+
+        let summary_only = finding(&findings, "vulnerability", "CVE-2022-41741");
+
+        assert_eq!(summary_only.port, None);
+        assert_eq!(summary_only.severity, "info");
+    }
+
+    #[test]
+    fn reads_tls_issues_and_problem_flags() {
+        let findings = parse_external_scan_findings(&scan_results());
+        let tls: Vec<&ParsedExternalScanFinding> = findings
+            .iter()
+            .filter(|finding| finding.kind == "tls")
+            .collect();
+
+        assert_eq!(tls.len(), 2, "{:#?}", tls);
+
+        let protocol = finding(&findings, "tls", "TLS 1.0 enabled");
+
+        assert_eq!(protocol.severity, "medium");
+        assert_eq!(protocol.port, Some(443));
+
+        let self_signed = finding(&findings, "tls", "Self signed");
+
+        assert_eq!(self_signed.port, Some(443));
+    }
+
+    #[test]
+    fn ignores_scores_outside_the_cvss_range() {
+        let findings =
+            parse_external_scan_findings(&json!({ "ports": [{ "port": 80, "score": 85 }] }));
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].cvss_score, None);
+        assert_eq!(findings[0].severity, "info");
+    }
+
+    #[test]
+    fn de_duplicates_repeated_findings() {
+        let results = json!([
+            { "port": 8443, "vulnerabilities": [{ "cve": "CVE-2023-44487", "severity": "medium" }] },
+            { "port": 8443, "vulnerabilities": [{ "cve": "CVE-2023-44487", "severity": "high", "title": "HTTP/2 Rapid Reset" }] },
+            { "port": 9443, "vulnerabilities": [{ "cve": "CVE-2023-44487" }] },
+            { "port": 8443 },
+            { "tls": { "expired": true } },
+            { "tls": { "expired": true } }
+        ]);
+
+        let findings = parse_external_scan_findings(&results);
+        let rapid_reset: Vec<&ParsedExternalScanFinding> = findings
+            .iter()
+            .filter(|finding| finding.cve.as_deref() == Some("CVE-2023-44487"))
+            .collect();
+
+        assert_eq!(rapid_reset.len(), 2, "{:#?}", rapid_reset);
+        assert_eq!(rapid_reset[0].port, Some(8443));
+        assert_eq!(rapid_reset[0].severity, "high");
+        assert_eq!(rapid_reset[0].title, "HTTP/2 Rapid Reset");
+        assert_eq!(rapid_reset[1].port, Some(9443));
+
+        assert_eq!(
+            findings
+                .iter()
+                .filter(|finding| finding.kind == "open_port")
+                .count(),
+            2
+        );
+        assert_eq!(
+            findings
+                .iter()
+                .filter(|finding| finding.kind == "tls")
+                .count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,653 @@
+use std::{
+    env,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    time::Duration,
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use dotenv::dotenv;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tokio::{sync::Semaphore, time::sleep};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    external_scan::{execute_quick_external_scan, ExternalScanHostname, ExternalScanOutcome},
+    external_scan_target::validate_external_scan_target,
+};
+
+static EXTERNAL_SCAN_RUNNER: OnceLock<ExternalScanRunner> = OnceLock::new();
+
+/// Finished runs kept for the runs endpoint, the oldest are dropped first.
+const MAX_FINISHED_EXTERNAL_SCAN_RUNS: usize = 500;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalScanStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    TimedOut,
+    Cancelled,
+}
+
+impl ExternalScanStatus {
+    pub fn is_finished(self) -> bool {
+        !matches!(
+            self,
+            ExternalScanStatus::Queued | ExternalScanStatus::Running
+        )
+    }
+}
+
+/// A scan of one hostname, from being queued until it finished.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ExternalScanRun {
+    pub id: u64,
+    pub tenant_id: i32,
+    pub tenant_name: Option<String>,
+    pub host_name: String,
+    pub status: ExternalScanStatus,
+    /// CyberCNS requests made, including retries.
+    pub attempts: u32,
+    pub queued_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// The stored scan once succeeded.
+    pub scan_id: Option<i32>,
+    pub error: Option<String>,
+}
+
+/**
+Limits of the external scan runner, read from the environment once.
+
+- `EXTERNAL_SCAN_CONCURRENCY`: hostnames scanned at the same time (4)
+- `EXTERNAL_SCAN_MAX_POLL_MINUTES`: how long results are waited for before the scan times out (30)
+- `EXTERNAL_SCAN_POLL_INTERVAL_SECONDS`: first wait between result polls, doubled after each (5)
+- `EXTERNAL_SCAN_MAX_POLL_INTERVAL_SECONDS`: longest wait between result polls (60)
+- `EXTERNAL_SCAN_MAX_ATTEMPTS`: tries of a failing CyberCNS request before the scan fails (3)
+- `EXTERNAL_SCAN_REQUEST_TIMEOUT_SECONDS`: how long a single CyberCNS request may take (60)
+*/
+#[derive(Debug, Clone)]
+pub struct ExternalScanRunnerConfig {
+    pub concurrency: usize,
+    pub max_poll_duration: Duration,
+    pub poll_interval: Duration,
+    pub max_poll_interval: Duration,
+    pub max_attempts: u32,
+    pub request_timeout: Duration,
+}
+
+impl ExternalScanRunnerConfig {
+    pub fn from_env() -> Self {
+        dotenv().ok();
+
+        ExternalScanRunnerConfig {
+            concurrency: env_number("EXTERNAL_SCAN_CONCURRENCY", 4) as usize,
+            max_poll_duration: Duration::from_secs(
+                env_number("EXTERNAL_SCAN_MAX_POLL_MINUTES", 30) * 60,
+            ),
+            poll_interval: Duration::from_secs(env_number(
+                "EXTERNAL_SCAN_POLL_INTERVAL_SECONDS",
+                5,
+            )),
+            max_poll_interval: Duration::from_secs(env_number(
+                "EXTERNAL_SCAN_MAX_POLL_INTERVAL_SECONDS",
+                60,
+            )),
+            max_attempts: env_number("EXTERNAL_SCAN_MAX_ATTEMPTS", 3) as u32,
+            request_timeout: Duration::from_secs(env_number(
+                "EXTERNAL_SCAN_REQUEST_TIMEOUT_SECONDS",
+                60,
+            )),
+        }
+    }
+
+    /// Wait before the poll or retry following one that waited `interval`.
+    pub fn next_interval(&self, interval: Duration) -> Duration {
+        (interval * 2).min(self.max_poll_interval.max(self.poll_interval))
+    }
+}
+
+/// A positive number from the environment, `default` when unset or invalid.
+fn env_number(name: &str, default: u64) -> u64 {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(default)
+}
+
+struct ExternalScanRunEntry {
+    run: ExternalScanRun,
+    cancellation: CancellationToken,
+}
+
+/**
+Runs external scans in the background, at most `concurrency` at a time.
+
+Runs are kept in memory so in-flight scans can be listed and cancelled, they do not survive a
+restart. Scans that succeeded are stored in `external_scans`.
+*/
+pub struct ExternalScanRunner {
+    config: ExternalScanRunnerConfig,
+    semaphore: Semaphore,
+    next_id: AtomicU64,
+    runs: Mutex<Vec<ExternalScanRunEntry>>,
+}
+
+pub fn external_scan_runner() -> &'static ExternalScanRunner {
+    EXTERNAL_SCAN_RUNNER
+        .get_or_init(|| ExternalScanRunner::new(ExternalScanRunnerConfig::from_env()))
+}
+
+impl ExternalScanRunner {
+    fn new(config: ExternalScanRunnerConfig) -> Self {
+        ExternalScanRunner {
+            semaphore: Semaphore::new(config.concurrency),
+            config,
+            next_id: AtomicU64::new(1),
+            runs: Mutex::new(Vec::new()),
+        }
+    }
+
+    /**
+    Queue a scan of a hostname.
+
+    The hostname is validated first, one that no longer resolves or now points at a private address
+    is not queued and the reason is returned. A hostname already queued or running for the tenant is
+    not scanned twice, its run is returned instead.
+    */
+    pub async fn queue(
+        &'static self,
+        pool: &PgPool,
+        hostname: ExternalScanHostname,
+    ) -> Result<ExternalScanRun, String> {
+        validate_external_scan_target(&hostname.host_name).await?;
+
+        let pool = pool.clone();
+        let scanned = hostname.clone();
+
+        Ok(self.enqueue(hostname, move |id| async move {
+            execute_quick_external_scan(&pool, &scanned, &self.config, || {
+                self.update(id, |run| run.attempts += 1)
+            })
+            .await
+        }))
+    }
+
+    /// Queue `scan`, called with the run id, unless the hostname is already queued or running.
+    fn enqueue<S, Fut>(&'static self, hostname: ExternalScanHostname, scan: S) -> ExternalScanRun
+    where
+        S: FnOnce(u64) -> Fut,
+        Fut: Future<Output = Result<ExternalScanOutcome>> + Send + 'static,
+    {
+        let mut runs = self.runs.lock().unwrap();
+
+        if let Some(entry) = runs.iter().find(|entry| {
+            !entry.run.status.is_finished()
+                && entry.run.tenant_id == hostname.tenant_id
+                && entry
+                    .run
+                    .host_name
+                    .eq_ignore_ascii_case(&hostname.host_name)
+        }) {
+            return entry.run.clone();
+        }
+
+        let run = ExternalScanRun {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            tenant_id: hostname.tenant_id,
+            tenant_name: hostname.tenant_name,
+            host_name: hostname.host_name,
+            status: ExternalScanStatus::Queued,
+            attempts: 0,
+            queued_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            scan_id: None,
+            error: None,
+        };
+        let cancellation = CancellationToken::new();
+
+        runs.push(ExternalScanRunEntry {
+            run: run.clone(),
+            cancellation: cancellation.clone(),
+        });
+        prune_finished_runs(&mut runs);
+
+        tokio::spawn(self.run(run.id, run.host_name.clone(), cancellation, scan(run.id)));
+
+        run
+    }
+
+    /// Runs, most recently queued first.
+    pub fn runs(&self) -> Vec<ExternalScanRun> {
+        self.runs
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|entry| entry.run.clone())
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<ExternalScanRun> {
+        self.runs
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|entry| entry.run.id == id)
+            .map(|entry| entry.run.clone())
+    }
+
+    /**
+    Cancel a queued or running scan.
+
+    Returns `None` for an unknown run and the run unchanged when it already finished.
+    */
+    pub fn cancel(&self, id: u64) -> Option<ExternalScanRun> {
+        let mut runs = self.runs.lock().unwrap();
+        let entry = runs.iter_mut().find(|entry| entry.run.id == id)?;
+
+        if !entry.run.status.is_finished() {
+            entry.cancellation.cancel();
+            finish(&mut entry.run, ExternalScanStatus::Cancelled, None);
+        }
+
+        Some(entry.run.clone())
+    }
+
+    async fn run(
+        &'static self,
+        id: u64,
+        host_name: String,
+        cancellation: CancellationToken,
+        scan: impl Future<Output = Result<ExternalScanOutcome>>,
+    ) {
+        let _permit = tokio::select! {
+            _ = cancellation.cancelled() => return,
+            permit = self.semaphore.acquire() => match permit {
+                Ok(permit) => permit,
+                Err(error) => {
+                    self.finish(id, ExternalScanStatus::Failed, Some(error.to_string()));
+                    return;
+                }
+            },
+        };
+
+        self.update(id, |run| {
+            run.status = ExternalScanStatus::Running;
+            run.started_at = Some(Utc::now());
+        });
+
+        let outcome = tokio::select! {
+            _ = cancellation.cancelled() => return,
+            outcome = scan => outcome,
+        };
+
+        match outcome {
+            Ok(ExternalScanOutcome::Stored(scan_id)) => self.update(id, |run| {
+                run.scan_id = Some(scan_id);
+                finish(run, ExternalScanStatus::Succeeded, None);
+            }),
+            Ok(ExternalScanOutcome::Rejected(message)) => {
+                self.finish(id, ExternalScanStatus::Failed, Some(message))
+            }
+            Ok(ExternalScanOutcome::TimedOut) => self.finish(
+                id,
+                ExternalScanStatus::TimedOut,
+                Some(format!(
+                    "No results after {} minutes.",
+                    self.config.max_poll_duration.as_secs() / 60
+                )),
+            ),
+            Err(error) => {
+                println!("External Scan Error for {}: {}", host_name, error);
+                self.finish(id, ExternalScanStatus::Failed, Some(error.to_string()))
+            }
+        }
+    }
+
+    fn finish(&self, id: u64, status: ExternalScanStatus, error: Option<String>) {
+        self.update(id, |run| finish(run, status, error));
+    }
+
+    /// Update a run unless it was cancelled in the meantime.
+    fn update(&self, id: u64, change: impl FnOnce(&mut ExternalScanRun)) {
+        let mut runs = self.runs.lock().unwrap();
+
+        if let Some(entry) = runs.iter_mut().find(|entry| entry.run.id == id) {
+            if !entry.run.status.is_finished() {
+                change(&mut entry.run);
+            }
+        }
+    }
+}
+
+fn finish(run: &mut ExternalScanRun, status: ExternalScanStatus, error: Option<String>) {
+    run.status = status;
+    run.finished_at = Some(Utc::now());
+    run.error = error;
+}
+
+fn prune_finished_runs(runs: &mut Vec<ExternalScanRunEntry>) {
+    let finished = runs
+        .iter()
+        .filter(|entry| entry.run.status.is_finished())
+        .count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED_EXTERNAL_SCAN_RUNS);
+
+    runs.retain(|entry| {
+        if excess > 0 && entry.run.status.is_finished() {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+/**
+Make a CyberCNS request, retrying failures with exponential backoff.
+
+`attempted` is called before every try. The last error is returned once `max_attempts` tries failed.
+*/
+pub async fn with_retries<T, F, Fut>(
+    config: &ExternalScanRunnerConfig,
+    attempted: &impl Fn(),
+    mut request: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut interval = config.poll_interval;
+    let mut attempt = 1;
+
+    loop {
+        attempted();
+
+        match request().await {
+            Ok(value) => return Ok(value),
+            Err(error) if attempt >= config.max_attempts => return Err(error),
+            Err(error) => {
+                println!(
+                    "External Scan Request Failed (attempt {} of {}), retrying in {}s: {}",
+                    attempt,
+                    config.max_attempts,
+                    interval.as_secs(),
+                    error
+                );
+            }
+        }
+
+        sleep(interval).await;
+        interval = config.next_interval(interval);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::AtomicU32, Arc};
+
+    use anyhow::anyhow;
+    use tokio::{sync::oneshot, task::yield_now, time::Instant};
+
+    use super::*;
+
+    fn config(concurrency: usize) -> ExternalScanRunnerConfig {
+        ExternalScanRunnerConfig {
+            concurrency,
+            max_poll_duration: Duration::from_secs(30 * 60),
+            poll_interval: Duration::from_secs(5),
+            max_poll_interval: Duration::from_secs(60),
+            max_attempts: 3,
+            request_timeout: Duration::from_secs(60),
+        }
+    }
+
+    fn runner(concurrency: usize) -> &'static ExternalScanRunner {
+        Box::leak(Box::new(ExternalScanRunner::new(config(concurrency))))
+    }
+
+    fn hostname(tenant_id: i32, host_name: &str) -> ExternalScanHostname {
+        ExternalScanHostname {
+            tenant_id,
+            tenant_name: None,
+            host_name: host_name.to_string(),
+            company_id: None,
+        }
+    }
+
+    /// A scan that counts its start and stores scan 7 once `release` fires.
+    async fn blocked_scan(
+        release: oneshot::Receiver<()>,
+        started: Arc<AtomicU32>,
+    ) -> Result<ExternalScanOutcome> {
+        started.fetch_add(1, Ordering::SeqCst);
+        release.await?;
+
+        Ok(ExternalScanOutcome::Stored(7))
+    }
+
+    /// Let the spawned runs make progress.
+    async fn settle() {
+        for _ in 0..20 {
+            yield_now().await;
+        }
+    }
+
+    fn status(runner: &ExternalScanRunner, id: u64) -> ExternalScanStatus {
+        runner.get(id).unwrap().status
+    }
+
+    #[tokio::test]
+    async fn runs_at_most_the_configured_scans_and_each_hostname_once() {
+        let runner = runner(1);
+        let started = Arc::new(AtomicU32::new(0));
+        let (release_a, a_released) = oneshot::channel();
+        let (release_b, b_released) = oneshot::channel();
+
+        let a = runner.enqueue(hostname(1, "a.example.com"), |_| {
+            blocked_scan(a_released, started.clone())
+        });
+        let b = runner.enqueue(hostname(1, "b.example.com"), |_| {
+            blocked_scan(b_released, started.clone())
+        });
+        let duplicate = runner.enqueue(hostname(1, "A.EXAMPLE.COM"), |_| {
+            blocked_scan(oneshot::channel().1, started.clone())
+        });
+        let other_tenant = runner.enqueue(hostname(2, "a.example.com"), |_| async {
+            Ok(ExternalScanOutcome::Rejected("Quota exceeded.".to_string()))
+        });
+
+        assert_eq!(duplicate.id, a.id);
+        assert_ne!(other_tenant.id, a.id);
+        assert_eq!(runner.runs().len(), 3);
+
+        settle().await;
+
+        assert_eq!(status(runner, a.id), ExternalScanStatus::Running);
+        assert_eq!(status(runner, b.id), ExternalScanStatus::Queued);
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+
+        release_a.send(()).unwrap();
+        settle().await;
+
+        let a = runner.get(a.id).unwrap();
+
+        assert_eq!(a.status, ExternalScanStatus::Succeeded);
+        assert_eq!(a.scan_id, Some(7));
+        assert!(a.finished_at.is_some());
+        assert_eq!(status(runner, b.id), ExternalScanStatus::Running);
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+
+        release_b.send(()).unwrap();
+        settle().await;
+
+        assert_eq!(status(runner, b.id), ExternalScanStatus::Succeeded);
+
+        let other_tenant = runner.get(other_tenant.id).unwrap();
+
+        assert_eq!(other_tenant.status, ExternalScanStatus::Failed);
+        assert_eq!(other_tenant.error.as_deref(), Some("Quota exceeded."));
+
+        // Only in-flight scans are de-duplicated.
+        let again = runner.enqueue(hostname(1, "a.example.com"), |_| async {
+            Ok(ExternalScanOutcome::TimedOut)
+        });
+
+        assert_ne!(again.id, a.id);
+    }
+
+    #[tokio::test]
+    async fn cancels_queued_and_running_scans() {
+        let runner = runner(1);
+        let started = Arc::new(AtomicU32::new(0));
+        let (release_running, running_released) = oneshot::channel();
+
+        let running = runner.enqueue(hostname(1, "a.example.com"), |_| {
+            blocked_scan(running_released, started.clone())
+        });
+        let queued = runner.enqueue(hostname(1, "b.example.com"), |_| {
+            blocked_scan(oneshot::channel().1, started.clone())
+        });
+
+        settle().await;
+
+        assert_eq!(
+            runner.cancel(queued.id).unwrap().status,
+            ExternalScanStatus::Cancelled
+        );
+        assert_eq!(
+            runner.cancel(running.id).unwrap().status,
+            ExternalScanStatus::Cancelled
+        );
+
+        settle().await;
+
+        // The running scan was dropped and the queued one never started.
+        assert!(release_running.send(()).is_err());
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+        assert_eq!(status(runner, running.id), ExternalScanStatus::Cancelled);
+        assert_eq!(status(runner, queued.id), ExternalScanStatus::Cancelled);
+        assert!(runner.cancel(u64::MAX).is_none());
+
+        // The cancelled scan gave its permit back.
+        let next = runner.enqueue(hostname(1, "c.example.com"), |_| async {
+            Ok(ExternalScanOutcome::Stored(8))
+        });
+
+        settle().await;
+
+        assert_eq!(status(runner, next.id), ExternalScanStatus::Succeeded);
+        assert_eq!(
+            runner.cancel(next.id).unwrap().status,
+            ExternalScanStatus::Succeeded
+        );
+    }
+
+    #[test]
+    fn prunes_the_oldest_finished_runs() {
+        let entry = |id: u64, status: ExternalScanStatus| ExternalScanRunEntry {
+            run: ExternalScanRun {
+                id,
+                tenant_id: 1,
+                tenant_name: None,
+                host_name: format!("{}.example.com", id),
+                status,
+                attempts: 0,
+                queued_at: Utc::now(),
+                started_at: None,
+                finished_at: None,
+                scan_id: None,
+                error: None,
+            },
+            cancellation: CancellationToken::new(),
+        };
+
+        let mut runs = vec![entry(0, ExternalScanStatus::Running)];
+        let finished = MAX_FINISHED_EXTERNAL_SCAN_RUNS as u64 + 2;
+
+        runs.extend((1..=finished).map(|id| entry(id, ExternalScanStatus::Succeeded)));
+        runs.push(entry(finished + 1, ExternalScanStatus::Queued));
+
+        prune_finished_runs(&mut runs);
+
+        let ids: Vec<u64> = runs.iter().map(|entry| entry.run.id).collect();
+
+        assert_eq!(ids.len(), MAX_FINISHED_EXTERNAL_SCAN_RUNS + 2);
+        assert_eq!(ids[..2], [0, 3]);
+        assert_eq!(ids.last(), Some(&(finished + 1)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_with_backoff_until_max_attempts() {
+        let config = config(1);
+        let attempts = AtomicU32::new(0);
+        let attempted = || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+        };
+        let started = Instant::now();
+
+        let result: Result<()> = with_retries(&config, &attempted, || async {
+            Err(anyhow!("CyberCNS is unavailable."))
+        })
+        .await;
+
+        assert_eq!(result.unwrap_err().to_string(), "CyberCNS is unavailable.");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(started.elapsed(), Duration::from_secs(5 + 10));
+
+        let failures = AtomicU32::new(1);
+        let started = Instant::now();
+
+        let result = with_retries(&config, &attempted, || async {
+            match failures.fetch_sub(1, Ordering::SeqCst) {
+                0 => Ok("results"),
+                _ => Err(anyhow!("Bad gateway.")),
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "results");
+        assert_eq!(attempts.load(Ordering::SeqCst), 5);
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn doubles_the_interval_up_to_the_maximum() {
+        let config = config(1);
+
+        assert_eq!(
+            config.next_interval(Duration::from_secs(5)),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            config.next_interval(Duration::from_secs(40)),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            config.next_interval(Duration::from_secs(60)),
+            Duration::from_secs(60)
+        );
+
+        let config = ExternalScanRunnerConfig {
+            max_poll_interval: Duration::from_secs(2),
+            ..config
+        };
+
+        assert_eq!(
+            config.next_interval(Duration::from_secs(5)),
+            Duration::from_secs(5)
+        );
+    }
+}
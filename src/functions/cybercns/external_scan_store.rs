@@ -0,0 +1,178 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+
+use super::external_scan_findings::parse_external_scan_findings;
+use crate::{
+    functions::storage::blob_store::blob_store,
+    models::cybercns::{external_scan::ExternalScan, external_scan_finding::ExternalScanFinding},
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExternalScanSummary {
+    pub id: i32,
+    pub tenant_id: i32,
+    pub tenant_name: String,
+    pub host_name: String,
+    pub scanned_at: DateTime<Utc>,
+    pub findings: i64,
+    pub open_ports: i64,
+    pub critical: i64,
+    pub high: i64,
+    pub medium: i64,
+    pub low: i64,
+    pub opened_ports_since_previous: Option<i32>,
+    pub new_vulnerabilities: Option<i32>,
+    pub new_tls_issues: Option<i32>,
+    pub resolved_findings: Option<i32>,
+}
+
+/**
+Store the results of an external scan of a tenant's host.
+
+The raw CyberCNS JSON is kept in the blob store as an attachment of the scan, the findings parsed
+from it are what the API and reports read.
+*/
+pub async fn store_external_scan(
+    pool: &PgPool,
+    tenant_id: i32,
+    host_name: &str,
+    company_id: Option<&str>,
+    results: &Value,
+) -> Result<ExternalScan> {
+    let mut transaction = pool.begin().await?;
+
+    let scan = sqlx::query_as!(
+        ExternalScan,
+        r#"
+            INSERT INTO external_scans (tenant_id, host_name, company_id)
+            VALUES ($1, $2, $3)
+            RETURNING *;
+        "#,
+        tenant_id,
+        host_name,
+        company_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?;
+
+    let raw_key = format!("scans/external-scan-{}.json", scan.id);
+
+    blob_store()
+        .put(&raw_key, serde_json::to_vec(results)?, "application/json")
+        .await?;
+
+    let scan = sqlx::query_as!(
+        ExternalScan,
+        "UPDATE external_scans SET raw_key = $2 WHERE id = $1 RETURNING *;",
+        scan.id,
+        raw_key
+    )
+    .fetch_one(&mut *transaction)
+    .await?;
+
+    for finding in parse_external_scan_findings(results) {
+        sqlx::query!(
+            r#"
+                INSERT INTO external_scan_findings
+                    (scan_id, kind, severity, title, port, protocol, service, cve, cvss_score, detail)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);
+            "#,
+            scan.id,
+            finding.kind,
+            finding.severity,
+            finding.title,
+            finding.port,
+            finding.protocol,
+            finding.service,
+            finding.cve,
+            finding.cvss_score,
+            finding.detail
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(scan)
+}
+
+/// Scans with finding counts, newest first, optionally filtered by tenant, host and an inclusive date range.
+pub async fn external_scans(
+    pool: &PgPool,
+    tenant_id: Option<i32>,
+    host_name: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<ExternalScanSummary>> {
+    let scans = sqlx::query_as!(
+        ExternalScanSummary,
+        r#"
+            SELECT
+                s.id,
+                s.tenant_id,
+                t.tenant_name,
+                s.host_name,
+                s.scanned_at,
+                COUNT(f.id) AS "findings!",
+                COUNT(f.id) FILTER (WHERE f.kind = 'open_port') AS "open_ports!",
+                COUNT(f.id) FILTER (WHERE f.severity = 'critical') AS "critical!",
+                COUNT(f.id) FILTER (WHERE f.severity = 'high') AS "high!",
+                COUNT(f.id) FILTER (WHERE f.severity = 'medium') AS "medium!",
+                COUNT(f.id) FILTER (WHERE f.severity = 'low') AS "low!",
+                s.opened_ports AS opened_ports_since_previous,
+                s.new_vulnerabilities,
+                s.new_tls_issues,
+                s.resolved_findings
+            FROM external_scans AS s
+            JOIN tenants AS t ON t.id = s.tenant_id
+            LEFT JOIN external_scan_findings AS f ON f.scan_id = s.id
+            WHERE ($1::INTEGER IS NULL OR s.tenant_id = $1)
+            AND ($2::TEXT IS NULL OR LOWER(s.host_name) = LOWER($2))
+            AND ($3::DATE IS NULL OR s.scanned_at >= $3::DATE)
+            AND ($4::DATE IS NULL OR s.scanned_at < $4::DATE + 1)
+            GROUP BY s.id, t.tenant_name
+            ORDER BY s.scanned_at DESC;
+        "#,
+        tenant_id,
+        host_name,
+        from,
+        to
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(scans)
+}
+
+/// Findings of a scan, most severe first.
+pub async fn external_scan_findings(
+    pool: &PgPool,
+    scan_id: i32,
+) -> Result<Vec<ExternalScanFinding>> {
+    let findings = sqlx::query_as!(
+        ExternalScanFinding,
+        r#"
+            SELECT * FROM external_scan_findings
+            WHERE scan_id = $1
+            ORDER BY
+                CASE severity
+                    WHEN 'critical' THEN 0
+                    WHEN 'high' THEN 1
+                    WHEN 'medium' THEN 2
+                    WHEN 'low' THEN 3
+                    ELSE 4
+                END,
+                port NULLS LAST,
+                title;
+        "#,
+        scan_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(findings)
+}
@@ -0,0 +1,280 @@
+use std::{
+    fmt,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use tokio::net::lookup_host;
+
+/// Largest ranges accepted as a prefix length, a scan of anything larger would take hours.
+const MIN_IPV4_PREFIX: u32 = 24;
+const MIN_IPV6_PREFIX: u32 = 120;
+const MAX_HOSTNAME_LENGTH: usize = 253;
+const MAX_HOSTNAME_LABEL_LENGTH: usize = 63;
+
+/// What an external scan hostname entry points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExternalScanTarget {
+    Hostname(String),
+    Ip(IpAddr),
+    /// Network address and prefix length.
+    Cidr(IpAddr, u32),
+}
+
+impl ExternalScanTarget {
+    /// The `target_kind` of the entry.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ExternalScanTarget::Hostname(_) => "hostname",
+            ExternalScanTarget::Ip(_) => "ip",
+            ExternalScanTarget::Cidr(_, _) => "cidr",
+        }
+    }
+}
+
+/// The normalised form stored as `host_name`.
+impl fmt::Display for ExternalScanTarget {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExternalScanTarget::Hostname(host_name) => write!(formatter, "{}", host_name),
+            ExternalScanTarget::Ip(address) => write!(formatter, "{}", address),
+            ExternalScanTarget::Cidr(address, prefix) => {
+                write!(formatter, "{}/{}", address, prefix)
+            }
+        }
+    }
+}
+
+/**
+Parse and normalise a hostname, IP address or CIDR range to scan.
+
+Hostnames are lowercased without a trailing dot, CIDR ranges are reduced to their network address
+(`203.0.113.7/24` becomes `203.0.113.0/24`). Addresses and ranges that are not reachable from the
+internet, and ranges larger than a /24 (/120 for IPv6), are rejected.
+*/
+pub fn parse_external_scan_target(target: &str) -> Result<ExternalScanTarget, String> {
+    let target = target.trim().trim_end_matches('.').to_lowercase();
+
+    if target.is_empty() {
+        return Err("The hostname is empty.".to_string());
+    }
+
+    if let Some((address, prefix)) = target.split_once('/') {
+        let invalid = || format!("{} is not a valid CIDR range.", target);
+
+        let address = address.parse::<IpAddr>().map_err(|_| invalid())?;
+        let prefix = prefix.parse::<u32>().map_err(|_| invalid())?;
+
+        let (max_prefix, min_prefix) = match address {
+            IpAddr::V4(_) => (32, MIN_IPV4_PREFIX),
+            IpAddr::V6(_) => (128, MIN_IPV6_PREFIX),
+        };
+
+        if prefix > max_prefix {
+            return Err(invalid());
+        }
+
+        if prefix < min_prefix {
+            return Err(format!(
+                "{} is too large, ranges up to /{} can be scanned.",
+                target, min_prefix
+            ));
+        }
+
+        let network = network_address(address, prefix);
+
+        if !is_public_address(&network) {
+            return Err(format!("{} is a private range.", target));
+        }
+
+        return Ok(ExternalScanTarget::Cidr(network, prefix));
+    }
+
+    if let Ok(address) = target.parse::<IpAddr>() {
+        if !is_public_address(&address) {
+            return Err(format!("{} is a private address.", target));
+        }
+
+        return Ok(ExternalScanTarget::Ip(address));
+    }
+
+    let labels: Vec<&str> = target.split('.').collect();
+
+    let valid_hostname = target.len() <= MAX_HOSTNAME_LENGTH
+        && labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.len() <= MAX_HOSTNAME_LABEL_LENGTH
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|character| character.is_ascii_alphanumeric() || character == '-')
+        })
+        // Rules out malformed addresses such as `10.0.0.256`.
+        && !labels[labels.len() - 1]
+            .chars()
+            .all(|character| character.is_ascii_digit());
+
+    if !valid_hostname {
+        return Err(format!(
+            "{} is not a valid hostname, IP address or CIDR range.",
+            target
+        ));
+    }
+
+    Ok(ExternalScanTarget::Hostname(target))
+}
+
+/**
+Check that a target can be scanned from the internet, returning the addresses it covers or why it
+cannot be scanned.
+
+Hostnames that do not resolve are rejected, as are hostnames resolving to any private (RFC 1918),
+loopback, link-local or unspecified address since CyberCNS cannot reach those. A CIDR range returns
+its network address.
+*/
+pub async fn validate_external_scan_target(target: &str) -> Result<Vec<IpAddr>, String> {
+    let host_name = match parse_external_scan_target(target)? {
+        ExternalScanTarget::Hostname(host_name) => host_name,
+        ExternalScanTarget::Ip(address) | ExternalScanTarget::Cidr(address, _) => {
+            return Ok(vec![address])
+        }
+    };
+
+    let addresses: Vec<IpAddr> = match lookup_host((host_name.as_str(), 0)).await {
+        Ok(addresses) => addresses.map(|address| address.ip()).collect(),
+        Err(error) => return Err(format!("{} does not resolve: {}", host_name, error)),
+    };
+
+    if addresses.is_empty() {
+        return Err(format!("{} does not resolve.", host_name));
+    }
+
+    if let Some(address) = addresses.iter().find(|address| !is_public_address(address)) {
+        return Err(format!(
+            "{} resolves to the private address {}.",
+            host_name, address
+        ));
+    }
+
+    Ok(addresses)
+}
+
+fn network_address(address: IpAddr, prefix: u32) -> IpAddr {
+    match address {
+        IpAddr::V4(address) => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+
+            IpAddr::V4(Ipv4Addr::from(u32::from(address) & mask))
+        }
+        IpAddr::V6(address) => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+
+            IpAddr::V6(Ipv6Addr::from(u128::from(address) & mask))
+        }
+    }
+}
+
+/// Whether an address can be reached from the internet.
+pub fn is_public_address(address: &IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => {
+            !(address.is_private()
+                || address.is_loopback()
+                || address.is_link_local()
+                || address.is_unspecified()
+                || address.is_broadcast())
+        }
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(address) => is_public_address(&IpAddr::V4(address)),
+            None => {
+                let first_segment = address.segments()[0];
+
+                // Unique local (fc00::/7) and link-local (fe80::/10) addresses.
+                !(address.is_loopback()
+                    || address.is_unspecified()
+                    || first_segment & 0xfe00 == 0xfc00
+                    || first_segment & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_addresses_unreachable_from_the_internet() {
+        for address in [
+            "10.1.2.3",
+            "172.16.0.1",
+            "172.31.255.255",
+            "192.168.1.1",
+            "127.0.0.1",
+            "169.254.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:192.168.1.1",
+        ] {
+            assert!(!is_public_address(&address.parse().unwrap()), "{}", address);
+        }
+
+        for address in ["8.8.8.8", "172.32.0.1", "2606:4700::1111", "::ffff:1.1.1.1"] {
+            assert!(is_public_address(&address.parse().unwrap()), "{}", address);
+        }
+    }
+
+    #[test]
+    fn normalises_hostnames_addresses_and_ranges() {
+        let parse =
+            |target: &str| parse_external_scan_target(target).map(|target| target.to_string());
+
+        assert_eq!(
+            parse(" WWW.Example.com. "),
+            Ok("www.example.com".to_string())
+        );
+        assert_eq!(parse("8.8.4.4"), Ok("8.8.4.4".to_string()));
+        assert_eq!(parse("203.0.114.77/24"), Ok("203.0.114.0/24".to_string()));
+        assert_eq!(
+            parse("2606:4700::1111/124"),
+            Ok("2606:4700::1110/124".to_string())
+        );
+        assert_eq!(
+            parse_external_scan_target("8.8.4.0/30").map(|target| target.kind()),
+            Ok("cidr")
+        );
+
+        for target in [
+            "",
+            "localhost",
+            "-bad.example.com",
+            "bad_host.example.com",
+            "*.example.com",
+            "https://example.com",
+            "10.0.0.256",
+            "10.0.0.1",
+            "192.168.0.0/24",
+            "8.8.0.0/16",
+            "8.8.8.0/33",
+        ] {
+            assert!(parse_external_scan_target(target).is_err(), "{}", target);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_private_and_unresolvable_hostnames() {
+        assert!(validate_external_scan_target("192.168.10.20")
+            .await
+            .is_err());
+        assert!(validate_external_scan_target("localhost").await.is_err());
+        assert!(validate_external_scan_target("host.invalid").await.is_err());
+        assert!(validate_external_scan_target("  ").await.is_err());
+        assert_eq!(
+            validate_external_scan_target("1.1.1.1").await,
+            Ok(vec!["1.1.1.1".parse().unwrap()])
+        );
+    }
+}
@@ -1,4 +1,11 @@
 pub mod agents;
 pub mod assets;
 pub mod vulnerabilities;
-pub mod external_scan;
\ No newline at end of file
+pub mod vulnerability_priorities;
+pub mod vulnerability_store;
+pub mod external_scan;
+pub mod external_scan_diff;
+pub mod external_scan_findings;
+pub mod external_scan_runner;
+pub mod external_scan_store;
+pub mod external_scan_target;
\ No newline at end of file
@@ -1,12 +1,16 @@
 use std::{env, time::Duration};
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use dotenv::dotenv;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
 use sqlx::postgres::PgPoolOptions;
 
+/// Severities synced unless `CYBER_CNS_VULNERABILITY_SEVERITIES` lists others, as CyberCNS names them.
+pub const CYBER_CNS_VULNERABILITY_SEVERITIES: [&str; 4] = ["Critical", "High", "Medium", "Low"];
+const CYBER_CNS_VULNERABILITY_PAGE_SIZE: usize = 100;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CyberVulnerabilityScore {
     pub base_score: Option<f64>,
@@ -37,11 +41,12 @@ pub struct AssetRef {
     pub tags: Option<Vec<String>>,
 }
 
-// _id, product, category, title, severity, vector, cisa_vulnerabilityname, cisa, vuls_suppression, port, score, companyRef, agentRef, assetRef
+// _id, vul_id, product, category, title, severity, vector, cisa_vulnerabilityname, cisa, vuls_suppression, port, score, companyRef, agentRef, assetRef
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CyberVulnerability {
     pub _id: Option<String>,
+    pub vul_id: Option<String>,
     pub product: Option<Vec<String>>,
     pub category: Option<String>,
     pub title: Option<String>,
@@ -68,7 +73,57 @@ pub struct CyberVulnerabilityResponse {
     pub scroll_id: Option<String>,
 }
 
-pub async fn vulnerabilities() -> Result<Vec<CyberVulnerability>, Error> {
+/// Vulnerabilities fetched from CyberCNS.
+#[derive(Debug)]
+pub struct CyberVulnerabilitySync {
+    pub vulnerabilities: Vec<CyberVulnerability>,
+    /// `(company id, severity)` pairs fetched without errors, only their missing vulnerabilities
+    /// may be marked resolved.
+    pub complete: Vec<(String, String)>,
+}
+
+/**
+Severities to sync, from the comma separated `CYBER_CNS_VULNERABILITY_SEVERITIES`
+(e.g. `critical,high`), every severity by default.
+*/
+pub fn vulnerability_severities() -> Vec<String> {
+    dotenv().ok();
+
+    let severities: Vec<String> = env::var("CYBER_CNS_VULNERABILITY_SEVERITIES")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|severity| !severity.is_empty())
+        .map(|severity| {
+            let mut characters = severity.chars();
+
+            match characters.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(characters.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    if severities.is_empty() {
+        CYBER_CNS_VULNERABILITY_SEVERITIES
+            .iter()
+            .map(|severity| severity.to_string())
+            .collect()
+    } else {
+        severities
+    }
+}
+
+/// The CyberCNS query for a company's vulnerabilities of one severity.
+pub fn vulnerability_query(company_id: &str, severity: &str) -> Value {
+    json!({"query":{"bool":{"must":[{"exists":{"field":"_id"}},{"exists":{"field":"vul_id"}},{"exists":{"field":"score"}},{"exists":{"field":"severity"}},{"exists":{"field":"companyRef"}},{"exists":{"field":"assetRef"}},{"match":{"companyRef.id.keyword":company_id}},{"match":{"severity.keyword":severity}}]}}})
+}
+
+/// Every company's vulnerabilities of the configured severities.
+pub async fn vulnerabilities() -> Result<CyberVulnerabilitySync, Error> {
     dotenv().ok();
 
     let database_url = std::env::var("DATABASE_URL")
@@ -83,8 +138,8 @@ pub async fn vulnerabilities() -> Result<Vec<CyberVulnerability>, Error> {
 
     #[derive(Debug, Deserialize, Serialize)]
     pub struct CyberCompany {
-        id: Option<String>,
-        name: Option<String>,
+        id: String,
+        name: String,
     }
 
     let companies_result = sqlx::query_as!(
@@ -97,204 +152,121 @@ pub async fn vulnerabilities() -> Result<Vec<CyberVulnerability>, Error> {
     .await
     .expect("Failed to find CyberCNS companies from postgres database.");
 
-    let username = env::var("CYBER_CNS_CLIENT_ID").ok().unwrap();
-    let password = env::var("CYBER_CNS_CLIENT_SECRET").ok().unwrap();
+    let username =
+        env::var("CYBER_CNS_CLIENT_ID").map_err(|_| anyhow!("CYBER_CNS_CLIENT_ID is not set."))?;
+    let password = env::var("CYBER_CNS_CLIENT_SECRET")
+        .map_err(|_| anyhow!("CYBER_CNS_CLIENT_SECRET is not set."))?;
+
+    let client = Client::builder().http1_title_case_headers().build()?;
+    let severities = vulnerability_severities();
 
-    let mut vulnerabilities: Vec<CyberVulnerability> = Vec::new();
+    let mut sync = CyberVulnerabilitySync {
+        vulnerabilities: Vec::new(),
+        complete: Vec::new(),
+    };
 
     for company in companies_result {
-        let company_name = company.name.unwrap();
-
-        let client = Client::builder().http1_title_case_headers().build()?;
-
-        println!(
-            "Fetching critical severity vulnerabilities for {}",
-            company_name
-        );
-
-        let response = client
-        .get(format!(
-            "https://portaleuwest2.mycybercns.com/api/vulnerability?q={}&limit=1",
-            json!({"query":{"bool":{"must":[{"exists":{"field":"_id"}},{"exists":{"field":"vul_id"}},{"exists":{"field":"score"}},{"exists":{"field":"severity"}},{"exists":{"field":"companyRef"}},{"exists":{"field":"assetRef"}},{"match":{"companyRef.id.keyword":company.id}},{"match":{"severity.keyword":"Critical"}}]}}}).to_string(),
-        ))
-        .header("customerid", "clay")
-        .header("User-Agent", "ra-v1")
-        .basic_auth(&username, Some(&password))
-        .send()
-        .await?;
-
-        let body = response
-            .json::<CyberVulnerabilityResponse>()
-            .await
-            .expect("Failed to retrieve CyberCNS assets.");
-
-        let total_assets = body.total.ok_or(0);
-
-        println!(
-            "Found {} vulnerabilities from CyberCNS for {}.",
-            total_assets.unwrap(),
-            company_name
-        );
-
-        let limit = 100;
-        let total = total_assets.unwrap();
-        let pages = total / limit;
-        let mut current_page = 0;
-
-        println!("Pages: {}", pages + 1);
-
-        let company_name = company_name.clone();
-
-        while current_page < pages + 1 {
-            let response = client
-            .get(format!(
-                "https://portaleuwest2.mycybercns.com/api/vulnerability?q={}&limit=100&skip={}",
-                json!({"query":{"bool":{"must":[{"exists":{"field":"_id"}},{"exists":{"field":"vul_id"}},{"exists":{"field":"score"}},{"exists":{"field":"severity"}},{"exists":{"field":"companyRef"}},{"exists":{"field":"assetRef"}},{"match":{"companyRef.id.keyword":company.id}},{"match":{"severity.keyword":"Critical"}}]}}}).to_string(),
-                current_page * limit
-            ))
-            .header("customerid", "clay")
-            .header("User-Agent", "ra-v1")
-            .basic_auth(&username, Some(&password))
-            .send()
-            .await;
-
-            match response {
-                Ok(response) => {
-                    let vulnerabilities_result =
-                        response.json::<CyberVulnerabilityResponse>().await;
-
-                    match vulnerabilities_result {
-                        Ok(result) => {
-                            if result.data.is_some() {
-                                let page_vulnerabilities = result.data.unwrap();
-                                let page_size = page_vulnerabilities.len();
-
-                                if page_size == 0 {
-                                    break;
-                                }
-
-                                vulnerabilities.extend(page_vulnerabilities);
-
-                                println!(
-                                    "Fetched page {}, current dataset: {}, total dataset: {}, company: {}",
-                                    current_page + 1,
-                                    page_size,
-                                    vulnerabilities.len(),
-                                    company_name
-                                );
-                                current_page += 1;
-                                continue;
-                            }
-                        }
-                        Err(error) => {
-                            println!("Failed to get page {}: {}", current_page + 1, error);
-                            current_page += 1;
-                            continue;
+        for severity in &severities {
+            println!(
+                "Fetching {} severity vulnerabilities for {}",
+                severity.to_lowercase(),
+                company.name
+            );
+
+            let query = vulnerability_query(&company.id, severity).to_string();
+            let mut vulnerabilities: Vec<CyberVulnerability> = Vec::new();
+            let mut complete = true;
+
+            loop {
+                let response = client
+                    .get(format!(
+                        "https://portaleuwest2.mycybercns.com/api/vulnerability?q={}&limit={}&skip={}",
+                        query,
+                        CYBER_CNS_VULNERABILITY_PAGE_SIZE,
+                        vulnerabilities.len()
+                    ))
+                    .header("customerid", "clay")
+                    .header("User-Agent", "ra-v1")
+                    .basic_auth(&username, Some(&password))
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+
+                let page = match response {
+                    Ok(response) => response.json::<CyberVulnerabilityResponse>().await,
+                    Err(error) => Err(error),
+                };
+
+                match page {
+                    Ok(page) => {
+                        let page_vulnerabilities = page.data.unwrap_or_default();
+                        let page_size = page_vulnerabilities.len();
+
+                        vulnerabilities.extend(page_vulnerabilities);
+
+                        println!(
+                            "Fetched {} of {} {} severity vulnerabilities, company: {}",
+                            vulnerabilities.len(),
+                            page.total
+                                .map_or_else(|| "unknown".to_string(), |total| total.to_string()),
+                            severity.to_lowercase(),
+                            company.name
+                        );
+
+                        if is_last_vulnerability_page(vulnerabilities.len(), page_size, page.total)
+                        {
+                            break;
                         }
                     }
-                }
-                Err(error) => {
-                    println!("Failed to get page {}: {}", current_page + 1, error);
-                    current_page += 1;
-                    continue;
+                    Err(error) => {
+                        println!(
+                            "Failed to get page {}: {}",
+                            vulnerabilities.len() / CYBER_CNS_VULNERABILITY_PAGE_SIZE + 1,
+                            error
+                        );
+                        complete = false;
+                        break;
+                    }
                 }
             }
-        }
 
-        println!(
-            "Fetching high severity vulnerabilities for {}",
-            company_name
-        );
-
-        let response = client
-        .get(format!(
-            "https://portaleuwest2.mycybercns.com/api/vulnerability?q={}&limit=1",
-            json!({"query":{"bool":{"must":[{"exists":{"field":"_id"}},{"exists":{"field":"vul_id"}},{"exists":{"field":"score"}},{"exists":{"field":"severity"}},{"exists":{"field":"companyRef"}},{"exists":{"field":"assetRef"}},{"match":{"companyRef.id.keyword":company.id}},{"match":{"severity.keyword":"High"}}]}}}).to_string(),
-        ))
-        .header("customerid", "clay")
-        .header("User-Agent", "ra-v1")
-        .basic_auth(&username, Some(&password))
-        .send()
-        .await?;
-
-        let body = response
-            .json::<CyberVulnerabilityResponse>()
-            .await
-            .expect("Failed to retrieve CyberCNS assets.");
-
-        let total_assets = body.total.ok_or(0);
-
-        println!(
-            "Found {} vulnerabilities from CyberCNS for {}.",
-            total_assets.unwrap(),
-            company_name
-        );
-
-        let limit = 100;
-        let total = total_assets.unwrap();
-        let pages = total / limit;
-        let mut current_page = 0;
-
-        println!("Pages: {}", pages + 1);
-
-        let company_name = company_name.clone();
-
-        while current_page < pages + 1 {
-            let response = client
-            .get(format!(
-                "https://portaleuwest2.mycybercns.com/api/vulnerability?q={}&limit=100&skip={}",
-                json!({"query":{"bool":{"must":[{"exists":{"field":"_id"}},{"exists":{"field":"vul_id"}},{"exists":{"field":"score"}},{"exists":{"field":"severity"}},{"exists":{"field":"companyRef"}},{"exists":{"field":"assetRef"}},{"match":{"companyRef.id.keyword":company.id}},{"match":{"severity.keyword":"High"}}]}}}).to_string(),
-                current_page * limit,
-            ))
-            .header("customerid", "clay")
-            .header("User-Agent", "ra-v1")
-            .basic_auth(&username, Some(&password))
-            .send()
-            .await;
-
-            match response {
-                Ok(response) => {
-                    let vulnerabilities_result =
-                        response.json::<CyberVulnerabilityResponse>().await;
-
-                    match vulnerabilities_result {
-                        Ok(result) => {
-                            if result.data.is_some() {
-                                let page_vulnerabilities = result.data.unwrap();
-                                let page_size = page_vulnerabilities.len();
-
-                                if page_size == 0 {
-                                    break;
-                                }
-
-                                vulnerabilities.extend(page_vulnerabilities);
-
-                                println!(
-                                    "Fetched page {}, current dataset: {}, total dataset: {}, company: {}",
-                                    current_page + 1,
-                                    page_size,
-                                    vulnerabilities.len(),
-                                    company_name
-                                );
-                                current_page += 1;
-                                continue;
-                            }
-                        }
-                        Err(error) => {
-                            println!("Failed to get page {}: {}", current_page + 1, error);
-                            current_page += 1;
-                            continue;
-                        }
-                    }
-                }
-                Err(error) => {
-                    println!("Failed to get page {}: {}", current_page + 1, error);
-                    current_page += 1;
-                    continue;
-                }
+            sync.vulnerabilities.extend(vulnerabilities);
+
+            if complete {
+                sync.complete.push((company.id.clone(), severity.clone()));
             }
         }
     }
 
-    Ok(vulnerabilities)
+    Ok(sync)
+}
+
+/**
+Whether a page is the last one of a company's vulnerabilities of one severity.
+
+A missing `total` is unknown rather than zero, paging then continues until a short page, since
+stopping early would mark the unfetched vulnerabilities as resolved.
+*/
+fn is_last_vulnerability_page(fetched: usize, page_size: usize, total: Option<i64>) -> bool {
+    page_size < CYBER_CNS_VULNERABILITY_PAGE_SIZE
+        || total.is_some_and(|total| fetched as i64 >= total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_paging_while_the_total_is_unknown() {
+        let full = CYBER_CNS_VULNERABILITY_PAGE_SIZE;
+
+        assert!(!is_last_vulnerability_page(full, full, None));
+        assert!(!is_last_vulnerability_page(3 * full, full, None));
+        assert!(is_last_vulnerability_page(full + 7, 7, None));
+        assert!(is_last_vulnerability_page(full, 0, None));
+
+        assert!(!is_last_vulnerability_page(full, full, Some(250)));
+        assert!(is_last_vulnerability_page(2 * full, full, Some(200)));
+        assert!(is_last_vulnerability_page(full + 50, 50, Some(250)));
+    }
 }
@@ -0,0 +1,186 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// Share of the 0 to 100 risk score from CVSS, EPSS and being known exploited.
+const CVSS_RISK_WEIGHT: f64 = 50.0;
+const EPSS_RISK_WEIGHT: f64 = 30.0;
+const KNOWN_EXPLOITED_RISK_WEIGHT: f64 = 20.0;
+
+/// An open vulnerability of a tenant, one entry per CVE (or title without one) across its assets.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VulnerabilityPriority {
+    pub vul_id: Option<String>,
+    pub title: Option<String>,
+    pub severity: Option<String>,
+    pub cvss_score: Option<f64>,
+    pub epss: Option<f64>,
+    pub epss_percentile: Option<f64>,
+    /// In the CISA KEV catalogue, or flagged as such by CyberCNS.
+    pub known_exploited: bool,
+    pub known_ransomware_campaign_use: bool,
+    pub kev_due_date: Option<NaiveDate>,
+    pub required_action: Option<String>,
+    pub risk_score: f64,
+    pub affected_assets: i64,
+    pub host_names: Vec<String>,
+    /// `None` when found before first seen was tracked.
+    pub first_seen: Option<DateTime<Utc>>,
+}
+
+/**
+Risk of a vulnerability from 0 to 100.
+
+CVSS (0 to 10) contributes up to 50, the EPSS probability of exploitation in the next 30 days up to
+30 and being known exploited 20, so a known exploited high ranks above a critical that is unlikely
+to be exploited.
+*/
+pub fn vulnerability_risk_score(
+    cvss_score: Option<f64>,
+    epss: Option<f64>,
+    known_exploited: bool,
+) -> f64 {
+    let cvss = cvss_score.unwrap_or(0.0).clamp(0.0, 10.0) / 10.0;
+    let epss = epss.unwrap_or(0.0).clamp(0.0, 1.0);
+    let known_exploited = if known_exploited { 1.0 } else { 0.0 };
+
+    let score = CVSS_RISK_WEIGHT * cvss
+        + EPSS_RISK_WEIGHT * epss
+        + KNOWN_EXPLOITED_RISK_WEIGHT * known_exploited;
+
+    (score * 10.0).round() / 10.0
+}
+
+/**
+A tenant's open vulnerabilities, riskiest first.
+
+Ties are broken by the number of affected assets, then by how long the vulnerability has been open.
+Returns every vulnerability, callers cut the list to what they display.
+*/
+pub async fn vulnerability_priorities(
+    pool: &PgPool,
+    tenant_id: i32,
+) -> Result<Vec<VulnerabilityPriority>> {
+    let mut priorities = sqlx::query!(
+        r#"
+            WITH vulnerabilities AS (
+                SELECT
+                    UPPER(NULLIF(TRIM(v.vul_id), '')) AS vul_id,
+                    CASE WHEN NULLIF(TRIM(v.vul_id), '') IS NULL THEN v.title END AS untracked_title,
+                    v.title,
+                    v.severity,
+                    COALESCE(NULLIF(v.cvss_score, 0), v.base_score) AS cvss_score,
+                    v.cisa,
+                    v.first_seen,
+                    v.asset_id,
+                    h.host_name
+                FROM cybercns_vulnerabilities AS v
+                JOIN cybercns_companies AS c ON c.id = v.company_id
+                JOIN tenants AS t ON LOWER(c.name) = LOWER(t.cyber_cns_name)
+                LEFT JOIN cybercns_assets AS a ON a.id = v.asset_id
+                LEFT JOIN cybercns_hosts AS h ON h.id = a.host
+                WHERE t.id = $1
+                AND v.resolved_at IS NULL
+            ),
+            priorities AS (
+                SELECT
+                    vul_id,
+                    (ARRAY_AGG(title ORDER BY cvss_score DESC NULLS LAST))[1] AS title,
+                    (ARRAY_AGG(severity ORDER BY cvss_score DESC NULLS LAST))[1] AS severity,
+                    MAX(cvss_score) AS cvss_score,
+                    BOOL_OR(cisa) AS cisa,
+                    COUNT(DISTINCT asset_id) AS affected_assets,
+                    COALESCE(ARRAY_AGG(DISTINCT host_name) FILTER (WHERE host_name IS NOT NULL), '{}') AS host_names,
+                    -- Found before first seen was tracked counts as the oldest.
+                    CASE WHEN BOOL_OR(first_seen IS NULL) THEN NULL ELSE MIN(first_seen) END AS first_seen
+                FROM vulnerabilities
+                GROUP BY vul_id, untracked_title
+            )
+            SELECT
+                p.vul_id,
+                p.title,
+                p.severity,
+                p.cvss_score,
+                p.cisa AS "cisa!",
+                p.affected_assets AS "affected_assets!",
+                p.host_names AS "host_names!",
+                p.first_seen,
+                k.cve_id IS NOT NULL AS "in_kev!",
+                k.known_ransomware_campaign_use AS "known_ransomware_campaign_use?",
+                k.due_date AS "kev_due_date?",
+                k.required_action AS "required_action?",
+                e.epss AS "epss?",
+                e.percentile AS "epss_percentile?"
+            FROM priorities AS p
+            LEFT JOIN known_exploited_vulnerabilities AS k ON k.cve_id = p.vul_id
+            LEFT JOIN epss_scores AS e ON e.cve_id = p.vul_id;
+        "#,
+        tenant_id
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|priority| {
+        let known_exploited = priority.in_kev || priority.cisa;
+
+        VulnerabilityPriority {
+            risk_score: vulnerability_risk_score(
+                priority.cvss_score,
+                priority.epss,
+                known_exploited,
+            ),
+            vul_id: priority.vul_id,
+            title: priority.title,
+            severity: priority.severity,
+            cvss_score: priority.cvss_score,
+            epss: priority.epss,
+            epss_percentile: priority.epss_percentile,
+            known_exploited,
+            known_ransomware_campaign_use: priority
+                .known_ransomware_campaign_use
+                .unwrap_or(false),
+            kev_due_date: priority.kev_due_date,
+            required_action: priority.required_action,
+            affected_assets: priority.affected_assets,
+            host_names: priority.host_names,
+            first_seen: priority.first_seen,
+        }
+    })
+    .collect::<Vec<VulnerabilityPriority>>();
+
+    priorities.sort_by(|a, b| {
+        b.risk_score
+            .total_cmp(&a.risk_score)
+            .then(b.affected_assets.cmp(&a.affected_assets))
+            .then(a.first_seen.cmp(&b.first_seen))
+    });
+
+    Ok(priorities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_known_exploited_and_likely_exploited_vulnerabilities_first() {
+        assert_eq!(vulnerability_risk_score(Some(10.0), Some(1.0), true), 100.0);
+        assert_eq!(vulnerability_risk_score(None, None, false), 0.0);
+        assert_eq!(
+            vulnerability_risk_score(Some(9.8), Some(0.0004), false),
+            49.0
+        );
+        assert_eq!(
+            vulnerability_risk_score(Some(12.0), Some(-1.0), false),
+            50.0
+        );
+
+        let known_exploited_high = vulnerability_risk_score(Some(7.5), Some(0.02), true);
+        let unexploited_critical = vulnerability_risk_score(Some(9.8), Some(0.001), false);
+        let likely_exploited_critical = vulnerability_risk_score(Some(9.8), Some(0.9), false);
+
+        assert!(known_exploited_high > unexploited_critical);
+        assert!(likely_exploited_critical > unexploited_critical);
+    }
+}
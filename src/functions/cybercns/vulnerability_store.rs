@@ -0,0 +1,193 @@
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::vulnerabilities::{CyberVulnerabilityScore, CyberVulnerabilitySync};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CyberVulnerabilitySyncSummary {
+    pub inserted: u64,
+    pub updated: u64,
+    pub skipped: u64,
+    pub resolved: u64,
+}
+
+/// Open and resolved vulnerabilities of a severity, with the mean time to remediate in days.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CyberVulnerabilityRemediation {
+    pub severity: Option<String>,
+    pub open: i64,
+    pub resolved: i64,
+    pub mean_days_to_remediate: Option<f64>,
+}
+
+/**
+Store fetched vulnerabilities, upserting them by id.
+
+New vulnerabilities get `first_seen`, every vulnerability found gets `last_seen` and loses
+`resolved_at` when it was resolved before. Open vulnerabilities of a fully fetched company and
+severity that were not found are marked resolved.
+*/
+pub async fn store_vulnerabilities(
+    pool: &PgPool,
+    sync: CyberVulnerabilitySync,
+) -> Result<CyberVulnerabilitySyncSummary> {
+    let seen_at = Utc::now();
+    let mut summary = CyberVulnerabilitySyncSummary::default();
+
+    for vulnerability in sync.vulnerabilities {
+        let (Some(id), Some(asset_id), Some(company_ref)) = (
+            vulnerability._id,
+            vulnerability.asset_ref.and_then(|asset_ref| asset_ref.id),
+            vulnerability.company_ref,
+        ) else {
+            summary.skipped += 1;
+            continue;
+        };
+
+        let Some(company_id) = company_ref.id else {
+            summary.skipped += 1;
+            continue;
+        };
+
+        let score = vulnerability.score.unwrap_or(CyberVulnerabilityScore {
+            base_score: Some(0.0),
+            impact_score: Some(0.0),
+            exploit_score: Some(0.0),
+            cvss_score: Some(0.0),
+        });
+        let product = vulnerability.product.unwrap_or_default();
+
+        let inserted = sqlx::query_scalar!(
+            r#"
+                INSERT INTO cybercns_vulnerabilities
+                    (id, vul_id, title, severity, vector, product, base_score, impact_score, exploit_score, cvss_score, asset_id, company_id, company_name, cisa, cisa_vulnerability_name, first_seen, last_seen)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $16)
+                ON CONFLICT (id) DO UPDATE SET
+                    vul_id = EXCLUDED.vul_id,
+                    title = EXCLUDED.title,
+                    severity = EXCLUDED.severity,
+                    vector = EXCLUDED.vector,
+                    product = EXCLUDED.product,
+                    base_score = EXCLUDED.base_score,
+                    impact_score = EXCLUDED.impact_score,
+                    exploit_score = EXCLUDED.exploit_score,
+                    cvss_score = EXCLUDED.cvss_score,
+                    asset_id = EXCLUDED.asset_id,
+                    company_id = EXCLUDED.company_id,
+                    company_name = EXCLUDED.company_name,
+                    cisa = EXCLUDED.cisa,
+                    cisa_vulnerability_name = EXCLUDED.cisa_vulnerability_name,
+                    last_seen = EXCLUDED.last_seen,
+                    resolved_at = NULL
+                RETURNING (xmax = 0) AS "inserted!";
+            "#,
+            id,
+            vulnerability.vul_id,
+            vulnerability.title,
+            vulnerability.severity,
+            vulnerability.vector,
+            product.join(", "),
+            score.base_score,
+            score.impact_score,
+            score.exploit_score,
+            score.cvss_score,
+            asset_id,
+            company_id,
+            company_ref.name.unwrap_or_default(),
+            vulnerability.cisa.unwrap_or(false),
+            vulnerability.cisa_vulnerabilityname,
+            seen_at
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if inserted {
+            summary.inserted += 1;
+        } else {
+            summary.updated += 1;
+        }
+    }
+
+    for (company_id, severity) in &sync.complete {
+        let resolved = sqlx::query!(
+            r#"
+                UPDATE cybercns_vulnerabilities SET resolved_at = $3
+                WHERE company_id = $1
+                AND LOWER(severity) = LOWER($2)
+                AND resolved_at IS NULL
+                AND last_seen < $3;
+            "#,
+            company_id,
+            severity,
+            seen_at
+        )
+        .execute(pool)
+        .await?;
+
+        summary.resolved += resolved.rows_affected();
+    }
+
+    Ok(summary)
+}
+
+/**
+Mean time to remediate per severity, most severe first.
+
+Only vulnerabilities resolved within the inclusive `from`/`to` dates count as resolved, `open`
+counts every vulnerability still open. Vulnerabilities stored before `first_seen` was tracked have
+no known start and are not counted as resolved. `tenant_id` limits it to the tenant's CyberCNS
+company.
+*/
+pub async fn vulnerability_remediation(
+    pool: &PgPool,
+    tenant_id: Option<i32>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<CyberVulnerabilityRemediation>> {
+    let remediation = sqlx::query_as!(
+        CyberVulnerabilityRemediation,
+        r#"
+            WITH vulnerabilities AS (
+                SELECT
+                    v.severity,
+                    v.resolved_at IS NULL AS open,
+                    v.resolved_at IS NOT NULL
+                        AND v.first_seen IS NOT NULL
+                        AND ($2::DATE IS NULL OR v.resolved_at >= $2::DATE)
+                        AND ($3::DATE IS NULL OR v.resolved_at < $3::DATE + 1) AS resolved,
+                    EXTRACT(EPOCH FROM v.resolved_at - v.first_seen)::DOUBLE PRECISION / 86400 AS days_to_remediate
+                FROM cybercns_vulnerabilities AS v
+                WHERE $1::INTEGER IS NULL OR v.company_id IN (
+                    SELECT c.id
+                    FROM cybercns_companies AS c
+                    JOIN tenants AS t ON LOWER(c.name) = LOWER(t.cyber_cns_name)
+                    WHERE t.id = $1
+                )
+            )
+            SELECT
+                severity,
+                COUNT(*) FILTER (WHERE open) AS "open!",
+                COUNT(*) FILTER (WHERE resolved) AS "resolved!",
+                AVG(days_to_remediate) FILTER (WHERE resolved) AS mean_days_to_remediate
+            FROM vulnerabilities
+            GROUP BY severity
+            ORDER BY
+                CASE LOWER(severity)
+                    WHEN 'critical' THEN 0
+                    WHEN 'high' THEN 1
+                    WHEN 'medium' THEN 2
+                    WHEN 'low' THEN 3
+                    ELSE 4
+                END;
+        "#,
+        tenant_id,
+        from,
+        to
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(remediation)
+}
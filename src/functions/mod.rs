@@ -1,5 +1,10 @@
+pub mod assets;
 pub mod cybercns;
+pub mod os;
 pub mod rocketcyber;
 pub mod vsa;
 pub mod spanning;
-pub mod veeam;
\ No newline at end of file
+pub mod storage;
+pub mod tenant_assets;
+pub mod veeam;
+pub mod vulnerability_feeds;
\ No newline at end of file
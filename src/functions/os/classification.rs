@@ -0,0 +1,355 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::lifecycle::{os_lifecycle, OsLifecycleEntry};
+
+/// The lowest Windows build number, anything smaller in an OS string is a year or version.
+const MIN_WINDOWS_BUILD: i64 = 6000;
+/// First Windows 10 and Windows 11 builds, used to name builds missing from the lifecycle table.
+const FIRST_WINDOWS_10_BUILD: i64 = 10240;
+const FIRST_WINDOWS_11_BUILD: i64 = 22000;
+
+/// Keywords that identify a Linux distribution, paired with the product name to report.
+const LINUX_DISTRIBUTIONS: [(&str, &str); 9] = [
+    ("ubuntu", "Ubuntu"),
+    ("debian", "Debian"),
+    ("red hat", "Red Hat Enterprise Linux"),
+    ("centos", "CentOS"),
+    ("rocky", "Rocky Linux"),
+    ("alma", "AlmaLinux"),
+    ("suse", "SUSE Linux"),
+    ("oracle", "Oracle Linux"),
+    ("linux", "Linux"),
+];
+
+/// Keywords that identify a Windows edition, most specific first. LTSB is the former name of LTSC and
+/// `EnterpriseS` the edition id both report.
+const WINDOWS_EDITIONS: [(&str, &str); 11] = [
+    ("ltsc", "LTSC"),
+    ("ltsb", "LTSC"),
+    ("enterprises", "LTSC"),
+    ("enterprise", "Enterprise"),
+    ("education", "Education"),
+    ("datacenter", "Datacenter"),
+    ("essentials", "Essentials"),
+    ("standard", "Standard"),
+    ("professional", "Pro"),
+    ("pro", "Pro"),
+    ("home", "Home"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OsClassification {
+    /// One of `windows`, `windows_server`, `linux`, `macos` or `unknown`.
+    pub family: String,
+    pub product: Option<String>,
+    pub edition: Option<String>,
+    pub version: Option<String>,
+    pub build: Option<i64>,
+    pub end_of_support: Option<NaiveDate>,
+}
+
+impl OsClassification {
+    fn family(family: &str) -> Self {
+        OsClassification {
+            family: family.to_string(),
+            product: None,
+            edition: None,
+            version: None,
+            build: None,
+            end_of_support: None,
+        }
+    }
+}
+
+/**
+Classify an OS string such as VSA's `OSInfo` (`Professional x64 Edition Build 19045`).
+
+Windows is identified by build number against the lifecycle table, with `server` in the string
+telling client and server releases with the same build apart. Builds newer than the table are still
+named (Windows 10/11 or Windows Server) but have no version or end of support.
+*/
+pub fn classify_os(os_info: &str) -> OsClassification {
+    let lowercase = os_info.to_lowercase();
+    let lifecycle = os_lifecycle();
+
+    if lowercase.contains("mac os") || lowercase.contains("macos") || lowercase.contains("os x") {
+        let mut classification = OsClassification::family("macos");
+
+        classification.product = Some("macOS".to_string());
+
+        return classification;
+    }
+
+    if let Some((_, product)) = LINUX_DISTRIBUTIONS
+        .iter()
+        .find(|(keyword, _)| contains_word(&lowercase, keyword))
+    {
+        let mut classification = OsClassification::family("linux");
+
+        match lifecycle.iter().find(|entry| {
+            entry.family == "linux"
+                && entry
+                    .keyword
+                    .as_ref()
+                    .is_some_and(|keyword| lowercase.contains(keyword.as_str()))
+        }) {
+            Some(entry) => apply_lifecycle(&mut classification, entry),
+            None => classification.product = Some(product.to_string()),
+        }
+
+        return classification;
+    }
+
+    let family = if lowercase.contains("server") {
+        "windows_server"
+    } else {
+        "windows"
+    };
+
+    let edition = WINDOWS_EDITIONS
+        .iter()
+        .find(|(keyword, _)| contains_word(&lowercase, keyword))
+        .map(|(_, edition)| edition.to_string());
+
+    let builds = windows_builds(&lowercase);
+
+    if builds.is_empty() && edition.is_none() && !lowercase.contains("windows") {
+        return OsClassification::family("unknown");
+    }
+
+    let mut classification = OsClassification::family(family);
+
+    classification.edition = edition;
+
+    // Server strings often name the release ("Windows Server 2019") instead of the build.
+    let entry = builds
+        .iter()
+        .find_map(|build| {
+            let releases = || {
+                lifecycle
+                    .iter()
+                    .filter(|entry| entry.family == family && entry.build == Some(*build))
+            };
+
+            releases()
+                .find(|entry| entry.edition.is_some() && entry.edition == classification.edition)
+                .or_else(|| releases().find(|entry| entry.edition.is_none()))
+        })
+        .or_else(|| {
+            lifecycle
+                .iter()
+                .filter(|entry| {
+                    entry.family == "windows_server"
+                        && family == "windows_server"
+                        && entry.edition.is_none()
+                        && lowercase.contains(&entry.product.to_lowercase())
+                })
+                .max_by_key(|entry| entry.product.len())
+        });
+
+    match entry {
+        Some(entry) => apply_lifecycle(&mut classification, entry),
+        None => {
+            classification.build = builds.first().copied();
+            classification.product = Some(windows_product_name(
+                family,
+                classification.build,
+                &lowercase,
+            ));
+        }
+    }
+
+    classification
+}
+
+fn apply_lifecycle(classification: &mut OsClassification, entry: &OsLifecycleEntry) {
+    let extended = matches!(
+        classification.edition.as_deref(),
+        Some("Enterprise") | Some("Education") | Some("LTSC")
+    );
+
+    classification.product = Some(entry.product.clone());
+    classification.version = entry.version.clone();
+    classification.build = entry.build;
+    classification.end_of_support = match entry.enterprise_end_of_support {
+        Some(enterprise_end_of_support) if extended => Some(enterprise_end_of_support),
+        _ => Some(entry.end_of_support),
+    };
+}
+
+fn windows_product_name(family: &str, build: Option<i64>, lowercase: &str) -> String {
+    if family == "windows_server" {
+        return "Windows Server".to_string();
+    }
+
+    match build {
+        Some(build) if build >= FIRST_WINDOWS_11_BUILD => "Windows 11".to_string(),
+        Some(build) if build >= FIRST_WINDOWS_10_BUILD => "Windows 10".to_string(),
+        _ if lowercase.contains("windows 11") => "Windows 11".to_string(),
+        _ if lowercase.contains("windows 10") => "Windows 10".to_string(),
+        _ => "Windows".to_string(),
+    }
+}
+
+/// Candidate build numbers in an OS string, the one after `build` first.
+fn windows_builds(lowercase: &str) -> Vec<i64> {
+    let mut builds: Vec<i64> = Vec::new();
+
+    if let Some((_, after_build)) = lowercase.split_once("build") {
+        let digits: String = after_build
+            .trim_start()
+            .chars()
+            .take_while(|character| character.is_ascii_digit())
+            .collect();
+
+        if let Ok(build) = digits.parse::<i64>() {
+            builds.push(build);
+        }
+    }
+
+    for run in lowercase.split(|character: char| !character.is_ascii_digit()) {
+        if let Ok(build) = run.parse::<i64>() {
+            if build >= MIN_WINDOWS_BUILD && !builds.contains(&build) {
+                builds.push(build);
+            }
+        }
+    }
+
+    builds.retain(|build| *build >= MIN_WINDOWS_BUILD);
+
+    builds
+}
+
+fn contains_word(haystack: &str, word: &str) -> bool {
+    haystack
+        .split(|character: char| !character.is_ascii_alphanumeric())
+        .any(|token| token == word)
+        || (word.contains(' ') && haystack.contains(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(date: &str) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+    }
+
+    #[test]
+    fn classifies_windows_10_from_vsa_os_info() {
+        let classification = classify_os("Professional x64 Edition Build 19045");
+
+        assert_eq!(classification.family, "windows");
+        assert_eq!(classification.product.as_deref(), Some("Windows 10"));
+        assert_eq!(classification.edition.as_deref(), Some("Pro"));
+        assert_eq!(classification.version.as_deref(), Some("22H2"));
+        assert_eq!(classification.build, Some(19045));
+        assert_eq!(classification.end_of_support, date("2025-10-14"));
+    }
+
+    #[test]
+    fn uses_enterprise_end_of_support_for_enterprise_editions() {
+        let classification = classify_os("Enterprise x64 Edition Build 22631");
+
+        assert_eq!(classification.product.as_deref(), Some("Windows 11"));
+        assert_eq!(classification.edition.as_deref(), Some("Enterprise"));
+        assert_eq!(classification.end_of_support, date("2026-11-10"));
+    }
+
+    #[test]
+    fn uses_ltsc_lifecycle_for_long_term_servicing_editions() {
+        let classification = classify_os("Microsoft Windows 10 Enterprise LTSC 2019 Build 17763");
+
+        assert_eq!(classification.edition.as_deref(), Some("LTSC"));
+        assert_eq!(
+            classification.product.as_deref(),
+            Some("Windows 10 Enterprise LTSC 2019")
+        );
+        assert_eq!(classification.end_of_support, date("2029-01-09"));
+
+        let classification = classify_os("EnterpriseS x64 Edition Build 19044");
+
+        assert_eq!(
+            classification.product.as_deref(),
+            Some("Windows 10 Enterprise LTSC 2021")
+        );
+        assert_eq!(classification.end_of_support, date("2027-01-12"));
+
+        let classification = classify_os("Enterprise x64 Edition Build 19044");
+
+        assert_eq!(classification.product.as_deref(), Some("Windows 10"));
+        assert_eq!(classification.end_of_support, date("2024-06-11"));
+
+        let classification = classify_os("Enterprise 2016 LTSB x64 Edition Build 14393");
+
+        assert_eq!(classification.edition.as_deref(), Some("LTSC"));
+        assert_eq!(classification.end_of_support, date("2026-10-13"));
+    }
+
+    #[test]
+    fn classifies_newer_builds_from_dotted_versions() {
+        let classification = classify_os("Microsoft Windows 11 Pro 10.0.26100");
+
+        assert_eq!(classification.product.as_deref(), Some("Windows 11"));
+        assert_eq!(classification.version.as_deref(), Some("24H2"));
+        assert_eq!(classification.build, Some(26100));
+    }
+
+    #[test]
+    fn tells_servers_apart_from_clients_with_the_same_build() {
+        let classification = classify_os("Server Datacenter x64 Edition Build 14393");
+
+        assert_eq!(classification.family, "windows_server");
+        assert_eq!(
+            classification.product.as_deref(),
+            Some("Windows Server 2016")
+        );
+        assert_eq!(classification.edition.as_deref(), Some("Datacenter"));
+        assert_eq!(classification.end_of_support, date("2027-01-12"));
+    }
+
+    #[test]
+    fn classifies_servers_named_by_release() {
+        let classification = classify_os("Microsoft Windows Server 2008 R2 Standard");
+
+        assert_eq!(
+            classification.product.as_deref(),
+            Some("Windows Server 2008 R2")
+        );
+        assert_eq!(classification.edition.as_deref(), Some("Standard"));
+        assert_eq!(classification.end_of_support, date("2020-01-14"));
+    }
+
+    #[test]
+    fn names_builds_missing_from_the_lifecycle_table() {
+        let classification = classify_os("Professional x64 Edition Build 27000");
+
+        assert_eq!(classification.family, "windows");
+        assert_eq!(classification.product.as_deref(), Some("Windows 11"));
+        assert_eq!(classification.build, Some(27000));
+        assert_eq!(classification.version, None);
+        assert_eq!(classification.end_of_support, None);
+    }
+
+    #[test]
+    fn classifies_linux_distributions() {
+        let classification = classify_os("Ubuntu 20.04.6 LTS");
+
+        assert_eq!(classification.family, "linux");
+        assert_eq!(classification.product.as_deref(), Some("Ubuntu"));
+        assert_eq!(classification.version.as_deref(), Some("20.04"));
+        assert_eq!(classification.end_of_support, date("2025-05-31"));
+    }
+
+    #[test]
+    fn leaves_unrecognised_strings_unknown() {
+        assert_eq!(classify_os("Unknown").family, "unknown");
+        assert_eq!(classify_os("").family, "unknown");
+    }
+
+    #[test]
+    fn bundled_lifecycle_table_parses() {
+        assert!(!os_lifecycle().is_empty());
+    }
+}
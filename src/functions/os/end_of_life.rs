@@ -0,0 +1,182 @@
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::classification::classify_os;
+
+/// Machines whose OS leaves support within this many days are reported as nearing end of support.
+pub const DEFAULT_END_OF_LIFE_WINDOW_DAYS: i64 = 180;
+/// CyberCNS scores supported OS from 0 (fail) to 100 (pass), anything below this is unsupported.
+const CYBERCNS_SUPPORTED_OS_PASS_SCORE: f64 = 50.0;
+/// Evidence the CyberCNS sync stores when an asset came without a report card.
+const CYBERCNS_MISSING_EVIDENCE: &str = "Data not found from CyberCNS.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndOfLifeStatus {
+    Unsupported,
+    NearingEndOfSupport,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EndOfLifeMachine {
+    pub source: String,
+    pub hostname: String,
+    pub os_name: Option<String>,
+    pub product: Option<String>,
+    pub edition: Option<String>,
+    pub version: Option<String>,
+    pub end_of_support: Option<NaiveDate>,
+    /// Negative once support has ended.
+    pub days_until_end_of_support: Option<i64>,
+    pub supported_os_score: Option<f64>,
+    pub status: EndOfLifeStatus,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EndOfLifeReport {
+    pub window_days: i64,
+    pub unsupported: i64,
+    pub nearing_end_of_support: i64,
+    pub machines: Vec<EndOfLifeMachine>,
+}
+
+/**
+Machines of a tenant running an OS past, or within `window_days` of, its end of support.
+
+VSA agents use the classification stored by the VSA sync. CyberCNS assets are classified from their
+supported OS evidence, and ones without a known end of support date are still reported when
+CyberCNS fails their supported OS score.
+*/
+pub async fn end_of_life_machines(
+    pool: &PgPool,
+    tenant: &str,
+    window_days: i64,
+) -> Result<EndOfLifeReport> {
+    let today = Utc::now().date_naive();
+
+    let mut machines: Vec<EndOfLifeMachine> = Vec::new();
+
+    let vsa_agents = sqlx::query!(
+        r#"
+            SELECT
+                computer_name,
+                os_name,
+                os_product,
+                os_edition,
+                os_version,
+                os_end_of_support AS "os_end_of_support!"
+            FROM vsa_agents
+            WHERE similarity(LOWER(organization_name), LOWER($1)) >= 0.6
+            AND os_end_of_support IS NOT NULL
+            ORDER BY os_end_of_support, computer_name;
+        "#,
+        tenant
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for agent in vsa_agents {
+        let days_until_end_of_support = (agent.os_end_of_support - today).num_days();
+
+        let Some(status) = end_of_life_status(Some(days_until_end_of_support), window_days, None)
+        else {
+            continue;
+        };
+
+        machines.push(EndOfLifeMachine {
+            source: "vsa".to_string(),
+            hostname: agent.computer_name.unwrap_or("N/A".to_string()),
+            os_name: agent.os_name,
+            product: agent.os_product,
+            edition: agent.os_edition,
+            version: agent.os_version,
+            end_of_support: Some(agent.os_end_of_support),
+            days_until_end_of_support: Some(days_until_end_of_support),
+            supported_os_score: None,
+            status,
+        });
+    }
+
+    let cybercns_assets = sqlx::query!(
+        r#"
+            SELECT
+                h.host_name,
+                sr.supported_os AS supported_os_score,
+                sre.supported_os AS supported_os_evidence
+            FROM cybercns_assets AS a
+            LEFT JOIN cybercns_hosts AS h ON a.host = h.id
+            LEFT JOIN cybercns_security_report_card AS sr ON a.security_report_card = sr.id
+            LEFT JOIN cybercns_security_report_card_evidence AS sre ON sr.evidence = sre.id
+            LEFT JOIN cybercns_companies AS c ON a.company = c.id
+            WHERE similarity(LOWER(c.name), LOWER($1)) >= 0.6
+            ORDER BY h.host_name;
+        "#,
+        tenant
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for asset in cybercns_assets {
+        let Some(evidence) = asset
+            .supported_os_evidence
+            .filter(|evidence| evidence != CYBERCNS_MISSING_EVIDENCE)
+        else {
+            continue;
+        };
+
+        let classification = classify_os(&evidence);
+        let days_until_end_of_support = classification
+            .end_of_support
+            .map(|end_of_support| (end_of_support - today).num_days());
+
+        let Some(status) = end_of_life_status(
+            days_until_end_of_support,
+            window_days,
+            asset.supported_os_score,
+        ) else {
+            continue;
+        };
+
+        machines.push(EndOfLifeMachine {
+            source: "cybercns".to_string(),
+            hostname: asset.host_name.unwrap_or("N/A".to_string()),
+            os_name: Some(evidence),
+            product: classification.product,
+            edition: classification.edition,
+            version: classification.version,
+            end_of_support: classification.end_of_support,
+            days_until_end_of_support,
+            supported_os_score: asset.supported_os_score,
+            status,
+        });
+    }
+
+    let unsupported = machines
+        .iter()
+        .filter(|machine| machine.status == EndOfLifeStatus::Unsupported)
+        .count() as i64;
+
+    Ok(EndOfLifeReport {
+        window_days,
+        unsupported,
+        nearing_end_of_support: machines.len() as i64 - unsupported,
+        machines,
+    })
+}
+
+fn end_of_life_status(
+    days_until_end_of_support: Option<i64>,
+    window_days: i64,
+    supported_os_score: Option<f64>,
+) -> Option<EndOfLifeStatus> {
+    match days_until_end_of_support {
+        Some(days) if days < 0 => Some(EndOfLifeStatus::Unsupported),
+        Some(days) if days <= window_days => Some(EndOfLifeStatus::NearingEndOfSupport),
+        Some(_) => None,
+        None => supported_os_score
+            .filter(|score| *score < CYBERCNS_SUPPORTED_OS_PASS_SCORE)
+            .map(|_| EndOfLifeStatus::Unsupported),
+    }
+}
@@ -0,0 +1,63 @@
+use std::{env, fs, sync::OnceLock};
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use dotenv::dotenv;
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle table shipped with the binary, used unless `OS_LIFECYCLE_PATH` points at a newer one.
+const BUNDLED_OS_LIFECYCLE: &str = include_str!("os_lifecycle.json");
+
+static OS_LIFECYCLE: OnceLock<Vec<OsLifecycleEntry>> = OnceLock::new();
+
+/**
+A supported OS release.
+
+Windows releases are matched on `build`, others on `keyword` appearing in the lowercased OS string.
+An entry with an `edition` only applies to that edition, such as the LTSC releases that share their
+build with a general availability release but are serviced for years longer.
+*/
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OsLifecycleEntry {
+    pub family: String,
+    pub product: String,
+    pub version: Option<String>,
+    pub build: Option<i64>,
+    pub keyword: Option<String>,
+    pub edition: Option<String>,
+    pub end_of_support: NaiveDate,
+    /// Enterprise and Education editions of Windows are serviced longer than Home and Pro.
+    pub enterprise_end_of_support: Option<NaiveDate>,
+}
+
+/**
+The OS lifecycle table.
+
+Loaded once, from the JSON file at `OS_LIFECYCLE_PATH` when set, so new releases can be added
+without a rebuild. Falls back to the bundled table if that file is missing or invalid.
+*/
+pub fn os_lifecycle() -> &'static [OsLifecycleEntry] {
+    OS_LIFECYCLE.get_or_init(load_os_lifecycle)
+}
+
+fn load_os_lifecycle() -> Vec<OsLifecycleEntry> {
+    dotenv().ok();
+
+    if let Ok(path) = env::var("OS_LIFECYCLE_PATH") {
+        match read_os_lifecycle(&path) {
+            Ok(entries) => return entries,
+            Err(error) => println!(
+                "Failed to load OS lifecycle table from {}, using the bundled table: {}",
+                path, error
+            ),
+        }
+    }
+
+    serde_json::from_str(BUNDLED_OS_LIFECYCLE).expect("Bundled OS lifecycle table is invalid.")
+}
+
+fn read_os_lifecycle(path: &str) -> Result<Vec<OsLifecycleEntry>> {
+    let contents = fs::read_to_string(path)?;
+
+    Ok(serde_json::from_str(&contents)?)
+}
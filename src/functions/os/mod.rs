@@ -0,0 +1,3 @@
+pub mod classification;
+pub mod end_of_life;
+pub mod lifecycle;
@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use dotenv::dotenv;
+use reqwest::{Client, Error};
+use serde::{Deserialize, Serialize};
+
+use super::incidents::ROCKET_CYBER_PAGE_SIZE;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase", serialize = "camelCase"))]
+pub struct RocketEvent {
+    pub id: Option<i64>,
+    #[serde(alias = "customerId")]
+    pub account_id: Option<i64>,
+    #[serde(alias = "deviceId")]
+    pub agent_id: Option<String>,
+    #[serde(alias = "deviceName", alias = "computerName")]
+    pub hostname: Option<String>,
+    #[serde(alias = "appName")]
+    pub app: Option<String>,
+    #[serde(alias = "title")]
+    pub detection: Option<String>,
+    pub verdict: Option<String>,
+    pub details: Option<serde_json::Value>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase", serialize = "camelCase"))]
+pub struct RocketEventsResponse {
+    pub total_pages: Option<i64>,
+    pub data: Option<Vec<RocketEvent>>,
+}
+
+/**
+Fetch every page of the events behind an incident from RocketCyber using the reqwest HTTP Client crate.
+*/
+pub async fn incident_events(incident_id: i64) -> Result<Vec<RocketEvent>, Error> {
+    dotenv().ok();
+
+    let client = Client::builder().http1_title_case_headers().build()?;
+
+    let access_token = std::env::var("ROCKET_CYBER_API_KEY").ok().unwrap();
+
+    let mut events: Vec<RocketEvent> = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let body = client
+            .get("https://api-eu.rocketcyber.com/v3/events")
+            .query(&[
+                ("incidentId", incident_id),
+                ("pageSize", ROCKET_CYBER_PAGE_SIZE),
+                ("page", page),
+            ])
+            .bearer_auth(&access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RocketEventsResponse>()
+            .await?;
+
+        let data = body.data.unwrap_or_default();
+        let received = data.len();
+
+        events.extend(data);
+
+        if received == 0 || page >= body.total_pages.unwrap_or(page) {
+            break;
+        }
+
+        page += 1;
+    }
+
+    Ok(events)
+}
@@ -0,0 +1,138 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RocketIncidentAgeBucket {
+    pub bucket: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RocketIncidentStatusCount {
+    pub status: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RocketIncidentMonth {
+    pub month: String,
+    pub created: i64,
+    pub resolved: i64,
+    pub mean_time_to_resolve_hours: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RocketIncidentMetrics {
+    pub total: i64,
+    pub open: i64,
+    pub resolved: i64,
+    pub mean_time_to_resolve_hours: Option<f64>,
+    pub median_time_to_resolve_hours: Option<f64>,
+    pub open_age_buckets: Vec<RocketIncidentAgeBucket>,
+    pub by_status: Vec<RocketIncidentStatusCount>,
+    pub by_month: Vec<RocketIncidentMonth>,
+}
+
+/**
+Time-to-resolve and open-incident aging for a RocketCyber account.
+
+Time to resolve is `resolved_at - created_at` in hours. Open incidents (`is_open`, without a
+`resolved_at` and not marked resolved) are bucketed by how long ago they were created.
+*/
+pub async fn incident_metrics(pool: &PgPool, account_id: i64) -> Result<RocketIncidentMetrics> {
+    let totals = sqlx::query!(
+        r#"
+            SELECT
+                COUNT(*) AS "total!",
+                COUNT(*) FILTER (WHERE is_open) AS "open!",
+                COUNT(*) FILTER (WHERE NOT is_open) AS "resolved!",
+                (AVG(EXTRACT(EPOCH FROM resolved_at - created_at)) / 3600)::DOUBLE PRECISION
+                    AS mean_time_to_resolve_hours,
+                (PERCENTILE_CONT(0.5) WITHIN GROUP (
+                    ORDER BY EXTRACT(EPOCH FROM resolved_at - created_at)
+                ) / 3600)::DOUBLE PRECISION AS median_time_to_resolve_hours,
+                COUNT(*) FILTER (
+                    WHERE is_open AND created_at > NOW() - INTERVAL '1 day'
+                ) AS "open_under_1_day!",
+                COUNT(*) FILTER (
+                    WHERE is_open
+                    AND created_at <= NOW() - INTERVAL '1 day'
+                    AND created_at > NOW() - INTERVAL '7 days'
+                ) AS "open_1_to_7_days!",
+                COUNT(*) FILTER (
+                    WHERE is_open
+                    AND created_at <= NOW() - INTERVAL '7 days'
+                    AND created_at > NOW() - INTERVAL '30 days'
+                ) AS "open_7_to_30_days!",
+                COUNT(*) FILTER (
+                    WHERE is_open
+                    AND (created_at <= NOW() - INTERVAL '30 days' OR created_at IS NULL)
+                ) AS "open_over_30_days!"
+            FROM rocketcyber_incidents
+            WHERE account_id = $1;
+        "#,
+        account_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let by_status = sqlx::query_as!(
+        RocketIncidentStatusCount,
+        r#"
+            SELECT
+                status,
+                COUNT(*) AS "count!"
+            FROM rocketcyber_incidents
+            WHERE account_id = $1
+            GROUP BY status
+            ORDER BY status;
+        "#,
+        account_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let by_month = sqlx::query_as!(
+        RocketIncidentMonth,
+        r#"
+            SELECT
+                TO_CHAR(created_at, 'YYYY-MM') AS "month!",
+                COUNT(*) AS "created!",
+                COUNT(*) FILTER (WHERE NOT is_open) AS "resolved!",
+                (AVG(EXTRACT(EPOCH FROM resolved_at - created_at)) / 3600)::DOUBLE PRECISION
+                    AS mean_time_to_resolve_hours
+            FROM rocketcyber_incidents
+            WHERE account_id = $1 AND created_at IS NOT NULL
+            GROUP BY TO_CHAR(created_at, 'YYYY-MM')
+            ORDER BY TO_CHAR(created_at, 'YYYY-MM');
+        "#,
+        account_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let open_age_buckets = [
+        ("< 1 day", totals.open_under_1_day),
+        ("1 - 7 days", totals.open_1_to_7_days),
+        ("7 - 30 days", totals.open_7_to_30_days),
+        ("> 30 days", totals.open_over_30_days),
+    ]
+    .into_iter()
+    .map(|(bucket, count)| RocketIncidentAgeBucket {
+        bucket: bucket.to_string(),
+        count,
+    })
+    .collect();
+
+    Ok(RocketIncidentMetrics {
+        total: totals.total,
+        open: totals.open,
+        resolved: totals.resolved,
+        mean_time_to_resolve_hours: totals.mean_time_to_resolve_hours,
+        median_time_to_resolve_hours: totals.median_time_to_resolve_hours,
+        open_age_buckets,
+        by_status,
+        by_month,
+    })
+}
@@ -1,8 +1,10 @@
-use chrono::{DateTime, Days, Local};
+use chrono::{DateTime, Local, Utc};
 use dotenv::dotenv;
 use reqwest::{Client, Error};
 use serde::{Deserialize, Serialize};
 
+pub const ROCKET_CYBER_PAGE_SIZE: i64 = 100;
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all(deserialize = "camelCase", serialize = "camelCase"))]
 pub struct RocketIncident {
@@ -21,40 +23,70 @@ pub struct RocketIncident {
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all(deserialize = "camelCase", serialize = "camelCase"))]
 pub struct RocketIncidentsResponse {
+    pub total_pages: Option<i64>,
+    pub current_page: Option<i64>,
     pub data: Option<Vec<RocketIncident>>,
 }
 
-/*
-Fetch the last 30 days incidents from RocketCyber using the reqwest HTTP Client crate.
+/**
+Fetch every page of an account's incidents from RocketCyber using the reqwest HTTP Client crate.
+
+When `since` is given only incidents created on or after that day are requested, otherwise the
+account's full incident history is fetched.
 */
-pub async fn incidents() -> Result<Vec<RocketIncident>, Error> {
+pub async fn incidents(
+    account_id: i64,
+    since: Option<DateTime<Utc>>,
+) -> Result<Vec<RocketIncident>, Error> {
     dotenv().ok();
 
     let client = Client::builder().http1_title_case_headers().build()?;
 
     let access_token = std::env::var("ROCKET_CYBER_API_KEY").ok().unwrap();
 
-    let local: DateTime<Local> = Local::now();
-    let start_date = local
-        .checked_sub_days(Days::new(30))
-        .unwrap()
-        .format("%Y-%m-%d")
-        .to_string();
-    let end_date = local.format("%Y-%m-%d").to_string();
-
-    let response = client
-        .get(format!(
-            "https://api-eu.rocketcyber.com/v3/incidents?createdAt={}|{}",
-            start_date, end_date
-        ))
-        .bearer_auth(access_token)
-        .send()
-        .await?;
-
-    let body = response
-        .json::<RocketIncidentsResponse>()
-        .await
-        .expect("Failed to retrieve Rocket Cyber agents.");
-
-    Ok(body.data.unwrap())
+    let mut query = vec![
+        ("accountId", account_id.to_string()),
+        ("pageSize", ROCKET_CYBER_PAGE_SIZE.to_string()),
+        ("sort", "createdAt:asc".to_string()),
+    ];
+
+    if let Some(since) = since {
+        query.push((
+            "createdAt",
+            format!(
+                "{}|{}",
+                since.format("%Y-%m-%d"),
+                Utc::now().format("%Y-%m-%d")
+            ),
+        ));
+    }
+
+    let mut incidents: Vec<RocketIncident> = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let body = client
+            .get("https://api-eu.rocketcyber.com/v3/incidents")
+            .query(&query)
+            .query(&[("page", page)])
+            .bearer_auth(&access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RocketIncidentsResponse>()
+            .await?;
+
+        let data = body.data.unwrap_or_default();
+        let received = data.len();
+
+        incidents.extend(data);
+
+        if received == 0 || page >= body.total_pages.unwrap_or(page) {
+            break;
+        }
+
+        page += 1;
+    }
+
+    Ok(incidents)
 }
@@ -0,0 +1,254 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::{events::incident_events, incidents::incidents};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct RocketIncidentSyncResult {
+    pub accounts: i64,
+    pub inserted: i64,
+    pub updated: i64,
+    pub skipped: i64,
+    pub events: i64,
+}
+
+/**
+Incrementally sync RocketCyber incidents for every known account.
+
+Each account keeps a high-water mark of the newest `created_at` seen. The first run for an account
+backfills its whole history; later runs re-fetch from the high-water mark or the oldest incident
+that is still open, whichever is earlier, so resolved incidents get their status and resolved_at
+updated.
+*/
+pub async fn sync_incidents(pool: &PgPool) -> Result<RocketIncidentSyncResult> {
+    let account_ids = sqlx::query_scalar!("SELECT DISTINCT account_id FROM rocketcyber_accounts;")
+        .fetch_all(pool)
+        .await?;
+
+    let mut result = RocketIncidentSyncResult::default();
+
+    for account_id in account_ids {
+        let high_water_mark = sqlx::query_scalar!(
+            "SELECT high_water_mark FROM rocketcyber_incident_sync WHERE account_id = $1;",
+            account_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let oldest_open_incident = sqlx::query_scalar!(
+            r#"
+                SELECT MIN(created_at)
+                FROM rocketcyber_incidents
+                WHERE account_id = $1 AND is_open;
+            "#,
+            account_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let since = match (high_water_mark, oldest_open_incident) {
+            (Some(high_water_mark), Some(oldest_open_incident)) => {
+                Some(high_water_mark.min(oldest_open_incident))
+            }
+            (high_water_mark, _) => high_water_mark,
+        };
+
+        let account_incidents = match incidents(account_id, since).await {
+            Ok(account_incidents) => account_incidents,
+            Err(error) => {
+                println!(
+                    "Failed to get RocketCyber incidents for account {}: {:?}",
+                    account_id, error
+                );
+
+                continue;
+            }
+        };
+
+        let mut newest_created_at: Option<DateTime<Utc>> = high_water_mark;
+
+        for incident in account_incidents {
+            let Some(incident_id) = incident.id else {
+                result.skipped += 1;
+
+                continue;
+            };
+
+            let event_count = incident.event_count.unwrap_or(0);
+            let incident_account_id = incident.account_id.unwrap_or(account_id);
+
+            let created_at = incident
+                .created_at
+                .map(|created_at| created_at.with_timezone(&Utc));
+
+            let upsert_result = sqlx::query!(
+                r#"
+                    INSERT INTO rocketcyber_incidents (
+                        id,
+                        title,
+                        description,
+                        remediation,
+                        resolved_at,
+                        published_at,
+                        created_at,
+                        status,
+                        account_id,
+                        event_count
+                    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                    ON CONFLICT (id) DO UPDATE SET
+                        title = EXCLUDED.title,
+                        description = EXCLUDED.description,
+                        remediation = EXCLUDED.remediation,
+                        resolved_at = EXCLUDED.resolved_at,
+                        published_at = EXCLUDED.published_at,
+                        status = EXCLUDED.status,
+                        event_count = EXCLUDED.event_count
+                    RETURNING (xmax = 0) AS "inserted!";
+                "#,
+                incident_id,
+                incident.title.unwrap_or("N/A".to_string()),
+                incident.description.unwrap_or("N/A".to_string()),
+                incident.remediation.unwrap_or("N/A".to_string()),
+                incident
+                    .resolved_at
+                    .map(|resolved_at| resolved_at.with_timezone(&Utc)),
+                incident
+                    .published_at
+                    .map(|published_at| published_at.with_timezone(&Utc)),
+                created_at,
+                incident.status.unwrap_or("N/A".to_string()),
+                incident_account_id,
+                event_count
+            )
+            .fetch_one(pool)
+            .await;
+
+            match upsert_result {
+                Ok(upserted) if upserted.inserted => result.inserted += 1,
+                Ok(_) => result.updated += 1,
+                Err(error) => {
+                    println!("Failed to upsert RocketCyber incident: {:?}", error);
+
+                    result.skipped += 1;
+
+                    continue;
+                }
+            }
+
+            match sync_incident_events(pool, incident_id, incident_account_id, event_count).await {
+                Ok(events) => result.events += events,
+                Err(error) => println!(
+                    "Failed to sync RocketCyber events for incident {}: {:?}",
+                    incident_id, error
+                ),
+            }
+
+            if created_at > newest_created_at {
+                newest_created_at = created_at;
+            }
+        }
+
+        if let Some(newest_created_at) = newest_created_at {
+            sqlx::query!(
+                r#"
+                    INSERT INTO rocketcyber_incident_sync (account_id, high_water_mark, last_synced_at)
+                    VALUES ($1, $2, NOW())
+                    ON CONFLICT (account_id) DO UPDATE SET
+                        high_water_mark = EXCLUDED.high_water_mark,
+                        last_synced_at = EXCLUDED.last_synced_at;
+                "#,
+                account_id,
+                newest_created_at
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        result.accounts += 1;
+    }
+
+    Ok(result)
+}
+
+/**
+Fetch and upsert the events behind an incident.
+
+Events are only requested when the incident's `event_count` differs from the one they were last
+fetched for. RocketCyber can return fewer events than it counts, so comparing the stored events
+against `event_count` would refetch them on every sync.
+*/
+async fn sync_incident_events(
+    pool: &PgPool,
+    incident_id: i64,
+    account_id: i64,
+    event_count: i64,
+) -> Result<i64> {
+    let events_synced_count = sqlx::query_scalar!(
+        "SELECT events_synced_count FROM rocketcyber_incidents WHERE id = $1;",
+        incident_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if events_synced_count == Some(event_count) {
+        return Ok(0);
+    }
+
+    let mut synced = 0;
+
+    for event in incident_events(incident_id).await? {
+        let Some(event_id) = event.id else {
+            continue;
+        };
+
+        sqlx::query!(
+            r#"
+                INSERT INTO rocketcyber_incident_events (
+                    id,
+                    incident_id,
+                    account_id,
+                    agent_id,
+                    hostname,
+                    app,
+                    detection,
+                    verdict,
+                    details,
+                    created_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                ON CONFLICT (id) DO UPDATE SET
+                    agent_id = EXCLUDED.agent_id,
+                    hostname = EXCLUDED.hostname,
+                    app = EXCLUDED.app,
+                    detection = EXCLUDED.detection,
+                    verdict = EXCLUDED.verdict,
+                    details = EXCLUDED.details;
+            "#,
+            event_id,
+            incident_id,
+            event.account_id.unwrap_or(account_id),
+            event.agent_id,
+            event.hostname.unwrap_or("N/A".to_string()),
+            event.app.unwrap_or("N/A".to_string()),
+            event.detection.unwrap_or("N/A".to_string()),
+            event.verdict.unwrap_or("N/A".to_string()),
+            event.details,
+            event.created_at
+        )
+        .execute(pool)
+        .await?;
+
+        synced += 1;
+    }
+
+    sqlx::query!(
+        "UPDATE rocketcyber_incidents SET events_synced_count = $2 WHERE id = $1;",
+        incident_id,
+        event_count
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(synced)
+}
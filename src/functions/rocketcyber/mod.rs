@@ -1,3 +1,6 @@
 pub mod accounts;
 pub mod agents;
+pub mod events;
+pub mod incident_metrics;
 pub mod incidents;
+pub mod incidents_sync;
@@ -1 +1,2 @@
-pub mod spanning_backups;
\ No newline at end of file
+pub mod spanning_backups;
+pub mod spanning_store;
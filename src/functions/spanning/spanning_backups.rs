@@ -6,12 +6,36 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgPoolOptions;
 
+/// Spanning Backup for Microsoft 365 regions, as stored in `tenants.spanning_region`.
+pub const SPANNING_REGIONS: [&str; 3] = ["us", "eu", "ap"];
+pub const DEFAULT_SPANNING_REGION: &str = "eu";
+
+/// Spanning products, as stored in `spanning_backups.platform`.
+pub const SPANNING_PLATFORMS: [&str; 3] = ["o365", "google_workspace", "salesforce"];
+pub const O365_SPANNING_PLATFORM: &str = "o365";
+/**
+Spanning products whose backups are synced.
+
+Google Workspace and Salesforce credentials can be stored on a tenant, but only the Microsoft 365
+`/external/users` API is modelled, so their backups are not fetched yet.
+*/
+pub const SUPPORTED_SPANNING_PLATFORMS: [&str; 1] = [O365_SPANNING_PLATFORM];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Tenant {
     pub id: i64,
     pub tenant_name: Option<String>,
     pub spanning_name: Option<String>,
     pub spanning_key: Option<String>,
+    pub spanning_region: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TenantSpanningPlatform {
+    pub tenant_name: String,
+    pub platform: String,
+    pub spanning_name: String,
+    pub spanning_key: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,6 +76,8 @@ pub struct SpanningUser {
     pub is_deleted: Option<bool>,
     pub backup_summary: Option<Vec<SpanningBackupSummary>>,
     pub company_name: Option<String>,
+    #[serde(default)]
+    pub platform: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -74,7 +100,7 @@ pub async fn spanning_backups() -> Result<Vec<SpanningUser>, Error> {
         .await
         .expect("Failed to connect to postgres.");
 
-    let tenants = sqlx::query_as!(Tenant, "SELECT id, tenant_name, spanning_name, spanning_key FROM tenants WHERE spanning_name IS NOT NULL AND spanning_key IS NOT NULL;")
+    let tenants = sqlx::query_as!(Tenant, "SELECT id, tenant_name, spanning_name, spanning_key, spanning_region FROM tenants WHERE spanning_name IS NOT NULL AND spanning_key IS NOT NULL;")
         .fetch_all(&pool)
         .await
         .expect("Failed to get tenants from postgres.");
@@ -87,7 +113,10 @@ pub async fn spanning_backups() -> Result<Vec<SpanningUser>, Error> {
         let spanning_key = tenant.spanning_key.unwrap();
 
         let new_backups = get_spanning_backups(
-            "https://o365-api-eu.spanningbackup.com/external/users".to_string(),
+            format!(
+                "{}/external/users",
+                spanning_base_url(&tenant.spanning_region)
+            ),
             tenant_name.clone().unwrap(),
             spanning_name,
             spanning_key,
@@ -100,6 +129,7 @@ pub async fn spanning_backups() -> Result<Vec<SpanningUser>, Error> {
 
                 for mut new_backup in new_backups {
                     new_backup.company_name = tenant_name.clone();
+                    new_backup.platform = Some(O365_SPANNING_PLATFORM.to_string());
 
                     backups.push(new_backup);
                 }
@@ -117,17 +147,61 @@ pub async fn spanning_backups() -> Result<Vec<SpanningUser>, Error> {
         }
     }
 
-    let mut valid_backups: Vec<SpanningUser> = Vec::new();
-
-    for backup in spanning_backups {
-        if backup.assigned.unwrap() {
-            valid_backups.push(backup);
+    let tenant_platforms = sqlx::query_as!(
+        TenantSpanningPlatform,
+        r#"
+            SELECT t.tenant_name, p.platform, p.spanning_name, p.spanning_key
+            FROM tenants_spanning_platforms AS p
+            INNER JOIN tenants AS t ON t.id = p.tenant_id;
+        "#
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get tenant spanning platforms from postgres.");
+
+    for tenant_platform in tenant_platforms {
+        if !spanning_platform_supported(&tenant_platform.platform) {
+            println!(
+                "Skipping Spanning {} backups for {}, only Microsoft 365 is supported.",
+                tenant_platform.platform, tenant_platform.tenant_name
+            );
         }
     }
 
-    println!("Spanning Backups: {}", valid_backups.len());
+    println!("Spanning Backups: {}", spanning_backups.len());
+
+    Ok(spanning_backups)
+}
+
+/// Normalise a region name and check it is one Spanning supports.
+pub fn parse_spanning_region(region: &str) -> Option<&'static str> {
+    let region = region.trim().to_lowercase();
+
+    SPANNING_REGIONS
+        .into_iter()
+        .find(|spanning_region| *spanning_region == region)
+}
+
+/// Normalise a platform name and check it is a Spanning product we sync.
+pub fn parse_spanning_platform(platform: &str) -> Option<&'static str> {
+    let platform = platform.trim().to_lowercase();
+
+    SPANNING_PLATFORMS
+        .into_iter()
+        .find(|spanning_platform| *spanning_platform == platform)
+}
+
+/// Whether backups of a Spanning platform are synced, see `SUPPORTED_SPANNING_PLATFORMS`.
+pub fn spanning_platform_supported(platform: &str) -> bool {
+    parse_spanning_platform(platform)
+        .is_some_and(|platform| SUPPORTED_SPANNING_PLATFORMS.contains(&platform))
+}
 
-    Ok(valid_backups)
+pub fn spanning_base_url(region: &str) -> String {
+    format!(
+        "https://o365-api-{}.spanningbackup.com",
+        parse_spanning_region(region).unwrap_or(DEFAULT_SPANNING_REGION)
+    )
 }
 
 pub async fn get_spanning_backups(
@@ -150,10 +224,10 @@ pub async fn get_spanning_backups(
         .get(query_url)
         .basic_auth(&spanning_name, Some(&spanning_key))
         .send()
-        .await
-        .expect("Failed to get response from Spanning Backup API");
+        .await?
+        .error_for_status()?;
 
-    let mut body = response.json::<SpanningResponse>().await.unwrap();
+    let mut body = response.json::<SpanningResponse>().await?;
 
     backups.extend(body.users);
 
@@ -175,10 +249,10 @@ pub async fn get_spanning_backups(
             .get(query_url)
             .basic_auth(&spanning_name, Some(&spanning_key))
             .send()
-            .await
-            .expect("Failed to get response from Spanning Backup API");
+            .await?
+            .error_for_status()?;
 
-        body = response.json::<SpanningResponse>().await.unwrap();
+        body = response.json::<SpanningResponse>().await?;
 
         backups.extend(body.users);
 
@@ -191,3 +265,63 @@ pub async fn get_spanning_backups(
 
     Ok(backups)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(body: &str) -> SpanningResponse {
+        serde_json::from_str(body).expect("Failed to parse Spanning response.")
+    }
+
+    fn successful_backups(user: &SpanningUser) -> Option<i64> {
+        user.backup_summary.as_ref()?[0].backup.as_ref()?.successful
+    }
+
+    #[test]
+    fn reads_microsoft_365_users() {
+        let response = parse(
+            r#"{
+                "nextLink": "https://o365-api-eu.spanningbackup.com/external/users?page=2",
+                "users": [{
+                    "userPrincipalName": "jane@example.com",
+                    "userDisplayName": "Jane Doe",
+                    "email": "jane@example.com",
+                    "msId": "8f6c3c2e-0000-0000-0000-000000000000",
+                    "assigned": true,
+                    "isAdmin": false,
+                    "isDeleted": false,
+                    "backupSummary": [{
+                        "date": "2023-12-11",
+                        "type": "mail",
+                        "backup": {
+                            "total": "120",
+                            "partial": 0,
+                            "failed": 1,
+                            "successful": 3,
+                            "data": { "created": 10, "deleted": 2, "failed": 1, "total": 120, "attempts": 4 }
+                        }
+                    }]
+                }]
+            }"#,
+        );
+
+        assert!(response.next_link.is_some());
+        assert_eq!(response.users.len(), 1);
+
+        let user = &response.users[0];
+
+        assert_eq!(user.email.as_deref(), Some("jane@example.com"));
+        assert_eq!(user.assigned, Some(true));
+        assert_eq!(successful_backups(user), Some(3));
+        assert_eq!(user.platform, None);
+    }
+
+    #[test]
+    fn only_syncs_microsoft_365() {
+        assert!(spanning_platform_supported(" O365 "));
+        assert!(!spanning_platform_supported("google_workspace"));
+        assert!(!spanning_platform_supported("salesforce"));
+        assert!(!spanning_platform_supported("dropbox"));
+    }
+}
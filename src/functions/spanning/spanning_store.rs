@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::spanning_backups::{SpanningUser, O365_SPANNING_PLATFORM};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SpanningStoreResult {
+    pub inserted: i64,
+    pub updated: i64,
+    pub unassigned: i64,
+    pub summaries_inserted: i64,
+    pub summaries_updated: i64,
+    pub skipped: i64,
+}
+
+/**
+Upsert Spanning users and their daily backup summaries.
+
+Users are keyed by `(email, company_name, platform)` and summaries by `(backup, date, backup_type)`,
+so running this repeatedly refreshes existing rows and appends new days. Users of a company and
+platform that were not returned by this fetch are flagged as unassigned.
+*/
+pub async fn store_spanning_backups(
+    pool: &PgPool,
+    backups: Vec<SpanningUser>,
+) -> Result<SpanningStoreResult> {
+    // Taken from postgres so it is comparable with the `last_seen = NOW()` written below.
+    let synced_at = sqlx::query_scalar!(r#"SELECT NOW() AS "now!";"#)
+        .fetch_one(pool)
+        .await?;
+
+    let mut result = SpanningStoreResult::default();
+    let mut synced_companies: HashSet<(String, String)> = HashSet::new();
+
+    for backup in backups {
+        let company_name = backup.company_name.unwrap_or("N/A".to_string());
+        let platform = backup
+            .platform
+            .unwrap_or(O365_SPANNING_PLATFORM.to_string());
+
+        synced_companies.insert((company_name.clone(), platform.clone()));
+
+        let user = sqlx::query!(
+            r#"
+                INSERT INTO spanning_backups (
+                    user_principal_name,
+                    user_display_name,
+                    email,
+                    ms_id,
+                    assigned,
+                    is_admin,
+                    is_deleted,
+                    company_name,
+                    platform,
+                    unassigned_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, CASE WHEN $5 THEN NULL ELSE NOW() END)
+                ON CONFLICT (email, company_name, platform) DO UPDATE SET
+                    user_principal_name = EXCLUDED.user_principal_name,
+                    user_display_name = EXCLUDED.user_display_name,
+                    ms_id = EXCLUDED.ms_id,
+                    assigned = EXCLUDED.assigned,
+                    is_admin = EXCLUDED.is_admin,
+                    is_deleted = EXCLUDED.is_deleted,
+                    last_seen = NOW(),
+                    unassigned_at = CASE
+                        WHEN EXCLUDED.assigned THEN NULL
+                        ELSE COALESCE(spanning_backups.unassigned_at, NOW())
+                    END
+                RETURNING id, (xmax = 0) AS "inserted!";
+            "#,
+            backup.user_principal_name.unwrap_or("N/A".to_string()),
+            backup.user_display_name.unwrap_or("N/A".to_string()),
+            backup.email.unwrap_or("N/A".to_string()),
+            backup.ms_id.unwrap_or("N/A".to_string()),
+            backup.assigned.unwrap_or(false),
+            backup.is_admin.unwrap_or(false),
+            backup.is_deleted.unwrap_or(false),
+            company_name,
+            platform
+        )
+        .fetch_one(pool)
+        .await;
+
+        let user = match user {
+            Ok(user) => user,
+            Err(error) => {
+                println!("Failed to upsert Spanning backup: {}", error);
+
+                result.skipped += 1;
+
+                continue;
+            }
+        };
+
+        if user.inserted {
+            result.inserted += 1;
+        } else {
+            result.updated += 1;
+        }
+
+        for backup_summary in backup.backup_summary.unwrap_or_default() {
+            let (Some(date), Some(backup_type)) = (backup_summary.date, backup_summary.backup_type)
+            else {
+                continue;
+            };
+
+            let summary_backup = backup_summary.backup;
+            let summary_data = summary_backup
+                .as_ref()
+                .and_then(|backup| backup.data.as_ref());
+
+            let summary = sqlx::query!(
+                r#"
+                    INSERT INTO spanning_backups_summaries (
+                        backup,
+                        date,
+                        backup_type,
+                        total,
+                        partial,
+                        failed,
+                        successful,
+                        data_created,
+                        data_deleted,
+                        data_failed,
+                        data_total,
+                        data_attempts
+                    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                    ON CONFLICT (backup, date, backup_type) DO UPDATE SET
+                        total = EXCLUDED.total,
+                        partial = EXCLUDED.partial,
+                        failed = EXCLUDED.failed,
+                        successful = EXCLUDED.successful,
+                        data_created = EXCLUDED.data_created,
+                        data_deleted = EXCLUDED.data_deleted,
+                        data_failed = EXCLUDED.data_failed,
+                        data_total = EXCLUDED.data_total,
+                        data_attempts = EXCLUDED.data_attempts
+                    RETURNING (xmax = 0) AS "inserted!";
+                "#,
+                user.id,
+                date,
+                backup_type,
+                summary_backup
+                    .as_ref()
+                    .and_then(|backup| backup.total.clone())
+                    .unwrap_or("0".to_string()),
+                summary_backup
+                    .as_ref()
+                    .and_then(|backup| backup.partial)
+                    .unwrap_or(0),
+                summary_backup
+                    .as_ref()
+                    .and_then(|backup| backup.failed)
+                    .unwrap_or(0),
+                summary_backup
+                    .as_ref()
+                    .and_then(|backup| backup.successful)
+                    .unwrap_or(0),
+                summary_data.and_then(|data| data.created).unwrap_or(0),
+                summary_data.and_then(|data| data.deleted).unwrap_or(0),
+                summary_data.and_then(|data| data.failed).unwrap_or(0),
+                summary_data.and_then(|data| data.total).unwrap_or(0),
+                summary_data.and_then(|data| data.attempts).unwrap_or(0)
+            )
+            .fetch_one(pool)
+            .await;
+
+            match summary {
+                Ok(summary) if summary.inserted => result.summaries_inserted += 1,
+                Ok(_) => result.summaries_updated += 1,
+                Err(error) => println!("Failed to upsert Spanning backup summary: {}", error),
+            }
+        }
+    }
+
+    let (company_names, platforms): (Vec<String>, Vec<String>) =
+        synced_companies.into_iter().unzip();
+
+    // Users Spanning no longer returns for a synced company have lost their license.
+    let unassigned = sqlx::query!(
+        r#"
+            UPDATE spanning_backups SET
+                assigned = false,
+                unassigned_at = COALESCE(unassigned_at, NOW())
+            WHERE (company_name, platform) IN (SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[]))
+            AND last_seen < $3
+            AND unassigned_at IS NULL;
+        "#,
+        &company_names,
+        &platforms,
+        synced_at
+    )
+    .execute(pool)
+    .await?;
+
+    result.unassigned = unassigned.rows_affected() as i64;
+
+    Ok(result)
+}
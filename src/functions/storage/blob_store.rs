@@ -0,0 +1,109 @@
+use std::{env, sync::OnceLock};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use dotenv::dotenv;
+
+use super::{local_blob_store::LocalBlobStore, s3_blob_store::S3BlobStore};
+
+static BLOB_STORE: OnceLock<Box<dyn BlobStore>> = OnceLock::new();
+
+/**
+Storage for generated reports, scan results and uploaded tenant assets.
+
+Keys are relative `/` separated paths such as `reports/report-{tenant}-{date}.json`, validated by
+`validate_blob_key` so a key can never point outside the store.
+*/
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<()>;
+    /// `None` when nothing is stored under `key`.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    async fn exists(&self, key: &str) -> Result<bool>;
+    /// Deleting a missing key is not an error.
+    async fn delete(&self, key: &str) -> Result<()>;
+    /// Keys directly inside `directory` (not nested deeper), e.g. `list("reports")`.
+    async fn list(&self, directory: &str) -> Result<Vec<String>>;
+}
+
+/**
+The configured blob store.
+
+`BLOB_STORE=s3` stores blobs in an S3 compatible bucket (AWS, MinIO, ...) configured by the `S3_*`
+variables, anything else stores them on disk under `BLOB_STORE_PATH` (the working directory by
+default).
+*/
+pub fn blob_store() -> &'static dyn BlobStore {
+    BLOB_STORE.get_or_init(load_blob_store).as_ref()
+}
+
+fn load_blob_store() -> Box<dyn BlobStore> {
+    dotenv().ok();
+
+    match env::var("BLOB_STORE").as_deref() {
+        Ok("s3") => {
+            Box::new(S3BlobStore::from_env().expect("Invalid S3 blob store configuration."))
+        }
+        _ => Box::new(LocalBlobStore::new(
+            env::var("BLOB_STORE_PATH").unwrap_or_else(|_| ".".into()),
+        )),
+    }
+}
+
+/// Reject keys that are absolute, empty or contain `.`/`..` segments.
+pub fn validate_blob_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && !key.contains('\\')
+        && !key.contains('\0')
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("Invalid blob key: {}", key))
+    }
+}
+
+/// File name part of a key, `reports/report-a.json` becomes `report-a.json`.
+pub fn blob_file_name(key: &str) -> &str {
+    key.rsplit('/').next().unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_relative_keys_only() {
+        for key in [
+            "reports/report-Tenant & Co-2023-12-11.json",
+            "uploads/9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+            "report.json",
+            "reports/..json",
+        ] {
+            assert!(validate_blob_key(key).is_ok(), "{}", key);
+        }
+
+        for key in [
+            "",
+            "../x",
+            "reports/../../x",
+            "/etc/passwd",
+            "a//b",
+            "a/./b",
+            "reports/",
+            r"reports\..\x",
+            "reports/a\0.json",
+        ] {
+            assert!(validate_blob_key(key).is_err(), "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn takes_the_file_name_from_a_key() {
+        assert_eq!(blob_file_name("reports/report-a.json"), "report-a.json");
+        assert_eq!(blob_file_name("report-a.json"), "report-a.json");
+    }
+}
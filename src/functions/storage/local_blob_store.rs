@@ -0,0 +1,152 @@
+use std::{io::ErrorKind, path::PathBuf};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::fs::{create_dir_all, read, read_dir, remove_file, try_exists, write};
+
+use super::blob_store::{validate_blob_key, BlobStore};
+
+/// Blobs stored as files under a root directory, the key is the path relative to the root.
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalBlobStore { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        validate_blob_key(key)?;
+
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, data: Vec<u8>, _content_type: &str) -> Result<()> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).await?;
+        }
+
+        write(path, data).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match read(self.path(key)?).await {
+            Ok(data) => Ok(Some(data)),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(try_exists(self.path(key)?).await?)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match remove_file(self.path(key)?).await {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn list(&self, directory: &str) -> Result<Vec<String>> {
+        let mut dir = match read_dir(self.path(directory)?).await {
+            Ok(dir) => dir,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut keys: Vec<String> = Vec::new();
+
+        while let Some(entry) = dir.next_entry().await? {
+            if !entry.file_type().await?.is_file() {
+                continue;
+            }
+
+            if let Some(file_name) = entry.file_name().to_str() {
+                keys.push(format!("{}/{}", directory, file_name));
+            }
+        }
+
+        keys.sort();
+
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trips_blobs_on_disk() {
+        let root = tempfile::tempdir().unwrap();
+        let store = LocalBlobStore::new(root.path().join("blobs"));
+        let key = "reports/report-Tenant & Co-2023-12-11.json";
+
+        assert!(!store.exists(key).await.unwrap());
+        assert_eq!(store.get(key).await.unwrap(), None);
+        assert_eq!(store.list("reports").await.unwrap(), Vec::<String>::new());
+        store.delete(key).await.unwrap();
+
+        store
+            .put(key, b"{}".to_vec(), "application/json")
+            .await
+            .unwrap();
+        store
+            .put(
+                "reports/nested/report.json",
+                b"[]".to_vec(),
+                "application/json",
+            )
+            .await
+            .unwrap();
+
+        assert!(store.exists(key).await.unwrap());
+        assert_eq!(store.get(key).await.unwrap(), Some(b"{}".to_vec()));
+        assert_eq!(
+            std::fs::read(root.path().join("blobs").join(key)).unwrap(),
+            b"{}"
+        );
+        assert_eq!(store.list("reports").await.unwrap(), vec![key.to_string()]);
+
+        store
+            .put(key, b"{\"total\": 1}".to_vec(), "application/json")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get(key).await.unwrap(),
+            Some(b"{\"total\": 1}".to_vec())
+        );
+
+        store.delete(key).await.unwrap();
+
+        assert!(!store.exists(key).await.unwrap());
+        assert_eq!(store.get(key).await.unwrap(), None);
+        assert!(store.list("reports").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_keys_outside_the_root() {
+        let root = tempfile::tempdir().unwrap();
+        let store = LocalBlobStore::new(root.path().join("blobs"));
+
+        assert!(store
+            .put("../escape", b"x".to_vec(), "text/plain")
+            .await
+            .is_err());
+        assert!(store.get("/etc/passwd").await.is_err());
+        assert!(store.exists("a/./b").await.is_err());
+        assert!(store.delete("a//b").await.is_err());
+        assert!(store.list("..").await.is_err());
+        assert!(!root.path().join("escape").exists());
+    }
+}
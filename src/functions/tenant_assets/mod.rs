@@ -0,0 +1,2 @@
+pub mod tenant_asset_image;
+pub mod tenant_asset_store;
@@ -0,0 +1,162 @@
+use std::io::Cursor;
+
+use image::{
+    imageops::FilterType, metadata::Orientation, DynamicImage, ImageDecoder, ImageFormat,
+    ImageReader, Limits,
+};
+
+/// Variants rendered for every uploaded image, with the longest side each is resized to fit.
+pub const TENANT_IMAGE_VARIANTS: [(&str, u32); 2] = [("full", 1024), ("thumbnail", 256)];
+/// Largest image accepted per side, so a small file can't decode into a huge bitmap.
+const MAX_DECODED_IMAGE_DIMENSION: u32 = 12_000;
+const MAX_DECODED_IMAGE_BYTES: u64 = 512 * 1024 * 1024;
+
+pub struct TenantImageVariant {
+    pub variant: &'static str,
+    pub mime_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/**
+Decode an uploaded image and render the variants stored for it.
+
+Every variant is a PNG, which report PDFs can embed directly, resized to fit its maximum dimension
+after applying the EXIF orientation. Images are never upscaled. Decoding is CPU bound, call this
+from a blocking task.
+*/
+pub fn process_tenant_image(data: &[u8]) -> Result<Vec<TenantImageVariant>, String> {
+    let invalid_image =
+        |error: image::ImageError| format!("The uploaded file is not a valid image: {}", error);
+
+    let mut reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|error| error.to_string())?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DECODED_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_DECODED_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODED_IMAGE_BYTES);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(invalid_image)?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+
+    let mut image = DynamicImage::from_decoder(decoder).map_err(invalid_image)?;
+    image.apply_orientation(orientation);
+
+    TENANT_IMAGE_VARIANTS
+        .iter()
+        .map(|(variant, max_dimension)| {
+            let resized = if image.width() > *max_dimension || image.height() > *max_dimension {
+                image.resize(*max_dimension, *max_dimension, FilterType::Lanczos3)
+            } else {
+                image.clone()
+            };
+
+            let mut png: Vec<u8> = Vec::new();
+
+            resized
+                .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+                .map_err(|error| format!("Failed to encode the {} image: {}", variant, error))?;
+
+            Ok(TenantImageVariant {
+                variant,
+                mime_type: "image/png",
+                width: resized.width(),
+                height: resized.height(),
+                data: png,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GenericImageView, RgbImage};
+
+    use super::*;
+
+    fn encode(image: RgbImage, format: ImageFormat) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::new();
+
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut Cursor::new(&mut data), format)
+            .unwrap();
+
+        data
+    }
+
+    fn dimensions(variants: &[TenantImageVariant]) -> Vec<(&str, u32, u32)> {
+        variants
+            .iter()
+            .map(|variant| (variant.variant, variant.width, variant.height))
+            .collect()
+    }
+
+    #[test]
+    fn renders_full_and_thumbnail_variants() {
+        let variants =
+            process_tenant_image(&encode(RgbImage::new(1600, 800), ImageFormat::Png)).unwrap();
+
+        assert_eq!(
+            dimensions(&variants),
+            vec![("full", 1024, 512), ("thumbnail", 256, 128)]
+        );
+
+        for variant in &variants {
+            let decoded = image::load_from_memory(&variant.data).unwrap();
+
+            assert_eq!(variant.mime_type, "image/png");
+            assert_eq!(decoded.dimensions(), (variant.width, variant.height));
+        }
+    }
+
+    #[test]
+    fn does_not_upscale_small_images() {
+        let variants =
+            process_tenant_image(&encode(RgbImage::new(300, 120), ImageFormat::Jpeg)).unwrap();
+
+        assert_eq!(
+            dimensions(&variants),
+            vec![("full", 300, 120), ("thumbnail", 256, 102)]
+        );
+    }
+
+    #[test]
+    fn applies_the_exif_orientation() {
+        let jpeg = encode(RgbImage::new(400, 200), ImageFormat::Jpeg);
+
+        // APP1 segment with a big endian TIFF IFD holding only Orientation = 6 (rotate 90 clockwise).
+        let mut exif: Vec<u8> = vec![0xFF, 0xE1, 0x00, 0x22];
+        exif.extend_from_slice(b"Exif\0\0MM\0\x2a\0\0\0\x08");
+        exif.extend_from_slice(&[0x00, 0x01, 0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01]);
+        exif.extend_from_slice(&[0x00, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+        let mut rotated = jpeg[..2].to_vec();
+        rotated.extend(exif);
+        rotated.extend_from_slice(&jpeg[2..]);
+
+        let variants = process_tenant_image(&rotated).unwrap();
+
+        assert_eq!(
+            dimensions(&variants),
+            vec![("full", 200, 400), ("thumbnail", 128, 256)]
+        );
+    }
+
+    #[test]
+    fn rejects_images_beyond_the_decode_limit() {
+        let data = encode(
+            RgbImage::new(MAX_DECODED_IMAGE_DIMENSION + 1, 1),
+            ImageFormat::Png,
+        );
+
+        assert!(process_tenant_image(&data)
+            .err()
+            .unwrap()
+            .starts_with("The uploaded file is not a valid image"));
+        assert!(process_tenant_image(b"not an image").is_err());
+    }
+}
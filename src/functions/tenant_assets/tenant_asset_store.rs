@@ -0,0 +1,299 @@
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use super::tenant_asset_image::TenantImageVariant;
+use crate::{
+    functions::storage::blob_store::blob_store, models::tenant::tenant_asset::TenantAsset,
+};
+
+/// Blob store directory of the assets, each blob is named after the sha256 of its contents.
+pub const TENANT_ASSETS_DIRECTORY: &str = "uploads";
+/// Kind the tenant logo is uploaded as, the multipart field name.
+pub const TENANT_LOGO_KIND: &str = "logo";
+/// Largest asset accepted on upload, images are downscaled before they are stored.
+pub const MAX_TENANT_ASSET_BYTES: usize = 20 * 1024 * 1024;
+/// Longest asset kind (the multipart field name) accepted on upload.
+const MAX_TENANT_ASSET_KIND_LENGTH: usize = 64;
+
+/**
+Detect the image type of an upload from its magic bytes.
+
+Only raster formats are accepted, SVG can carry script and the client supplied content type is not
+trusted.
+*/
+pub fn sniff_image_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some("image/png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Check an upload before it is stored, returning its MIME type or why it was rejected.
+pub fn validate_tenant_asset(kind: &str, data: &[u8]) -> Result<&'static str, String> {
+    let valid_kind = !kind.is_empty()
+        && kind.len() <= MAX_TENANT_ASSET_KIND_LENGTH
+        && kind.chars().all(|character| {
+            character.is_ascii_alphanumeric() || character == '-' || character == '_'
+        });
+
+    if !valid_kind {
+        return Err(format!(
+            "Invalid asset kind '{}', use up to {} letters, digits, '-' or '_'.",
+            kind, MAX_TENANT_ASSET_KIND_LENGTH
+        ));
+    }
+
+    if data.is_empty() {
+        return Err("The uploaded file is empty.".to_string());
+    }
+
+    if data.len() > MAX_TENANT_ASSET_BYTES {
+        return Err(format!(
+            "The uploaded file is larger than {} bytes.",
+            MAX_TENANT_ASSET_BYTES
+        ));
+    }
+
+    sniff_image_mime_type(data)
+        .ok_or_else(|| "Only PNG, JPEG, GIF and WebP images are supported.".to_string())
+}
+
+/// Blob store key of an asset. Only ever built from a sha256 hex digest, never from request input.
+pub fn tenant_asset_key(sha256: &str) -> Result<String> {
+    if sha256.len() != 64
+        || !sha256
+            .chars()
+            .all(|character| character.is_ascii_hexdigit())
+    {
+        return Err(anyhow!("Invalid tenant asset digest: {}", sha256));
+    }
+
+    Ok(format!("{}/{}", TENANT_ASSETS_DIRECTORY, sha256))
+}
+
+/**
+Store the processed variants of an uploaded image, replacing any earlier variants of the same kind.
+
+Blobs are content addressed, so identical variants share one file. Blobs of replaced variants are
+removed once no other asset references them.
+*/
+pub async fn store_tenant_asset(
+    pool: &PgPool,
+    tenant_id: i32,
+    kind: &str,
+    file_name: &str,
+    variants: &[TenantImageVariant],
+) -> Result<Vec<TenantAsset>> {
+    let mut transaction = pool.begin().await?;
+
+    let previous_sha256s = sqlx::query_scalar!(
+        "SELECT sha256 FROM tenant_assets WHERE tenant_id = $1 AND kind = $2;",
+        tenant_id,
+        kind
+    )
+    .fetch_all(&mut *transaction)
+    .await?;
+
+    let mut assets: Vec<TenantAsset> = Vec::new();
+
+    for variant in variants {
+        let sha256 = hex::encode(Sha256::digest(&variant.data));
+        let key = tenant_asset_key(&sha256)?;
+
+        if !blob_store().exists(&key).await? {
+            blob_store()
+                .put(&key, variant.data.clone(), variant.mime_type)
+                .await?;
+        }
+
+        let asset = sqlx::query_as!(
+            TenantAsset,
+            r#"
+                INSERT INTO tenant_assets (tenant_id, kind, variant, file_name, mime_type, size, width, height, sha256)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (tenant_id, kind, variant) DO UPDATE SET
+                    file_name = EXCLUDED.file_name,
+                    mime_type = EXCLUDED.mime_type,
+                    size = EXCLUDED.size,
+                    width = EXCLUDED.width,
+                    height = EXCLUDED.height,
+                    sha256 = EXCLUDED.sha256,
+                    created_at = NOW()
+                RETURNING *;
+            "#,
+            tenant_id,
+            kind,
+            variant.variant,
+            file_name,
+            variant.mime_type,
+            variant.data.len() as i64,
+            variant.width as i32,
+            variant.height as i32,
+            sha256
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        assets.push(asset);
+    }
+
+    let variant_names: Vec<String> = variants
+        .iter()
+        .map(|variant| variant.variant.to_string())
+        .collect();
+
+    // Variants no longer rendered, such as assets stored before images were processed.
+    sqlx::query!(
+        "DELETE FROM tenant_assets WHERE tenant_id = $1 AND kind = $2 AND variant <> ALL($3);",
+        tenant_id,
+        kind,
+        &variant_names
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    for previous_sha256 in previous_sha256s {
+        if !assets.iter().any(|asset| asset.sha256 == previous_sha256) {
+            remove_unreferenced_blob(pool, &previous_sha256).await?;
+        }
+    }
+
+    Ok(assets)
+}
+
+/**
+Delete the asset with `id` together with the other variants of its tenant and kind, so removing
+the thumbnail also removes the full image. Returns the deleted variants, empty when there was no
+such asset.
+*/
+pub async fn remove_tenant_asset(pool: &PgPool, id: i32) -> Result<Vec<TenantAsset>> {
+    let assets = sqlx::query_as!(
+        TenantAsset,
+        r#"
+            DELETE FROM tenant_assets
+            WHERE (tenant_id, kind) = (SELECT tenant_id, kind FROM tenant_assets WHERE id = $1)
+            RETURNING *;
+        "#,
+        id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut sha256s: Vec<&str> = assets.iter().map(|asset| asset.sha256.as_str()).collect();
+
+    sha256s.sort();
+    sha256s.dedup();
+
+    for sha256 in sha256s {
+        remove_unreferenced_blob(pool, sha256).await?;
+    }
+
+    Ok(assets)
+}
+
+async fn remove_unreferenced_blob(pool: &PgPool, sha256: &str) -> Result<()> {
+    let references = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM tenant_assets WHERE sha256 = $1;"#,
+        sha256
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if references == 0 {
+        blob_store().delete(&tenant_asset_key(sha256)?).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = &[
+        0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0x0D,
+    ];
+
+    #[test]
+    fn sniffs_raster_images_only() {
+        assert_eq!(sniff_image_mime_type(PNG), Some("image/png"));
+        assert_eq!(
+            sniff_image_mime_type(&[0xFF, 0xD8, 0xFF, 0xE0, 0, 0x10]),
+            Some("image/jpeg")
+        );
+        assert_eq!(sniff_image_mime_type(b"GIF89a\x01\x00"), Some("image/gif"));
+        assert_eq!(sniff_image_mime_type(b"GIF87a\x01\x00"), Some("image/gif"));
+        assert_eq!(
+            sniff_image_mime_type(b"RIFF\x24\x00\x00\x00WEBPVP8 "),
+            Some("image/webp")
+        );
+
+        assert_eq!(
+            sniff_image_mime_type(b"<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>"),
+            None
+        );
+        assert_eq!(
+            sniff_image_mime_type(b"<?xml version=\"1.0\"?><svg></svg>"),
+            None
+        );
+        assert_eq!(
+            sniff_image_mime_type(b"<!DOCTYPE html><script>alert(1)</script>"),
+            None
+        );
+        assert_eq!(sniff_image_mime_type(b"RIFF\x24\x00\x00\x00WAVE"), None);
+        assert_eq!(sniff_image_mime_type(b""), None);
+    }
+
+    #[test]
+    fn validates_uploads() {
+        assert_eq!(validate_tenant_asset("logo", PNG), Ok("image/png"));
+        assert_eq!(
+            validate_tenant_asset("report-header_2", PNG),
+            Ok("image/png")
+        );
+
+        for kind in ["../logo", "logo/../../etc", "..", "", "logo.png", "a b"] {
+            assert!(validate_tenant_asset(kind, PNG).is_err(), "{}", kind);
+        }
+
+        assert!(validate_tenant_asset(&"a".repeat(65), PNG).is_err());
+        assert_eq!(
+            validate_tenant_asset("logo", b""),
+            Err("The uploaded file is empty.".to_string())
+        );
+
+        let mut oversize = PNG.to_vec();
+        oversize.resize(MAX_TENANT_ASSET_BYTES + 1, 0);
+
+        assert!(validate_tenant_asset("logo", &oversize)
+            .unwrap_err()
+            .contains("larger than"));
+        assert!(validate_tenant_asset("logo", b"<svg></svg>").is_err());
+    }
+
+    #[test]
+    fn builds_keys_from_sha256_digests_only() {
+        let sha256 = hex::encode(Sha256::digest(b"logo"));
+
+        assert_eq!(
+            tenant_asset_key(&sha256).unwrap(),
+            format!("uploads/{}", sha256)
+        );
+
+        assert!(tenant_asset_key(&sha256[..63]).is_err());
+        assert!(tenant_asset_key(&format!("{}0", sha256)).is_err());
+        assert!(tenant_asset_key(&format!("../{}", &sha256[3..])).is_err());
+        assert!(tenant_asset_key(&"g".repeat(64)).is_err());
+        assert!(tenant_asset_key("").is_err());
+    }
+}
@@ -25,5 +25,8 @@ dotenv().ok();
 */
 pub mod veeam_agents;
 pub mod veeam_agents_jobs;
+pub mod veeam_organizations;
+pub mod veeam_paging;
 pub mod veeam_servers;
 pub mod veeam_servers_jobs;
+
@@ -3,10 +3,14 @@ use std::{collections::HashMap, time::Duration};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use dotenv::dotenv;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgPoolOptions;
 
+use super::{
+    veeam_organizations::get_veeam_organizations,
+    veeam_paging::{get_veeam_paged, veeam_client},
+};
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Tenant {
     pub id: i64,
@@ -45,38 +49,6 @@ pub struct VeeamAgentJob {
     pub company_name: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct VeeamPagingInfo {
-    pub total: i32,
-    pub count: i32,
-    pub offset: i32,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all(deserialize = "camelCase", serialize = "camelCase"))]
-pub struct VeeamMeta {
-    pub paging_info: VeeamPagingInfo,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct VeeamAgentsJobsResponse {
-    pub data: Vec<VeeamAgentJob>,
-    pub meta: VeeamMeta,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all(deserialize = "camelCase", serialize = "camelCase"))]
-pub struct VeeamOrganization {
-    pub instance_uid: Option<String>,
-    pub name: Option<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct VeeamOrganizationsResponse {
-    pub data: Vec<VeeamOrganization>,
-    pub meta: VeeamMeta,
-}
-
 pub async fn veeam_agents_jobs() -> Result<Vec<VeeamAgentJob>> {
     dotenv().ok();
 
@@ -101,92 +73,67 @@ pub async fn veeam_agents_jobs() -> Result<Vec<VeeamAgentJob>> {
     let mut veeam_agent_jobs: Vec<VeeamAgentJob> = Vec::new();
 
     for tenant in tenants {
-        let veeam_organizations = get_veeam_organizations(
-            format!("{}/organizations", tenant.veeam_url.clone().unwrap()),
-            tenant.tenant_name.clone(),
-            tenant.veeam_key.clone().unwrap(),
-        )
-        .await
-        .expect("Failed to get Veeam Organizations.");
-
-        let mut organizations_map: HashMap<String, String> = HashMap::new();
-
-        for organization in veeam_organizations {
-            organizations_map.insert(
-                organization.instance_uid.clone().unwrap(),
-                organization.name.clone().unwrap(),
-            );
-        }
+        let veeam_url = tenant.veeam_url.unwrap();
+        let veeam_key = tenant.veeam_key.unwrap();
 
-        let veeam_windows_jobs = get_veeam_agents_jobs(
-            format!(
-                "{}/infrastructure/backupAgents/windows/jobs",
-                tenant.veeam_url.clone().unwrap()
-            ),
+        let veeam_organizations = get_veeam_organizations(
+            format!("{}/organizations", veeam_url),
             tenant.tenant_name.clone(),
-            tenant.veeam_key.clone().unwrap(),
-            "Windows".to_string(),
+            veeam_key.clone(),
         )
-        .await
-        .expect("Failed to get Veeam Windows Jobs.");
+        .await;
 
-        for windows_job in veeam_windows_jobs {
-            let mut new_backup = windows_job;
+        let veeam_organizations = match veeam_organizations {
+            Ok(veeam_organizations) => veeam_organizations,
+            Err(error) => {
+                println!("Failed to get Veeam Organizations: {:?}", error);
 
-            new_backup.company_name = Some(
-                organizations_map
-                    .get(new_backup.organization_uid.clone().unwrap().as_str())
-                    .unwrap_or(&tenant.tenant_name.clone())
-                    .clone(),
-            );
+                continue;
+            }
+        };
 
-            veeam_agent_jobs.push(new_backup);
-        }
-
-        let veeam_linux_jobs = get_veeam_agents_jobs(
-            "https://veeam.thusacloud.co.za:1280/api/v3/infrastructure/backupAgents/linux/jobs"
-                .to_string(),
-            tenant.tenant_name.clone(),
-            tenant.veeam_key.clone().unwrap(),
-            "Linux".to_string(),
-        )
-        .await
-        .expect("Failed to get Veeam Linux Jobs.");
-
-        for linux_job in veeam_linux_jobs {
-            let mut new_backup = linux_job;
-
-            new_backup.company_name = Some(
-                organizations_map
-                    .get(new_backup.organization_uid.clone().unwrap().as_str())
-                    .unwrap()
-                    .clone(),
-            );
+        let mut organizations_map: HashMap<String, String> = HashMap::new();
 
-            veeam_agent_jobs.push(new_backup);
+        for organization in veeam_organizations {
+            if let (Some(instance_uid), Some(name)) = (organization.instance_uid, organization.name)
+            {
+                organizations_map.insert(instance_uid, name);
+            }
         }
 
-        let veeam_mac_jobs = get_veeam_agents_jobs(
-            "https://veeam.thusacloud.co.za:1280/api/v3/infrastructure/backupAgents/mac/jobs"
-                .to_string(),
-            tenant.tenant_name.clone(),
-            tenant.veeam_key.clone().unwrap(),
-            "Mac".to_string(),
-        )
-        .await
-        .expect("Failed to get Veeam Mac Jobs.");
-
-        for mac_job in veeam_mac_jobs {
-            let mut new_backup = mac_job;
-
-            new_backup.company_name = Some(
-                organizations_map
-                    .get(new_backup.organization_uid.clone().unwrap().as_str())
-                    .unwrap()
-                    .clone(),
-            );
-
-            veeam_agent_jobs.push(new_backup);
+        for (job_path, job_platform) in [("windows", "Windows"), ("linux", "Linux"), ("mac", "Mac")]
+        {
+            let platform_jobs = get_veeam_agents_jobs(
+                format!(
+                    "{}/infrastructure/backupAgents/{}/jobs",
+                    veeam_url, job_path
+                ),
+                tenant.tenant_name.clone(),
+                veeam_key.clone(),
+                job_platform.to_string(),
+            )
+            .await;
+
+            let platform_jobs = match platform_jobs {
+                Ok(platform_jobs) => platform_jobs,
+                Err(error) => {
+                    println!("Failed to get Veeam {} Jobs: {:?}", job_platform, error);
+
+                    continue;
+                }
+            };
+
+            for mut job in platform_jobs {
+                job.company_name = Some(
+                    job.organization_uid
+                        .as_ref()
+                        .and_then(|organization_uid| organizations_map.get(organization_uid))
+                        .unwrap_or(&tenant.tenant_name)
+                        .clone(),
+                );
+
+                veeam_agent_jobs.push(job);
+            }
         }
     }
 
@@ -195,31 +142,6 @@ pub async fn veeam_agents_jobs() -> Result<Vec<VeeamAgentJob>> {
     Ok(veeam_agent_jobs)
 }
 
-pub async fn get_veeam_organizations(
-    url: String,
-    tenant_name: String,
-    veeam_key: String,
-) -> Result<Vec<VeeamOrganization>> {
-    println!("Fetching Veeam Organizations for {}", tenant_name);
-
-    let client = Client::builder()
-        .http1_title_case_headers()
-        .build()
-        .expect("Failed to create reqwest client.");
-
-    let response = client
-        .get(url)
-        .header("x-api-version", "1.0-rev0")
-        .bearer_auth(&veeam_key)
-        .send()
-        .await
-        .expect("Failed to get response from Veeam Backup API");
-
-    let body = response.json::<VeeamOrganizationsResponse>().await.unwrap();
-
-    Ok(body.data)
-}
-
 pub async fn get_veeam_agents_jobs(
     url: String,
     tenant_name: String,
@@ -228,59 +150,5 @@ pub async fn get_veeam_agents_jobs(
 ) -> Result<Vec<VeeamAgentJob>> {
     println!("Fetching Veeam {} Jobs for {}", job_platform, tenant_name);
 
-    let initial_offset = 0;
-    let initial_limit = 1;
-
-    let client = Client::builder()
-        .http1_title_case_headers()
-        .build()
-        .expect("Failed to create reqwest client.");
-
-    let query_url = url.clone();
-
-    let response = client
-        .get(format!(
-            "{}?offset={}limit={}",
-            query_url, initial_offset, initial_limit
-        ))
-        .header("x-api-version", "1.0-rev0")
-        .bearer_auth(&veeam_key)
-        .send()
-        .await
-        .expect("Failed to get response from Veeam Backup API");
-
-    let body = response.json::<VeeamAgentsJobsResponse>().await.unwrap();
-
-    let remote_offset = body.meta.paging_info.offset;
-    let remote_limit = 100;
-    let remote_total = body.meta.paging_info.total;
-
-    let pages = (remote_total as f32 / remote_limit as f32).ceil() as i32;
-
-    println!("Pages: {}", pages);
-
-    let mut veeam_agents_jobs: Vec<VeeamAgentJob> = Vec::new();
-
-    for page in 0..pages {
-        let response = client
-            .get(format!(
-                "{}?offset={}limit={}",
-                query_url,
-                remote_offset + page * remote_limit,
-                remote_limit
-            ))
-            .header("x-api-version", "1.0-rev0")
-            .bearer_auth(&veeam_key)
-            .send()
-            .await
-            .expect("Failed to get response from Veeam Backup API");
-
-        let body = response.json::<VeeamAgentsJobsResponse>().await.unwrap();
-
-        println!("Page: {}", page);
-
-        veeam_agents_jobs.extend(body.data);
-    }
-
-    Ok(veeam_agents_jobs)
+    get_veeam_paged(&veeam_client(), &url, &veeam_key).await
 }
@@ -0,0 +1,21 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::veeam_paging::{get_veeam_paged, veeam_client};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase", serialize = "camelCase"))]
+pub struct VeeamOrganization {
+    pub instance_uid: Option<String>,
+    pub name: Option<String>,
+}
+
+pub async fn get_veeam_organizations(
+    url: String,
+    tenant_name: String,
+    veeam_key: String,
+) -> Result<Vec<VeeamOrganization>> {
+    println!("Fetching Veeam Organizations for {}", tenant_name);
+
+    get_veeam_paged(&veeam_client(), &url, &veeam_key).await
+}
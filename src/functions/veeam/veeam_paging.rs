@@ -0,0 +1,261 @@
+use std::time::Duration;
+
+use anyhow::{Error, Result};
+use reqwest::Client;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::time::sleep;
+
+pub const VEEAM_PAGE_LIMIT: usize = 100;
+pub const VEEAM_PAGE_ATTEMPTS: u32 = 3;
+pub const VEEAM_PAGE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VeeamPagingInfo {
+    pub total: i32,
+    pub count: i32,
+    pub offset: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "camelCase", serialize = "camelCase"))]
+pub struct VeeamMeta {
+    pub paging_info: VeeamPagingInfo,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VeeamPagedResponse<T> {
+    pub data: Vec<T>,
+    pub meta: VeeamMeta,
+}
+
+pub fn veeam_client() -> Client {
+    Client::builder()
+        .http1_title_case_headers()
+        .build()
+        .expect("Failed to create reqwest client.")
+}
+
+/**
+Fetch every page of a Veeam Service Provider Console collection endpoint.
+
+Pages are requested with `offset` and `limit` until `meta.pagingInfo.total` items have been
+received, and each page is retried with an exponential backoff before giving up.
+*/
+pub async fn get_veeam_paged<T: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    veeam_key: &str,
+) -> Result<Vec<T>> {
+    let mut items: Vec<T> = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let page = get_veeam_page::<T>(client, url, veeam_key, offset).await?;
+
+        let received = page.data.len();
+        let total = page.meta.paging_info.total.max(0) as usize;
+
+        items.extend(page.data);
+        offset += received;
+
+        if received == 0 || offset >= total {
+            break;
+        }
+    }
+
+    Ok(items)
+}
+
+async fn get_veeam_page<T: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    veeam_key: &str,
+    offset: usize,
+) -> Result<VeeamPagedResponse<T>> {
+    let mut attempt = 1;
+
+    loop {
+        let result = request_veeam_page::<T>(client, url, veeam_key, offset).await;
+
+        match result {
+            Ok(page) => return Ok(page),
+            Err(error) if attempt < VEEAM_PAGE_ATTEMPTS => {
+                println!(
+                    "Failed to get Veeam page at offset {} (attempt {}/{}): {}",
+                    offset, attempt, VEEAM_PAGE_ATTEMPTS, error
+                );
+
+                sleep(VEEAM_PAGE_RETRY_DELAY * 2u32.pow(attempt - 1)).await;
+
+                attempt += 1;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+async fn request_veeam_page<T: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    veeam_key: &str,
+    offset: usize,
+) -> Result<VeeamPagedResponse<T>, Error> {
+    let response = client
+        .get(url)
+        .query(&[("offset", offset), ("limit", VEEAM_PAGE_LIMIT)])
+        .header("x-api-version", "1.0-rev0")
+        .bearer_auth(veeam_key)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let body = response.json::<VeeamPagedResponse<T>>().await?;
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use axum::{
+        extract::{Query, State},
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::get,
+        Json, Router,
+    };
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    const MOCK_TOTAL: usize = 250;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct MockAgent {
+        instance_uid: String,
+    }
+
+    #[derive(Default)]
+    struct MockVeeam {
+        requests: AtomicUsize,
+        failures_remaining: AtomicUsize,
+        fail_at_offset: usize,
+    }
+
+    async fn mock_agents(
+        State(mock): State<Arc<MockVeeam>>,
+        headers: HeaderMap,
+        Query(query): Query<HashMap<String, String>>,
+    ) -> impl IntoResponse {
+        mock.requests.fetch_add(1, Ordering::SeqCst);
+
+        if headers.get("authorization").and_then(|v| v.to_str().ok()) != Some("Bearer test-key") {
+            return (StatusCode::UNAUTHORIZED, Json(json!({}))).into_response();
+        }
+
+        let (Some(offset), Some(limit)) = (
+            query.get("offset").and_then(|v| v.parse::<usize>().ok()),
+            query.get("limit").and_then(|v| v.parse::<usize>().ok()),
+        ) else {
+            return (StatusCode::BAD_REQUEST, Json(json!({}))).into_response();
+        };
+
+        if offset == mock.fail_at_offset
+            && mock
+                .failures_remaining
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+        {
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({}))).into_response();
+        }
+
+        let data: Vec<_> = (offset..MOCK_TOTAL.min(offset + limit))
+            .map(|index| json!({ "instanceUid": format!("agent-{}", index) }))
+            .collect();
+
+        Json(json!({
+            "data": data,
+            "meta": {
+                "pagingInfo": { "total": MOCK_TOTAL, "count": data.len(), "offset": offset }
+            }
+        }))
+        .into_response()
+    }
+
+    async fn spawn_mock_veeam(mock: Arc<MockVeeam>) -> String {
+        let app = Router::new()
+            .route("/infrastructure/backupAgents", get(mock_agents))
+            .with_state(mock);
+
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(app.into_make_service());
+        let address = server.local_addr();
+
+        tokio::spawn(server);
+
+        format!("http://{}/infrastructure/backupAgents", address)
+    }
+
+    #[tokio::test]
+    async fn fetches_every_page() {
+        let mock = Arc::new(MockVeeam {
+            fail_at_offset: usize::MAX,
+            ..Default::default()
+        });
+        let url = spawn_mock_veeam(mock.clone()).await;
+
+        let agents: Vec<MockAgent> = get_veeam_paged(&veeam_client(), &url, "test-key")
+            .await
+            .unwrap();
+
+        assert_eq!(agents.len(), MOCK_TOTAL);
+        assert_eq!(agents[0].instance_uid, "agent-0");
+        assert_eq!(agents[MOCK_TOTAL - 1].instance_uid, "agent-249");
+        assert_eq!(mock.requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_failed_pages() {
+        let mock = Arc::new(MockVeeam {
+            fail_at_offset: VEEAM_PAGE_LIMIT,
+            failures_remaining: AtomicUsize::new(1),
+            ..Default::default()
+        });
+        let url = spawn_mock_veeam(mock.clone()).await;
+
+        let agents: Vec<MockAgent> = get_veeam_paged(&veeam_client(), &url, "test-key")
+            .await
+            .unwrap();
+
+        assert_eq!(agents.len(), MOCK_TOTAL);
+        assert_eq!(mock.requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_repeated_failures() {
+        let mock = Arc::new(MockVeeam {
+            fail_at_offset: 0,
+            failures_remaining: AtomicUsize::new(usize::MAX),
+            ..Default::default()
+        });
+        let url = spawn_mock_veeam(mock.clone()).await;
+
+        let result = get_veeam_paged::<MockAgent>(&veeam_client(), &url, "test-key").await;
+
+        assert!(result.is_err());
+        assert_eq!(
+            mock.requests.load(Ordering::SeqCst),
+            VEEAM_PAGE_ATTEMPTS as usize
+        );
+    }
+}
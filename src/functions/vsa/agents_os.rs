@@ -0,0 +1,115 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::functions::os::classification::classify_os;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VsaOsProductCount {
+    pub product: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VsaOsStatistics {
+    pub agents: i64,
+    pub agents_with_anti_virus: i64,
+    pub win11_agents: i64,
+    pub win10_agents: i64,
+    pub win7_agents: i64,
+    pub server_agents: i64,
+    pub by_product: Vec<VsaOsProductCount>,
+}
+
+/**
+Store an agent's OS string and its classification.
+
+VSA splits the OS over `OperatingSystem` and `OSInfo`, both are classified together so the edition
+and build from one and the product from the other are not lost. Only `OSInfo` is kept as `os_name`.
+*/
+pub async fn store_agent_os(
+    pool: &PgPool,
+    agent_id: &str,
+    operating_system: Option<&str>,
+    os_info: Option<&str>,
+) -> Result<()> {
+    let os_string = [operating_system, os_info]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    let classification = classify_os(&os_string);
+
+    sqlx::query!(
+        r#"
+            UPDATE vsa_agents SET
+                os_name = COALESCE($2, os_name),
+                os_family = $3,
+                os_product = $4,
+                os_edition = $5,
+                os_version = $6,
+                os_build = $7,
+                os_end_of_support = $8
+            WHERE id = $1;
+        "#,
+        agent_id,
+        os_info,
+        classification.family,
+        classification.product,
+        classification.edition,
+        classification.version,
+        classification.build,
+        classification.end_of_support
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Agent and operating system counts for a tenant's VSA organization.
+pub async fn vsa_os_statistics(pool: &PgPool, tenant: &str) -> Result<VsaOsStatistics> {
+    let totals = sqlx::query!(
+        r#"
+            SELECT
+                COUNT(*) AS "agents!",
+                COUNT(*) FILTER (WHERE anti_virus) AS "agents_with_anti_virus!",
+                COUNT(*) FILTER (WHERE os_family = 'windows' AND os_product LIKE 'Windows 11%') AS "win11_agents!",
+                COUNT(*) FILTER (WHERE os_family = 'windows' AND os_product LIKE 'Windows 10%') AS "win10_agents!",
+                COUNT(*) FILTER (WHERE os_family = 'windows' AND os_product LIKE 'Windows 7%') AS "win7_agents!",
+                COUNT(*) FILTER (WHERE os_family IN ('windows_server', 'linux')) AS "server_agents!"
+            FROM vsa_agents
+            WHERE similarity(LOWER(organization_name), LOWER($1)) >= 0.6;
+        "#,
+        tenant
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let by_product = sqlx::query_as!(
+        VsaOsProductCount,
+        r#"
+            SELECT
+                COALESCE(os_product, 'Unknown') AS "product!",
+                COUNT(*) AS "count!"
+            FROM vsa_agents
+            WHERE similarity(LOWER(organization_name), LOWER($1)) >= 0.6
+            GROUP BY COALESCE(os_product, 'Unknown')
+            ORDER BY COUNT(*) DESC, COALESCE(os_product, 'Unknown');
+        "#,
+        tenant
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(VsaOsStatistics {
+        agents: totals.agents,
+        agents_with_anti_virus: totals.agents_with_anti_virus,
+        win11_agents: totals.win11_agents,
+        win10_agents: totals.win10_agents,
+        win7_agents: totals.win7_agents,
+        server_agents: totals.server_agents,
+        by_product,
+    })
+}
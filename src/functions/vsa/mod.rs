@@ -1,6 +1,10 @@
 pub mod agents;
+pub mod agents_os;
 pub mod devices;
 pub mod disks;
 pub mod groups;
 pub mod patches;
 pub mod security_products;
+pub mod ticket_metrics;
+pub mod tickets;
+pub mod tickets_sync;
@@ -0,0 +1,117 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VsaTicketMonth {
+    pub month: String,
+    pub opened: i64,
+    pub closed: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VsaTicketGroupCount {
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VsaTicketMetrics {
+    pub total: i64,
+    pub open: i64,
+    pub closed: i64,
+    pub mean_resolution_hours: Option<f64>,
+    pub by_month: Vec<VsaTicketMonth>,
+    pub by_priority: Vec<VsaTicketGroupCount>,
+    pub by_category: Vec<VsaTicketGroupCount>,
+}
+
+/**
+Service Desk ticket statistics for a tenant's VSA organization.
+
+A ticket is closed once it has a `closed_date_time`, resolution time runs from creation to close.
+*/
+pub async fn ticket_metrics(pool: &PgPool, tenant: &str) -> Result<VsaTicketMetrics> {
+    let totals = sqlx::query!(
+        r#"
+            SELECT
+                COUNT(*) AS "total!",
+                COUNT(*) FILTER (WHERE closed_date_time IS NULL) AS "open!",
+                COUNT(*) FILTER (WHERE closed_date_time IS NOT NULL) AS "closed!",
+                (AVG(EXTRACT(EPOCH FROM closed_date_time - creation_date_time)) / 3600)::DOUBLE PRECISION
+                    AS mean_resolution_hours
+            FROM vsa_tickets
+            WHERE similarity(LOWER(organization), LOWER($1)) >= 0.6;
+        "#,
+        tenant
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let by_month = sqlx::query_as!(
+        VsaTicketMonth,
+        r#"
+            SELECT
+                month AS "month!",
+                SUM(opened)::BIGINT AS "opened!",
+                SUM(closed)::BIGINT AS "closed!"
+            FROM (
+                SELECT TO_CHAR(creation_date_time, 'YYYY-MM') AS month, 1 AS opened, 0 AS closed
+                FROM vsa_tickets
+                WHERE similarity(LOWER(organization), LOWER($1)) >= 0.6
+                UNION ALL
+                SELECT TO_CHAR(closed_date_time, 'YYYY-MM') AS month, 0 AS opened, 1 AS closed
+                FROM vsa_tickets
+                WHERE similarity(LOWER(organization), LOWER($1)) >= 0.6
+                AND closed_date_time IS NOT NULL
+            ) AS events
+            GROUP BY month
+            ORDER BY month;
+        "#,
+        tenant
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let by_priority = sqlx::query_as!(
+        VsaTicketGroupCount,
+        r#"
+            SELECT
+                priority AS name,
+                COUNT(*) AS "count!"
+            FROM vsa_tickets
+            WHERE similarity(LOWER(organization), LOWER($1)) >= 0.6
+            GROUP BY priority
+            ORDER BY COUNT(*) DESC, priority;
+        "#,
+        tenant
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let by_category = sqlx::query_as!(
+        VsaTicketGroupCount,
+        r#"
+            SELECT
+                category AS name,
+                COUNT(*) AS "count!"
+            FROM vsa_tickets
+            WHERE similarity(LOWER(organization), LOWER($1)) >= 0.6
+            GROUP BY category
+            ORDER BY COUNT(*) DESC, category;
+        "#,
+        tenant
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(VsaTicketMetrics {
+        total: totals.total,
+        open: totals.open,
+        closed: totals.closed,
+        mean_resolution_hours: totals.mean_resolution_hours,
+        by_month,
+        by_priority,
+        by_category,
+    })
+}
@@ -0,0 +1,227 @@
+use std::env;
+
+use anyhow::{anyhow, Error};
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use dotenv::dotenv;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Tickets requested per page from the Service Desk API.
+const VSA_TICKETS_PAGE_SIZE: u64 = 100;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "PascalCase", serialize = "PascalCase"))]
+pub struct VsaAuthResult {
+    pub api_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "PascalCase", serialize = "PascalCase"))]
+pub struct VsaAuthResponse {
+    pub result: Option<VsaAuthResult>,
+}
+
+/**
+A Service Desk ticket as returned by Kaseya VSA.
+
+Ids and flags come back as numbers or strings depending on the VSA version, and dates with or
+without an offset, so those fields are kept loose and normalised with the helpers below.
+*/
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "PascalCase", serialize = "PascalCase"))]
+pub struct VsaServiceDeskTicket {
+    pub partition_id: Option<Value>,
+    pub asset_agent_id: Option<Value>,
+    pub asset_machine_group_name: Option<String>,
+    #[serde(alias = "ServiceDeskTicketId")]
+    pub ticket_id: Option<Value>,
+    pub ticket_number: Option<String>,
+    pub service_desk: Option<String>,
+    pub summary: Option<String>,
+    pub status: Option<String>,
+    pub stage: Option<String>,
+    pub priority: Option<String>,
+    pub severity: Option<String>,
+    pub category: Option<String>,
+    pub resolution: Option<String>,
+    pub resolution_description: Option<String>,
+    pub submitter_type: Option<String>,
+    pub submitter_name: Option<String>,
+    pub submitter_email: Option<String>,
+    pub submitter_phone: Option<String>,
+    pub contact_name: Option<String>,
+    pub contact_email: Option<String>,
+    pub contact_phone: Option<String>,
+    pub assignee: Option<String>,
+    pub owner: Option<String>,
+    pub organization: Option<String>,
+    pub creation_date_time: Option<String>,
+    pub modification_date_time: Option<String>,
+    pub closed_date_time: Option<String>,
+    pub due_date: Option<String>,
+    pub projected_date: Option<String>,
+    pub locked_by: Option<String>,
+    pub locked_on_date_time: Option<String>,
+    pub source_type: Option<String>,
+    pub last_public_update_date: Option<String>,
+    pub resolution_date: Option<String>,
+    pub policy: Option<String>,
+    pub description: Option<String>,
+    pub is_archived: Option<Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all(deserialize = "PascalCase", serialize = "PascalCase"))]
+pub struct VsaTicketsResponse {
+    pub result: Option<Vec<VsaServiceDeskTicket>>,
+    pub total_records: Option<u64>,
+}
+
+/**
+Fetch the Service Desk tickets from Kaseya VSA using the reqwest HTTP Client crate.
+
+When `since` is given only tickets modified at or after it are requested.
+*/
+pub async fn tickets(since: Option<DateTime<Utc>>) -> Result<Vec<VsaServiceDeskTicket>, Error> {
+    dotenv().ok();
+
+    let username = env::var("VSA_USERNAME")?;
+    let password = env::var("VSA_API_KEY")?;
+
+    let client = Client::builder().http1_title_case_headers().build()?;
+
+    let auth_body = client
+        .get("https://vsa.thusa.co.za/api/v1.0/auth")
+        .basic_auth(&username, Some(&password))
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<VsaAuthResponse>()
+        .await?;
+
+    let api_token = auth_body
+        .result
+        .and_then(|result| result.api_token)
+        .ok_or_else(|| anyhow!("Kaseya VSA did not return an authentication token."))?;
+
+    let mut query: Vec<(&str, String)> = vec![
+        ("$orderby", "ModificationDateTime asc".to_string()),
+        ("$top", VSA_TICKETS_PAGE_SIZE.to_string()),
+    ];
+
+    if let Some(since) = since {
+        query.push((
+            "$filter",
+            format!(
+                "ModificationDateTime ge {}",
+                since.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ),
+        ));
+    }
+
+    let mut tickets: Vec<VsaServiceDeskTicket> = Vec::new();
+    let mut skip: u64 = 0;
+
+    loop {
+        let body = client
+            .get("https://vsa.thusa.co.za/api/v1.0/automation/servicedesktickets")
+            .query(&query)
+            .query(&[("$skip", skip)])
+            .bearer_auth(&api_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<VsaTicketsResponse>()
+            .await?;
+
+        let page = body.result.unwrap_or_default();
+        let received = page.len() as u64;
+
+        tickets.extend(page);
+
+        skip += received;
+
+        if received == 0 || skip >= body.total_records.unwrap_or(skip) {
+            break;
+        }
+    }
+
+    Ok(tickets)
+}
+
+/// Render an id or flag that VSA may send as a number, boolean or string.
+pub fn vsa_value_to_string(value: Option<Value>) -> Option<String> {
+    match value? {
+        Value::Null => None,
+        Value::String(value) => Some(value),
+        value => Some(value.to_string()),
+    }
+}
+
+/// Parse a VSA date, which is RFC 3339 on newer versions and an offset-less UTC time on older ones.
+pub fn parse_vsa_date(value: Option<&str>) -> Option<DateTime<Utc>> {
+    let value = value?.trim();
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|date| date.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn renders_numbers_strings_and_flags() {
+        assert_eq!(
+            vsa_value_to_string(Some(json!(123456789012_i64))),
+            Some("123456789012".to_string())
+        );
+        assert_eq!(
+            vsa_value_to_string(Some(json!("123456789012"))),
+            Some("123456789012".to_string())
+        );
+        assert_eq!(
+            vsa_value_to_string(Some(json!(true))),
+            Some("true".to_string())
+        );
+        assert_eq!(
+            vsa_value_to_string(Some(json!(1.5))),
+            Some("1.5".to_string())
+        );
+        assert_eq!(vsa_value_to_string(Some(Value::Null)), None);
+        assert_eq!(vsa_value_to_string(None), None);
+    }
+
+    #[test]
+    fn parses_both_vsa_date_formats() {
+        let expected = NaiveDateTime::parse_from_str("2023-12-11T09:30:15", "%Y-%m-%dT%H:%M:%S")
+            .unwrap()
+            .and_utc();
+
+        assert_eq!(parse_vsa_date(Some("2023-12-11T09:30:15Z")), Some(expected));
+        assert_eq!(
+            parse_vsa_date(Some("2023-12-11T11:30:15+02:00")),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_vsa_date(Some(" 2023-12-11T09:30:15 ")),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_vsa_date(Some("2023-12-11T09:30:15.250")),
+            Some(expected + chrono::Duration::milliseconds(250))
+        );
+
+        assert_eq!(parse_vsa_date(Some("11/12/2023 09:30")), None);
+        assert_eq!(parse_vsa_date(Some("")), None);
+        assert_eq!(parse_vsa_date(None), None);
+    }
+}
@@ -0,0 +1,183 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::tickets::{parse_vsa_date, tickets, vsa_value_to_string};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct VsaTicketSyncResult {
+    pub inserted: i64,
+    pub updated: i64,
+    pub skipped: i64,
+}
+
+/**
+Incrementally sync Kaseya VSA Service Desk tickets.
+
+The newest `modification_date_time` already stored is the high-water mark, only tickets modified
+at or after it are requested. The first run backfills every ticket.
+*/
+pub async fn sync_tickets(pool: &PgPool) -> Result<VsaTicketSyncResult> {
+    let high_water_mark =
+        sqlx::query_scalar!("SELECT MAX(modification_date_time) FROM vsa_tickets;")
+            .fetch_one(pool)
+            .await?;
+
+    let mut result = VsaTicketSyncResult::default();
+
+    for ticket in tickets(high_water_mark).await? {
+        let Some(ticket_id) = vsa_value_to_string(ticket.ticket_id) else {
+            result.skipped += 1;
+
+            continue;
+        };
+
+        let creation_date_time = parse_vsa_date(ticket.creation_date_time.as_deref());
+        let modification_date_time =
+            parse_vsa_date(ticket.modification_date_time.as_deref()).or(creation_date_time);
+
+        let (Some(creation_date_time), Some(modification_date_time)) =
+            (creation_date_time, modification_date_time)
+        else {
+            result.skipped += 1;
+
+            continue;
+        };
+
+        let upsert_result = sqlx::query!(
+            r#"
+                INSERT INTO vsa_tickets (
+                    partition_id,
+                    asset_agent_id,
+                    asset_machine_group_name,
+                    ticket_id,
+                    ticket_number,
+                    service_desk,
+                    summary,
+                    status,
+                    stage,
+                    priority,
+                    severity,
+                    category,
+                    resolution,
+                    resolution_description,
+                    submitter_type,
+                    submitter_name,
+                    submitter_email,
+                    submitter_phone,
+                    contact_name,
+                    contact_email,
+                    contact_phone,
+                    assignee,
+                    owner,
+                    organization,
+                    creation_date_time,
+                    modification_date_time,
+                    closed_date_time,
+                    due_date,
+                    projected_date,
+                    locked_by,
+                    locked_on_date_time,
+                    source_type,
+                    last_public_update_date,
+                    resolution_date,
+                    policy,
+                    description,
+                    is_archived
+                ) VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                    $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34,
+                    $35, $36, $37
+                )
+                ON CONFLICT (ticket_id) DO UPDATE SET
+                    partition_id = EXCLUDED.partition_id,
+                    asset_agent_id = EXCLUDED.asset_agent_id,
+                    asset_machine_group_name = EXCLUDED.asset_machine_group_name,
+                    ticket_number = EXCLUDED.ticket_number,
+                    service_desk = EXCLUDED.service_desk,
+                    summary = EXCLUDED.summary,
+                    status = EXCLUDED.status,
+                    stage = EXCLUDED.stage,
+                    priority = EXCLUDED.priority,
+                    severity = EXCLUDED.severity,
+                    category = EXCLUDED.category,
+                    resolution = EXCLUDED.resolution,
+                    resolution_description = EXCLUDED.resolution_description,
+                    submitter_type = EXCLUDED.submitter_type,
+                    submitter_name = EXCLUDED.submitter_name,
+                    submitter_email = EXCLUDED.submitter_email,
+                    submitter_phone = EXCLUDED.submitter_phone,
+                    contact_name = EXCLUDED.contact_name,
+                    contact_email = EXCLUDED.contact_email,
+                    contact_phone = EXCLUDED.contact_phone,
+                    assignee = EXCLUDED.assignee,
+                    owner = EXCLUDED.owner,
+                    organization = EXCLUDED.organization,
+                    modification_date_time = EXCLUDED.modification_date_time,
+                    closed_date_time = EXCLUDED.closed_date_time,
+                    due_date = EXCLUDED.due_date,
+                    projected_date = EXCLUDED.projected_date,
+                    locked_by = EXCLUDED.locked_by,
+                    locked_on_date_time = EXCLUDED.locked_on_date_time,
+                    source_type = EXCLUDED.source_type,
+                    last_public_update_date = EXCLUDED.last_public_update_date,
+                    resolution_date = EXCLUDED.resolution_date,
+                    policy = EXCLUDED.policy,
+                    description = EXCLUDED.description,
+                    is_archived = EXCLUDED.is_archived
+                RETURNING (xmax = 0) AS "inserted!";
+            "#,
+            vsa_value_to_string(ticket.partition_id).unwrap_or("N/A".to_string()),
+            vsa_value_to_string(ticket.asset_agent_id).unwrap_or("N/A".to_string()),
+            ticket.asset_machine_group_name.unwrap_or("N/A".to_string()),
+            ticket_id,
+            ticket.ticket_number.unwrap_or("N/A".to_string()),
+            ticket.service_desk.unwrap_or("N/A".to_string()),
+            ticket.summary.unwrap_or("N/A".to_string()),
+            ticket.status.unwrap_or("N/A".to_string()),
+            ticket.stage.unwrap_or("N/A".to_string()),
+            ticket.priority.unwrap_or("N/A".to_string()),
+            ticket.severity.unwrap_or("N/A".to_string()),
+            ticket.category.unwrap_or("N/A".to_string()),
+            ticket.resolution.unwrap_or("N/A".to_string()),
+            ticket.resolution_description.unwrap_or("N/A".to_string()),
+            ticket.submitter_type.unwrap_or("N/A".to_string()),
+            ticket.submitter_name.unwrap_or("N/A".to_string()),
+            ticket.submitter_email.unwrap_or("N/A".to_string()),
+            ticket.submitter_phone.unwrap_or("N/A".to_string()),
+            ticket.contact_name.unwrap_or("N/A".to_string()),
+            ticket.contact_email.unwrap_or("N/A".to_string()),
+            ticket.contact_phone.unwrap_or("N/A".to_string()),
+            ticket.assignee.unwrap_or("N/A".to_string()),
+            ticket.owner.unwrap_or("N/A".to_string()),
+            ticket.organization.unwrap_or("N/A".to_string()),
+            creation_date_time,
+            modification_date_time,
+            parse_vsa_date(ticket.closed_date_time.as_deref()),
+            parse_vsa_date(ticket.due_date.as_deref()),
+            parse_vsa_date(ticket.projected_date.as_deref()),
+            ticket.locked_by.unwrap_or("N/A".to_string()),
+            parse_vsa_date(ticket.locked_on_date_time.as_deref()),
+            ticket.source_type.unwrap_or("N/A".to_string()),
+            parse_vsa_date(ticket.last_public_update_date.as_deref()),
+            parse_vsa_date(ticket.resolution_date.as_deref()),
+            ticket.policy.unwrap_or("N/A".to_string()),
+            ticket.description.unwrap_or("N/A".to_string()),
+            vsa_value_to_string(ticket.is_archived).unwrap_or("N/A".to_string())
+        )
+        .fetch_one(pool)
+        .await;
+
+        match upsert_result {
+            Ok(upserted) if upserted.inserted => result.inserted += 1,
+            Ok(_) => result.updated += 1,
+            Err(error) => {
+                println!("Failed to upsert VSA ticket {}: {:?}", ticket_id, error);
+
+                result.skipped += 1;
+            }
+        }
+    }
+
+    Ok(result)
+}
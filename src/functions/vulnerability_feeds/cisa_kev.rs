@@ -0,0 +1,196 @@
+use std::{env, fs};
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use dotenv::dotenv;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KnownExploitedVulnerabilityCatalogue {
+    vulnerabilities: Vec<KnownExploitedVulnerabilityEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KnownExploitedVulnerabilityEntry {
+    #[serde(rename = "cveID")]
+    cve_id: String,
+    vendor_project: Option<String>,
+    product: Option<String>,
+    vulnerability_name: Option<String>,
+    date_added: Option<String>,
+    short_description: Option<String>,
+    required_action: Option<String>,
+    due_date: Option<String>,
+    known_ransomware_campaign_use: Option<String>,
+}
+
+/// An entry of the CISA Known Exploited Vulnerabilities catalogue.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct KnownExploitedVulnerability {
+    pub cve_id: String,
+    pub vendor_project: Option<String>,
+    pub product: Option<String>,
+    pub vulnerability_name: Option<String>,
+    pub date_added: Option<NaiveDate>,
+    pub short_description: Option<String>,
+    pub required_action: Option<String>,
+    /// Remediation deadline for US federal agencies.
+    pub due_date: Option<NaiveDate>,
+    pub known_ransomware_campaign_use: bool,
+}
+
+/// Path of the catalogue JSON (`known_exploited_vulnerabilities.json` from cisa.gov), from `CISA_KEV_PATH`.
+pub fn cisa_kev_path() -> Option<String> {
+    dotenv().ok();
+
+    env::var("CISA_KEV_PATH")
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+}
+
+/// Read the catalogue JSON, CVE ids are uppercased.
+pub fn parse_known_exploited_vulnerabilities(
+    data: &[u8],
+) -> Result<Vec<KnownExploitedVulnerability>> {
+    let catalogue: KnownExploitedVulnerabilityCatalogue = serde_json::from_slice(data)?;
+    let date = |date: Option<String>| {
+        date.and_then(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok())
+    };
+
+    Ok(catalogue
+        .vulnerabilities
+        .into_iter()
+        .filter(|entry| !entry.cve_id.trim().is_empty())
+        .map(|entry| KnownExploitedVulnerability {
+            cve_id: entry.cve_id.trim().to_uppercase(),
+            vendor_project: entry.vendor_project,
+            product: entry.product,
+            vulnerability_name: entry.vulnerability_name,
+            date_added: date(entry.date_added),
+            short_description: entry.short_description,
+            required_action: entry.required_action,
+            due_date: date(entry.due_date),
+            known_ransomware_campaign_use: entry
+                .known_ransomware_campaign_use
+                .is_some_and(|usage| usage.trim().eq_ignore_ascii_case("known")),
+        })
+        .collect())
+}
+
+/// Replace the stored catalogue with the one in the JSON file at `path`, returning the number of entries.
+pub async fn import_known_exploited_vulnerabilities(pool: &PgPool, path: &str) -> Result<usize> {
+    let mut vulnerabilities = parse_known_exploited_vulnerabilities(&fs::read(path)?)?;
+
+    vulnerabilities.sort_by(|a, b| a.cve_id.cmp(&b.cve_id));
+    vulnerabilities.dedup_by(|a, b| a.cve_id == b.cve_id);
+
+    if vulnerabilities.is_empty() {
+        return Err(anyhow!("{} has no known exploited vulnerabilities.", path));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!("DELETE FROM known_exploited_vulnerabilities;")
+        .execute(&mut *transaction)
+        .await?;
+
+    // sqlx checks `TEXT[]` parameters as `&[String]`, the nullable columns are passed `as _`.
+    sqlx::query!(
+        r#"
+            INSERT INTO known_exploited_vulnerabilities
+                (cve_id, vendor_project, product, vulnerability_name, date_added, short_description, required_action, due_date, known_ransomware_campaign_use)
+            SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::TEXT[], $5::DATE[], $6::TEXT[], $7::TEXT[], $8::DATE[], $9::BOOLEAN[]);
+        "#,
+        &vulnerabilities.iter().map(|v| v.cve_id.clone()).collect::<Vec<String>>(),
+        &vulnerabilities.iter().map(|v| v.vendor_project.clone()).collect::<Vec<Option<String>>>() as _,
+        &vulnerabilities.iter().map(|v| v.product.clone()).collect::<Vec<Option<String>>>() as _,
+        &vulnerabilities.iter().map(|v| v.vulnerability_name.clone()).collect::<Vec<Option<String>>>() as _,
+        &vulnerabilities.iter().map(|v| v.date_added).collect::<Vec<Option<NaiveDate>>>() as _,
+        &vulnerabilities.iter().map(|v| v.short_description.clone()).collect::<Vec<Option<String>>>() as _,
+        &vulnerabilities.iter().map(|v| v.required_action.clone()).collect::<Vec<Option<String>>>() as _,
+        &vulnerabilities.iter().map(|v| v.due_date).collect::<Vec<Option<NaiveDate>>>() as _,
+        &vulnerabilities.iter().map(|v| v.known_ransomware_campaign_use).collect::<Vec<bool>>()
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(vulnerabilities.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_catalogue_feed() {
+        let json = r#"{
+            "title": "CISA Catalog of Known Exploited Vulnerabilities",
+            "catalogVersion": "2023.12.11",
+            "dateReleased": "2023-12-11T15:00:00.0000Z",
+            "count": 3,
+            "vulnerabilities": [
+                {
+                    "cveID": "CVE-2021-44228",
+                    "vendorProject": "Apache",
+                    "product": "Log4j2",
+                    "vulnerabilityName": "Apache Log4j2 Remote Code Execution Vulnerability",
+                    "dateAdded": "2021-12-10",
+                    "shortDescription": "Apache Log4j2 contains a vulnerability where JNDI features do not protect against attacker-controlled JNDI-related endpoints, allowing for remote code execution.",
+                    "requiredAction": "Apply updates per vendor instructions.",
+                    "dueDate": "2021-12-24",
+                    "knownRansomwareCampaignUse": "Known",
+                    "notes": "",
+                    "cwes": ["CWE-20", "CWE-400", "CWE-502"]
+                },
+                {
+                    "cveID": " cve-2023-0001 ",
+                    "vendorProject": "Example",
+                    "product": "Gateway",
+                    "dateAdded": "not a date",
+                    "dueDate": " 2024-01-02 ",
+                    "knownRansomwareCampaignUse": "Unknown"
+                },
+                {
+                    "cveID": "",
+                    "vendorProject": "Example"
+                }
+            ]
+        }"#;
+
+        let vulnerabilities = parse_known_exploited_vulnerabilities(json.as_bytes()).unwrap();
+
+        assert_eq!(vulnerabilities.len(), 2);
+        assert_eq!(vulnerabilities[0].cve_id, "CVE-2021-44228");
+        assert_eq!(
+            vulnerabilities[0].date_added,
+            NaiveDate::from_ymd_opt(2021, 12, 10)
+        );
+        assert_eq!(
+            vulnerabilities[0].due_date,
+            NaiveDate::from_ymd_opt(2021, 12, 24)
+        );
+        assert!(vulnerabilities[0].known_ransomware_campaign_use);
+
+        assert_eq!(
+            vulnerabilities[1],
+            KnownExploitedVulnerability {
+                cve_id: "CVE-2023-0001".to_string(),
+                vendor_project: Some("Example".to_string()),
+                product: Some("Gateway".to_string()),
+                vulnerability_name: None,
+                date_added: None,
+                short_description: None,
+                required_action: None,
+                due_date: NaiveDate::from_ymd_opt(2024, 1, 2),
+                known_ransomware_campaign_use: false,
+            }
+        );
+
+        assert!(parse_known_exploited_vulnerabilities(b"{\"vulnerabilities\": {}}").is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use std::{env, fs, io::Read};
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use dotenv::dotenv;
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// Rows inserted per statement, the full EPSS file has a quarter million.
+const EPSS_IMPORT_BATCH_SIZE: usize = 10_000;
+
+/// The FIRST EPSS score of a CVE.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EpssScore {
+    pub cve_id: String,
+    /// Probability of exploitation in the next 30 days, 0 to 1.
+    pub epss: f64,
+    pub percentile: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EpssScores {
+    /// From the `score_date` in the file's comment line.
+    pub score_date: Option<NaiveDate>,
+    pub scores: Vec<EpssScore>,
+}
+
+/// Path of the EPSS CSV (`epss_scores-YYYY-MM-DD.csv`, optionally gzipped) from `EPSS_SCORES_PATH`.
+pub fn epss_scores_path() -> Option<String> {
+    dotenv().ok();
+
+    env::var("EPSS_SCORES_PATH")
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+}
+
+/**
+Read an EPSS CSV as published by FIRST, gzipped or not.
+
+```text
+#model_version:v2023.03.01,score_date:2023-12-11T00:00:00+0000
+cve,epss,percentile
+CVE-2021-44228,0.97565,0.99996
+```
+
+Rows that are not a CVE id with two numbers are skipped.
+*/
+pub fn parse_epss_scores(data: &[u8]) -> Result<EpssScores> {
+    let mut text = String::new();
+
+    if data.starts_with(&[0x1f, 0x8b]) {
+        GzDecoder::new(data).read_to_string(&mut text)?;
+    } else {
+        text = String::from_utf8(data.to_vec())?;
+    }
+
+    let mut score_date: Option<NaiveDate> = None;
+    let mut scores: Vec<EpssScore> = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix('#') {
+            score_date = score_date.or_else(|| {
+                comment
+                    .split(',')
+                    .find_map(|field| field.trim().strip_prefix("score_date:"))
+                    .and_then(|date| date.get(..10))
+                    .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            });
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+
+        let [cve_id, epss, percentile] = fields[..] else {
+            continue;
+        };
+
+        if !cve_id.to_uppercase().starts_with("CVE-") {
+            continue;
+        }
+
+        if let (Ok(epss), Ok(percentile)) = (epss.parse::<f64>(), percentile.parse::<f64>()) {
+            scores.push(EpssScore {
+                cve_id: cve_id.to_uppercase(),
+                epss,
+                percentile,
+            });
+        }
+    }
+
+    Ok(EpssScores { score_date, scores })
+}
+
+/// Replace the stored scores with those in the CSV at `path`, returning the number of scores.
+pub async fn import_epss_scores(pool: &PgPool, path: &str) -> Result<usize> {
+    let EpssScores {
+        score_date,
+        mut scores,
+    } = parse_epss_scores(&fs::read(path)?)?;
+
+    scores.sort_by(|a, b| a.cve_id.cmp(&b.cve_id));
+    scores.dedup_by(|a, b| a.cve_id == b.cve_id);
+
+    if scores.is_empty() {
+        return Err(anyhow!("{} has no EPSS scores.", path));
+    }
+
+    let mut transaction = pool.begin().await?;
+
+    sqlx::query!("DELETE FROM epss_scores;")
+        .execute(&mut *transaction)
+        .await?;
+
+    for batch in scores.chunks(EPSS_IMPORT_BATCH_SIZE) {
+        sqlx::query!(
+            r#"
+                INSERT INTO epss_scores (cve_id, epss, percentile, score_date)
+                SELECT cve_id, epss, percentile, $4
+                FROM UNNEST($1::TEXT[], $2::DOUBLE PRECISION[], $3::DOUBLE PRECISION[]) AS s (cve_id, epss, percentile);
+            "#,
+            &batch.iter().map(|score| score.cve_id.clone()).collect::<Vec<String>>(),
+            &batch.iter().map(|score| score.epss).collect::<Vec<f64>>(),
+            &batch.iter().map(|score| score.percentile).collect::<Vec<f64>>(),
+            score_date
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(scores.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    #[test]
+    fn reads_plain_and_gzipped_scores() {
+        let csv = "#model_version:v2023.03.01,score_date:2023-12-11T00:00:00+0000\n\
+            cve,epss,percentile\n\
+            CVE-2021-44228,0.97565,0.99996\n\
+            cve-2023-0001,0.0004,0.1\n\
+            not a row\n\
+            CVE-2023-0002,unknown,0.5\n";
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(csv.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        for data in [csv.as_bytes(), gzipped.as_slice()] {
+            let parsed = parse_epss_scores(data).unwrap();
+
+            assert_eq!(parsed.score_date, NaiveDate::from_ymd_opt(2023, 12, 11));
+            assert_eq!(
+                parsed.scores,
+                vec![
+                    EpssScore {
+                        cve_id: "CVE-2021-44228".to_string(),
+                        epss: 0.97565,
+                        percentile: 0.99996,
+                    },
+                    EpssScore {
+                        cve_id: "CVE-2023-0001".to_string(),
+                        epss: 0.0004,
+                        percentile: 0.1,
+                    },
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod cisa_kev;
+pub mod epss;
@@ -1,5 +1,7 @@
+pub mod assets;
 pub mod cyber_cns;
 pub mod rocket_cyber;
 pub mod spanning;
 pub mod vsa;
-pub mod veeam;
\ No newline at end of file
+pub mod veeam;
+pub mod vulnerability_feeds;
\ No newline at end of file
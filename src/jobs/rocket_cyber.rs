@@ -8,7 +8,7 @@ use serde_json::json;
 use sqlx::postgres::PgPoolOptions;
 
 use crate::{
-    functions::rocketcyber::{accounts::accounts, agents::agents, incidents::incidents},
+    functions::rocketcyber::{accounts::accounts, agents::agents, incidents_sync::sync_incidents},
     models::rocketcyber::{account::RocketAccount, agent::RocketAgent},
 };
 
@@ -143,49 +143,21 @@ pub async fn sync_rocketcyber() -> Result<()> {
 
     println!("Syncing RocketCyber incidents.");
 
-    let incidents = incidents()
-        .await
-        .expect("Failed to get rocketcyber incidents from rocketcyber api.");
-
-    sqlx::query_as!(RocketIncident, "TRUNCATE rocketcyber_incidents;")
-        .execute(&pool)
-        .await
-        .expect("Failed to remove old incidents from postgres database.");
-
-    let mut inserted = 0;
-
-    for incident in incidents {
-        let incident_id = incident.id.unwrap();
-
-        sqlx::query_as!(
-            RocketIncident,
-            "INSERT INTO rocketcyber_incidents VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10);",
-            incident_id,
-            incident.title.unwrap(),
-            incident.description.unwrap(),
-            incident.remediation.unwrap(),
-            incident.resolved_at,
-            incident.published_at,
-            incident.created_at,
-            incident.status.unwrap(),
-            incident.account_id.unwrap(),
-            incident.event_count.unwrap()
-        )
-        .execute(&pool)
-        .await
-        .expect("Failed to insert rocketcyber incident into postgres database.");
-
-        inserted += 1;
+    match sync_incidents(&pool).await {
+        Ok(result) => println!(
+            "{:?}",
+            Json(json!({
+                "status": StatusCode::OK.as_u16(),
+                "accounts": result.accounts,
+                "inserted": result.inserted,
+                "updated": result.updated,
+                "skipped": result.skipped,
+                "events": result.events
+            }))
+        ),
+        Err(error) => println!("Failed to sync RocketCyber incidents: {:?}", error),
     }
 
-    println!(
-        "{:?}",
-        Json(json!({
-            "status": StatusCode::OK.as_u16(),
-            "inserted": inserted
-        }))
-    );
-
     println!("Finished RocketCyber sync.");
 
     Ok(())
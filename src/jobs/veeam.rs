@@ -185,6 +185,46 @@ pub async fn sync_veeam() -> Result<()> {
     match veeam_agent_jobs {
         Ok(veeam_agents_jobs) => {
             for veeam_agent_job in veeam_agents_jobs {
+                if let (Some(job_uid), Some(last_run)) =
+                    (veeam_agent_job.instance_uid.as_deref(), veeam_agent_job.last_run)
+                {
+                    // Record each distinct run before the job row below is overwritten, a run first seen
+                    // while running gets its outcome on a later sync
+                    let session_result = sqlx::query!(
+                        "INSERT INTO veeam_job_sessions (
+                            job_kind,
+                            job_uid,
+                            job_name,
+                            status,
+                            last_run,
+                            last_end_time,
+                            last_duration,
+                            failure_message,
+                            company_name
+                        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                        ON CONFLICT (job_kind, job_uid, last_run) DO UPDATE SET
+                            status = EXCLUDED.status,
+                            last_end_time = EXCLUDED.last_end_time,
+                            last_duration = EXCLUDED.last_duration,
+                            failure_message = EXCLUDED.failure_message;",
+                        "agent",
+                        job_uid,
+                        veeam_agent_job.name.as_deref().unwrap_or("N/A"),
+                        veeam_agent_job.status.as_deref().unwrap_or("N/A"),
+                        last_run,
+                        veeam_agent_job.last_end_time,
+                        veeam_agent_job.last_duration.unwrap_or(0),
+                        veeam_agent_job.failure_message.as_deref().unwrap_or("N/A"),
+                        veeam_agent_job.company_name.as_deref().unwrap_or("N/A")
+                    )
+                    .execute(&pool)
+                    .await;
+
+                    if let Err(error) = session_result {
+                        println!("Failed to insert Veeam Agent Job Session: {:?}", error);
+                    }
+                }
+
                 let existing_veeam_agent_job = sqlx::query!(
                     "SELECT * FROM veeam_agents_jobs WHERE name = $1;",
                     veeam_agent_job.name
@@ -527,6 +567,46 @@ pub async fn sync_veeam() -> Result<()> {
     match veeam_server_jobs {
         Ok(veeam_server_jobs) => {
             for veeam_server_job in veeam_server_jobs {
+                if let (Some(job_uid), Some(last_run)) =
+                    (veeam_server_job.instance_uid.as_deref(), veeam_server_job.last_run)
+                {
+                    // Record each distinct run before the job row below is overwritten, a run first seen
+                    // while running gets its outcome on a later sync
+                    let session_result = sqlx::query!(
+                        "INSERT INTO veeam_job_sessions (
+                            job_kind,
+                            job_uid,
+                            job_name,
+                            status,
+                            last_run,
+                            last_end_time,
+                            last_duration,
+                            failure_message,
+                            company_name
+                        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                        ON CONFLICT (job_kind, job_uid, last_run) DO UPDATE SET
+                            status = EXCLUDED.status,
+                            last_end_time = EXCLUDED.last_end_time,
+                            last_duration = EXCLUDED.last_duration,
+                            failure_message = EXCLUDED.failure_message;",
+                        "server",
+                        job_uid,
+                        veeam_server_job.name.as_deref().unwrap_or("N/A"),
+                        veeam_server_job.status.as_deref().unwrap_or("N/A"),
+                        last_run,
+                        veeam_server_job.last_end_time,
+                        veeam_server_job.last_duration.unwrap_or(0),
+                        veeam_server_job.failure_message.as_deref().unwrap_or("N/A"),
+                        veeam_server_job.company_name.as_deref().unwrap_or("N/A")
+                    )
+                    .execute(&pool)
+                    .await;
+
+                    if let Err(error) = session_result {
+                        println!("Failed to insert Veeam Server Job Session: {:?}", error);
+                    }
+                }
+
                 let existing_veeam_server_job = sqlx::query!(
                     "SELECT * FROM veeam_servers_jobs WHERE name = $1;",
                     veeam_server_job.name
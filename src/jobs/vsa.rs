@@ -10,11 +10,13 @@ use sqlx::postgres::PgPoolOptions;
 use crate::{
     functions::vsa::{
         agents::agents,
+        agents_os::store_agent_os,
         devices::{devices, VsaDevice},
         disks::{disks, VsaDisk},
         groups::{groups, VsaGroup},
         patches::{patches, VsaPatch},
         security_products::{security_products, VsaSecurityProduct},
+        tickets_sync::sync_tickets,
     },
     models::vsa::agent::VsaAgent,
 };
@@ -72,6 +74,17 @@ pub async fn sync_vsa() -> Result<()> {
                 inserted += 1;
             }
         }
+
+        if let Err(error) = store_agent_os(
+            &pool,
+            &agent_id,
+            agent.operating_system.as_deref(),
+            agent.operating_system_info.as_deref(),
+        )
+        .await
+        {
+            println!("Failed to classify VSA agent {} OS: {:?}", agent_id, error);
+        }
     }
 
     println!(
@@ -362,6 +375,21 @@ pub async fn sync_vsa() -> Result<()> {
         }))
     );
 
+    println!("Syncing VSA tickets.");
+
+    match sync_tickets(&pool).await {
+        Ok(result) => println!(
+            "{:?}",
+            Json(json!({
+                "status": StatusCode::OK.as_u16(),
+                "inserted": result.inserted,
+                "updated": result.updated,
+                "skipped": result.skipped
+            }))
+        ),
+        Err(error) => println!("Failed to sync VSA tickets: {:?}", error),
+    }
+
     println!("Finished VSA sync.");
 
     Ok(())
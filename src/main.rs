@@ -11,10 +11,10 @@ use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
     documentation::openapi::ApiDoc,
-    functions::cybercns::external_scan::start_external_scan,
+    functions::{cybercns::external_scan::start_external_scan, storage::blob_store::blob_store},
     jobs::{
-        cyber_cns::sync_cybercns, rocket_cyber::sync_rocketcyber, spanning::sync_spanning,
-        veeam::sync_veeam, vsa::sync_vsa,
+        assets::sync_asset_sources, spanning::sync_spanning,
+        vulnerability_feeds::sync_vulnerability_feeds,
     },
     routes::router::router,
 };
@@ -36,6 +36,9 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // Fail on startup rather than on the first report or upload when the blob store is misconfigured.
+    blob_store();
+
     let router = router().await;
 
     let app = Router::new()
@@ -53,11 +56,9 @@ async fn main() {
     tokio::spawn(start_external_scan());
 
     tokio::spawn(cronjobs());
-    tokio::spawn(sync_vsa());
-    tokio::spawn(sync_cybercns());
-    tokio::spawn(sync_rocketcyber());
+    tokio::spawn(sync_asset_sources());
     tokio::spawn(sync_spanning());
-    tokio::spawn(sync_veeam());
+    tokio::spawn(sync_vulnerability_feeds());
     
     axum::Server::bind(&address)
         .serve(app.into_make_service())
@@ -89,11 +90,8 @@ async fn cronjobs() {
                 |_uuid, _l| {
                     println!("Running hourly cronjobs.");
 
-                    tokio::spawn(sync_vsa());
-                    tokio::spawn(sync_cybercns());
-                    tokio::spawn(sync_rocketcyber());
+                    tokio::spawn(sync_asset_sources());
                     tokio::spawn(sync_spanning());
-                    tokio::spawn(sync_veeam());
                 },
             );
 
@@ -108,6 +106,7 @@ async fn cronjobs() {
                 println!("Running daily cronjobs.");
 
                 tokio::spawn(start_external_scan());
+                tokio::spawn(sync_vulnerability_feeds());
             });
 
             match daily_job {
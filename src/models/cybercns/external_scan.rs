@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExternalScan {
+    pub id: i32,
+    pub tenant_id: i32,
+    pub host_name: String,
+    pub company_id: Option<String>,
+    /// Blob store key of the raw CyberCNS results.
+    pub raw_key: Option<String>,
+    pub scanned_at: DateTime<Utc>,
+    /// The scan this one was compared with, the counts below are `None` until it has been.
+    pub previous_scan_id: Option<i32>,
+    pub opened_ports: Option<i32>,
+    pub new_vulnerabilities: Option<i32>,
+    pub new_tls_issues: Option<i32>,
+    pub resolved_findings: Option<i32>,
+}
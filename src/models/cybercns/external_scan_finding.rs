@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ExternalScanFinding {
+    pub id: i32,
+    pub scan_id: i32,
+    /// One of `open_port`, `tls` or `vulnerability`.
+    pub kind: String,
+    /// One of `critical`, `high`, `medium`, `low` or `info`.
+    pub severity: String,
+    pub title: String,
+    pub port: Option<i32>,
+    pub protocol: Option<String>,
+    pub service: Option<String>,
+    pub cve: Option<String>,
+    pub cvss_score: Option<f64>,
+    pub detail: Option<String>,
+}
@@ -1,6 +1,8 @@
 pub mod agent;
 pub mod asset;
 pub mod company;
+pub mod external_scan;
+pub mod external_scan_finding;
 pub mod host;
 pub mod security_report_card;
 pub mod security_report_card_evidence;
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -14,4 +15,14 @@ pub struct CyberVulnerability {
     pub asset_id: String,
     pub company_id: String,
     pub company_name: String,
+    /// CVE id, e.g. `CVE-2021-44228`.
+    pub vul_id: Option<String>,
+    /// `None` for vulnerabilities stored before first seen was tracked.
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: DateTime<Utc>,
+    /// When a sync no longer found the vulnerability, `None` while it is open.
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// CyberCNS flags vulnerabilities in the CISA Known Exploited Vulnerabilities catalogue.
+    pub cisa: bool,
+    pub cisa_vulnerability_name: Option<String>,
 }
@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Asset {
+    pub id: i32,
+    pub tenant_id: i32,
+    pub hostname: String,
+    pub display_name: String,
+    pub serial_number: Option<String>,
+    pub ip_address: Option<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
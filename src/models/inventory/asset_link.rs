@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AssetLink {
+    pub id: i32,
+    pub asset_id: i32,
+    pub source: String,
+    pub source_id: String,
+    pub source_name: String,
+    pub matched_on: String,
+}
@@ -1,4 +1,5 @@
 pub mod cybercns;
+pub mod inventory;
 pub mod rocketcyber;
 pub mod tenant;
 pub mod vsa;
@@ -14,4 +14,7 @@ pub struct RocketIncident {
     pub status: String,
     pub account_id: i64,
     pub event_count: i64,
+    /// The `event_count` the events were last fetched for.
+    pub events_synced_count: Option<i64>,
+    pub is_open: bool,
 }
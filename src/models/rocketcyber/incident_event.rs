@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RocketIncidentEvent {
+    pub id: i64,
+    pub incident_id: i64,
+    pub account_id: i64,
+    pub agent_id: Option<String>,
+    pub hostname: String,
+    pub app: String,
+    pub detection: String,
+    pub verdict: String,
+    /// The event as RocketCyber reported it.
+    pub details: Option<serde_json::Value>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub operating_system: Option<String>,
+    pub agent_version: Option<String>,
+}
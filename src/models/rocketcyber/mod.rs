@@ -1,3 +1,4 @@
 pub mod account;
 pub mod agent;
 pub mod incident;
+pub mod incident_event;
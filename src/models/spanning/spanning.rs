@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,4 +29,8 @@ pub struct SpanningUser {
     pub is_admin: bool,
     pub is_deleted: bool,
     pub company_name: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub unassigned_at: Option<DateTime<Utc>>,
+    pub platform: String,
 }
@@ -1 +1,3 @@
-pub mod tenant_external_scan_hostname;
\ No newline at end of file
+pub mod tenant_asset;
+pub mod tenant_external_scan_hostname;
+pub mod tenant_spanning_platform;
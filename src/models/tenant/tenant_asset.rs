@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TenantAsset {
+    pub id: i32,
+    pub tenant_id: i32,
+    pub kind: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub sha256: String,
+    pub created_at: DateTime<Utc>,
+    /// `full` or `thumbnail` for processed images, `original` for assets stored as uploaded.
+    pub variant: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+}
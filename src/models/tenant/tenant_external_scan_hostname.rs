@@ -1,8 +1,16 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct TenantExternalScanHostname {
-    pub id: f64,
-    pub tenant_id: f64,
+    pub id: i32,
+    pub tenant_id: i32,
+    /// Normalised hostname, IP address or CIDR range.
     pub host_name: String,
+    /// `hostname`, `ip` or `cidr`.
+    pub target_kind: String,
+    pub enabled: bool,
+    /// `daily`, `weekly` or `monthly`.
+    pub scan_frequency: String,
+    pub created_at: DateTime<Utc>,
 }
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TenantSpanningPlatform {
+    pub id: i32,
+    pub tenant_id: i32,
+    pub platform: String,
+    pub spanning_name: String,
+    pub spanning_key: String,
+}
@@ -1,4 +1,5 @@
 pub mod veeam_agent;
 pub mod veeam_agent_job;
+pub mod veeam_job_session;
 pub mod veeam_server;
-pub mod veeam_server_job;
\ No newline at end of file
+pub mod veeam_server_job;
@@ -5,22 +5,22 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all(deserialize = "camelCase", serialize = "camelCase"))]
 pub struct VeeamAgent {
     pub id: i32,
-    pub instance_uid: Option<String>,
-    pub agent_platform: Option<String>,
-    pub status: Option<String>,
-    pub management_agent_uid: Option<String>,
-    pub site_uid: Option<String>,
-    pub organization_uid: Option<String>,
-    pub name: Option<String>,
-    pub operation_mode: Option<String>,
-    pub gui_mode: Option<String>,
-    pub platform: Option<String>,
-    pub version: Option<String>,
-    pub activation_time: Option<DateTime<Utc>>,
-    pub management_mode: Option<String>,
-    pub installation_type: Option<String>,
-    pub total_jobs_count: Option<i32>,
-    pub running_jobs_count: Option<i32>,
-    pub success_jobs_count: Option<i32>,
-    pub company_name: Option<String>,
-}
\ No newline at end of file
+    pub instance_uid: String,
+    pub agent_platform: String,
+    pub status: String,
+    pub management_agent_uid: String,
+    pub site_uid: String,
+    pub organization_uid: String,
+    pub name: String,
+    pub operation_mode: String,
+    pub gui_mode: String,
+    pub platform: String,
+    pub version: String,
+    pub activation_time: DateTime<Utc>,
+    pub management_mode: String,
+    pub installation_type: String,
+    pub total_jobs_count: i64,
+    pub running_jobs_count: i64,
+    pub success_jobs_count: i64,
+    pub company_name: String,
+}
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VeeamJobSession {
+    pub id: i32,
+    pub job_kind: String,
+    pub job_uid: String,
+    pub job_name: String,
+    pub status: String,
+    pub last_run: DateTime<Utc>,
+    pub last_end_time: Option<DateTime<Utc>>,
+    pub last_duration: i64,
+    pub failure_message: String,
+    pub company_name: String,
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -23,4 +23,10 @@ pub struct VsaAgent {
     pub installed_patches: Option<f64>,
     pub last_patch: Option<DateTime<Utc>>,
     pub next_patch: Option<DateTime<Utc>>,
+    pub os_family: Option<String>,
+    pub os_product: Option<String>,
+    pub os_edition: Option<String>,
+    pub os_version: Option<String>,
+    pub os_build: Option<i64>,
+    pub os_end_of_support: Option<NaiveDate>,
 }
@@ -30,14 +30,14 @@ pub struct VsaTicket {
     pub organization: String,
     pub creation_date_time: DateTime<Utc>,
     pub modification_date_time: DateTime<Utc>,
-    pub closed_date_time: DateTime<Utc>,
-    pub due_date: DateTime<Utc>,
-    pub projected_date: DateTime<Utc>,
+    pub closed_date_time: Option<DateTime<Utc>>,
+    pub due_date: Option<DateTime<Utc>>,
+    pub projected_date: Option<DateTime<Utc>>,
     pub locked_by: String,
-    pub locked_on_date_time: DateTime<Utc>,
+    pub locked_on_date_time: Option<DateTime<Utc>>,
     pub source_type: String,
-    pub last_public_update_date: DateTime<Utc>,
-    pub resolution_date: DateTime<Utc>,
+    pub last_public_update_date: Option<DateTime<Utc>>,
+    pub resolution_date: Option<DateTime<Utc>>,
     pub policy: String,
     pub description: String,
     pub is_archived: String,
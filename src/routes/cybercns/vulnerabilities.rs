@@ -1,10 +1,24 @@
-use axum::{extract::State, response::IntoResponse, Json};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::NaiveDate;
 use reqwest::StatusCode;
 use serde_json::json;
 use sqlx::PgPool;
 
 use crate::{
-    functions::cybercns::vulnerabilities::{vulnerabilities, CyberVulnerabilityScore},
+    functions::{
+        cybercns::{
+            vulnerabilities::vulnerabilities,
+            vulnerability_store::{store_vulnerabilities, vulnerability_remediation},
+        },
+        vulnerability_feeds::{
+            cisa_kev::{cisa_kev_path, import_known_exploited_vulnerabilities},
+            epss::{epss_scores_path, import_epss_scores},
+        },
+    },
     models::cybercns::vulnerability::CyberVulnerability,
 };
 
@@ -25,78 +39,128 @@ pub async fn import(State(pool): State<PgPool>) -> impl IntoResponse {
         .await
         .expect("Failed to get cybercns vulnerabilities from cybercns api.");
 
-    let mut inserted = 0;
-    let mut skipped = 0;
+    match store_vulnerabilities(&pool, vulnerabilities).await {
+        Ok(summary) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "inserted": summary.inserted,
+            "updated": summary.updated,
+            "skipped": summary.skipped,
+            "resolved": summary.resolved,
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to store CyberCNS vulnerabilities.",
+            "error": error.to_string()
+        })),
+    }
+}
 
-    for vulnerability in vulnerabilities {
-        let vulnerability_id = vulnerability._id;
+/// Mean time to remediate per severity, optionally for one `tenant_id` and vulnerabilities resolved between `from` and `to` (`YYYY-MM-DD`).
+pub async fn remediation(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
 
-        if vulnerability_id.is_some() {
-            let vulnerability_id = vulnerability_id.to_owned().unwrap();
+    let tenant_id = match param("tenant_id").map(|tenant_id| tenant_id.parse::<i32>()) {
+        Some(Ok(tenant_id)) => Some(tenant_id),
+        Some(Err(_)) => {
+            return Json(json!({
+                "status": StatusCode::BAD_REQUEST.as_u16(),
+                "message": "tenant_id must be a number."
+            }))
+        }
+        None => None,
+    };
 
-            let existing_vulnerability_result = sqlx::query_as!(
-                CyberVulnerability,
-                "SELECT * FROM cybercns_vulnerabilities WHERE id = $1",
-                vulnerability_id.clone().to_string()
-            )
-            .fetch_one(&pool)
-            .await;
+    let mut dates: Vec<Option<NaiveDate>> = Vec::new();
 
-            match existing_vulnerability_result {
-                Ok(_) => {
-                    skipped += 1;
-                }
-                Err(_) => {
-                    let score = vulnerability
-                        .score
-                        .unwrap_or_else(|| CyberVulnerabilityScore {
-                            base_score: Some(0.0),
-                            impact_score: Some(0.0),
-                            exploit_score: Some(0.0),
-                            cvss_score: Some(0.0),
-                        });
+    for name in ["from", "to"] {
+        match param(name).map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d")) {
+            Some(Ok(date)) => dates.push(Some(date)),
+            Some(Err(_)) => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "message": format!("{} must be a date formatted as YYYY-MM-DD.", name)
+                }))
+            }
+            None => dates.push(None),
+        }
+    }
 
-                    let asset_ref = vulnerability.asset_ref;
-                    let company_ref = vulnerability.company_ref;
+    match vulnerability_remediation(&pool, tenant_id, dates[0], dates[1]).await {
+        Ok(remediation) => {
+            let resolved: i64 = remediation.iter().map(|severity| severity.resolved).sum();
+            let total_days: f64 = remediation
+                .iter()
+                .filter_map(|severity| {
+                    severity
+                        .mean_days_to_remediate
+                        .map(|days| days * severity.resolved as f64)
+                })
+                .sum();
 
-                    if asset_ref.is_some() && company_ref.is_some() {
-                        let asset_ref = asset_ref.unwrap();
-                        let company_ref = company_ref.unwrap();
-                        let product = vulnerability.product.unwrap_or(Vec::new());
+            Json(json!({
+                "status": StatusCode::OK.as_u16(),
+                "open": remediation.iter().map(|severity| severity.open).sum::<i64>(),
+                "resolved": resolved,
+                "mean_days_to_remediate": (resolved > 0).then(|| total_days / resolved as f64),
+                "data": remediation
+            }))
+        }
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to get vulnerability remediation.",
+            "error": error.to_string()
+        })),
+    }
+}
 
-                        sqlx::query!(
-                            "INSERT INTO cybercns_vulnerabilities (id, title, severity, vector, product, base_score, impact_score, exploit_score, cvss_score, asset_id, company_id, company_name) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12);",
-                            vulnerability_id.clone().to_string(),
-                            vulnerability.title,
-                            vulnerability.severity,
-                            vulnerability.vector,
-                            product.join(", "),
-                            score.base_score,
-                            score.impact_score,
-                            score.exploit_score,
-                            score.cvss_score,
-                            asset_ref.id,
-                            company_ref.id,
-                            company_ref.name
-                        )
-                        .execute(&pool)
-                        .await
-                        .expect("Failed to insert cybercns company into postgres database.");
+/// Import the CISA Known Exploited Vulnerabilities catalogue from `CISA_KEV_PATH`.
+pub async fn import_kev(State(pool): State<PgPool>) -> impl IntoResponse {
+    let Some(path) = cisa_kev_path() else {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "CISA_KEV_PATH is not set."
+        }));
+    };
 
-                        inserted += 1;
-                    } else {
-                        skipped += 1;
-                    }
-                }
-            }
-        } else {
-            skipped += 1;
-        }
+    match import_known_exploited_vulnerabilities(&pool, &path).await {
+        Ok(imported) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "imported": imported
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to import the CISA KEV catalogue.",
+            "error": error.to_string()
+        })),
     }
+}
+
+/// Import EPSS scores from `EPSS_SCORES_PATH`.
+pub async fn import_epss(State(pool): State<PgPool>) -> impl IntoResponse {
+    let Some(path) = epss_scores_path() else {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "EPSS_SCORES_PATH is not set."
+        }));
+    };
 
-    Json(json!({
-        "status": StatusCode::OK.as_u16(),
-        "inserted": inserted,
-        "skipped": skipped,
-    }))
+    match import_epss_scores(&pool, &path).await {
+        Ok(imported) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "imported": imported
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to import EPSS scores.",
+            "error": error.to_string()
+        })),
+    }
 }
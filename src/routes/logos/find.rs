@@ -1,52 +1,210 @@
-use axum::{body::StreamBody, extract::Query, response::IntoResponse, Json};
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Json,
+};
 use reqwest::StatusCode;
 use serde_json::json;
-use tokio::fs::read_dir;
-use tokio_util::io::ReaderStream;
-
-pub async fn index(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
-    let tenant = &params[0].1;
-    let directory_files_result = read_dir("uploads").await;
-    let mut files: Vec<String> = Vec::new();
-
-    match directory_files_result {
-        Ok(mut dir) => {
-            while let Some(entry) = dir.next_entry().await.unwrap() {
-                let file_name_str = &entry.file_name();
-                let mut file_name = String::from(file_name_str.to_str().unwrap());
-
-                if file_name.starts_with(tenant)
-                    || file_name.contains(tenant)
-                    || file_name.ends_with(tenant)
-                {
-                    file_name = file_name.replace(tenant, "");
-                    file_name = file_name.replace(":", "");
-
-                    files.push(file_name)
-                }
-            }
-        }
-        Err(_) => {}
+use sqlx::PgPool;
+
+use crate::{
+    functions::{
+        storage::blob_store::blob_store, tenant_assets::tenant_asset_store::tenant_asset_key,
+    },
+    models::tenant::tenant_asset::TenantAsset,
+};
+
+/// Blobs never change once written, the ETag changes with the content instead.
+const TENANT_ASSET_CACHE_CONTROL: &str = "public, max-age=3600";
+
+fn id_param(params: &[(String, String)], key: &str) -> Option<i32> {
+    params
+        .iter()
+        .find(|(param, _)| param == key)
+        .and_then(|(_, value)| value.parse::<i32>().ok())
+}
+
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let Some(tenant_id) = id_param(&params, "tenant_id") else {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "A numeric tenant_id is required."
+        }));
+    };
+
+    let assets_result = sqlx::query_as!(
+        TenantAsset,
+        "SELECT * FROM tenant_assets WHERE tenant_id = $1 ORDER BY kind;",
+        tenant_id
+    )
+    .fetch_all(&pool)
+    .await;
+
+    match assets_result {
+        Ok(assets) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "files": assets
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to get files.",
+            "error": error.to_string()
+        })),
     }
+}
 
-    Json(json!({
-        "status": StatusCode::OK.as_u16(),
-        "files": files
-    }))
+pub async fn get_file(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(id) = id_param(&params, "id") else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "A numeric asset id is required.".to_string(),
+        ));
+    };
+
+    let asset = match sqlx::query_as!(
+        TenantAsset,
+        "SELECT * FROM tenant_assets WHERE id = $1;",
+        id
+    )
+    .fetch_optional(&pool)
+    .await
+    {
+        Ok(Some(asset)) => asset,
+        Ok(None) => return Err((StatusCode::NOT_FOUND, "File not found.".to_string())),
+        Err(err) => return Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
+    };
+
+    tenant_asset_response(asset, &headers).await
 }
 
-pub async fn get_file(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
-    let file_name = &params[0].1;
+/// Whether `If-None-Match` lists `etag` (or is `*`), so the client's copy is current.
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|tag| tag.trim() == etag || tag.trim() == "*")
+        })
+}
+
+/// Serve an asset with its sha256 as the ETag, answering a matching `If-None-Match` with 304.
+pub async fn tenant_asset_response(
+    asset: TenantAsset,
+    headers: &HeaderMap,
+) -> Result<Response, (StatusCode, String)> {
+    let etag = format!("\"{}\"", asset.sha256);
+
+    if if_none_match(headers, &etag) {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (
+                    header::CACHE_CONTROL,
+                    TENANT_ASSET_CACHE_CONTROL.to_string(),
+                ),
+            ],
+        )
+            .into_response());
+    }
 
-    // `File` implements `AsyncRead`
-    let file = match tokio::fs::File::open(format!("uploads/{}", file_name)).await {
-        Ok(file) => file,
-        Err(err) => return Err((StatusCode::NOT_FOUND, format!("File not found: {}", err))),
+    let key = tenant_asset_key(&asset.sha256)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    let body = match blob_store().get(&key).await {
+        Ok(Some(body)) => body,
+        Ok(None) => return Err((StatusCode::NOT_FOUND, "File not found.".to_string())),
+        Err(err) => return Err((StatusCode::INTERNAL_SERVER_ERROR, err.to_string())),
     };
-    // convert the `AsyncRead` into a `Stream`
-    let stream = ReaderStream::new(file);
-    // convert the `Stream` into an `axum::body::HttpBody`
-    let body = StreamBody::new(stream);
 
-    Ok(body)
+    Ok((
+        [
+            (header::CONTENT_TYPE, asset.mime_type),
+            (header::ETAG, etag),
+            (
+                header::CACHE_CONTROL,
+                TENANT_ASSET_CACHE_CONTROL.to_string(),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use chrono::Utc;
+
+    use super::*;
+
+    const SHA256: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn headers(if_none_match: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(if_none_match).unwrap(),
+        );
+
+        headers
+    }
+
+    #[test]
+    fn matches_if_none_match_lists_and_wildcards() {
+        let etag = format!("\"{}\"", SHA256);
+
+        assert!(if_none_match(&headers(&etag), &etag));
+        assert!(if_none_match(
+            &headers(&format!("\"other\", {} , \"another\"", etag)),
+            &etag
+        ));
+        assert!(if_none_match(&headers("*"), &etag));
+        assert!(!if_none_match(&headers("\"other\", \"another\""), &etag));
+        assert!(!if_none_match(&headers(SHA256), &etag));
+        assert!(!if_none_match(&HeaderMap::new(), &etag));
+    }
+
+    #[tokio::test]
+    async fn answers_a_current_etag_with_not_modified() {
+        let asset = TenantAsset {
+            id: 1,
+            tenant_id: 1,
+            kind: "logo".to_string(),
+            file_name: "logo.png".to_string(),
+            mime_type: "image/png".to_string(),
+            size: 4,
+            sha256: SHA256.to_string(),
+            created_at: Utc::now(),
+            variant: "full".to_string(),
+            width: Some(1),
+            height: Some(1),
+        };
+
+        let response =
+            tenant_asset_response(asset, &headers(&format!("\"other\", \"{}\"", SHA256)))
+                .await
+                .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(
+            response.headers()[header::ETAG],
+            format!("\"{}\"", SHA256).as_str()
+        );
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            TENANT_ASSET_CACHE_CONTROL
+        );
+    }
 }
@@ -1,3 +1,3 @@
-pub mod remove;
 pub mod find;
+pub mod remove;
 pub mod upload;
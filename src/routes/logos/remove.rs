@@ -1,14 +1,35 @@
-use axum::{extract::Query, response::IntoResponse, Json};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
 use reqwest::StatusCode;
 use serde_json::json;
-use tokio::fs::remove_file;
+use sqlx::PgPool;
 
-pub async fn index(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
-    let file_name = &params[0].1;
+use crate::functions::tenant_assets::tenant_asset_store::remove_tenant_asset;
 
-    let delete_file_result = remove_file(format!("uploads/{}", file_name)).await;
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let Some(id) = params
+        .iter()
+        .find(|(key, _)| key == "id")
+        .and_then(|(_, value)| value.parse::<i32>().ok())
+    else {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "A numeric asset id is required."
+        }));
+    };
 
-    match delete_file_result {
+    match remove_tenant_asset(&pool, id).await {
+        Ok(assets) if assets.is_empty() => Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "id": id,
+            "message": "File not found."
+        })),
         Ok(_) => {
             Json(json!({"status":StatusCode::OK.as_u16(),"message": "Successfully deleted file."}))
         }
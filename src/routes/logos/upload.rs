@@ -1,86 +1,126 @@
 use axum::{
-    extract::{Multipart, Query},
+    extract::{Multipart, Query, State},
     response::IntoResponse,
     Json,
 };
 use reqwest::StatusCode;
 use serde_json::json;
-use tokio::{
-    fs::{create_dir, try_exists, File},
-    io::AsyncWriteExt,
+use sqlx::PgPool;
+
+use crate::{
+    functions::tenant_assets::{
+        tenant_asset_image::process_tenant_image,
+        tenant_asset_store::{store_tenant_asset, validate_tenant_asset},
+    },
+    models::tenant::tenant_asset::TenantAsset,
 };
 
 pub async fn index(
     Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
     mut multipart: Multipart,
 ) -> impl IntoResponse {
-    let tenant = &params[0].1;
-
-    let mut files_written = false;
-    let mut directory_created_or_found = false;
+    let Some(tenant_id) = params
+        .iter()
+        .find(|(key, _)| key == "tenant_id")
+        .and_then(|(_, value)| value.parse::<i32>().ok())
+    else {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "A numeric tenant_id is required."
+        }));
+    };
 
-    let directory_exists = try_exists("uploads").await;
+    let tenant = sqlx::query!("SELECT id FROM tenants WHERE id = $1;", tenant_id)
+        .fetch_optional(&pool)
+        .await
+        .expect("Failed to get tenant from postgres.");
 
-    match directory_exists {
-        Ok(directory) => {
-            if directory {
-                println!("directory found");
-                directory_created_or_found = true;
-            } else {
-                println!("directory not found. creating");
-
-                let create_dir_result = create_dir("uploads").await;
+    if tenant.is_none() {
+        return Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "tenant_id": tenant_id,
+            "message": "Tenant not found."
+        }));
+    }
 
-                match create_dir_result {
-                    Ok(_) => {
-                        println!("directory created");
+    let mut assets: Vec<TenantAsset> = Vec::new();
 
-                        directory_created_or_found = true;
-                    }
-                    Err(_) => {
-                        println!("failed to create directory");
-                    }
-                }
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(error) => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "message": "Failed to read upload.",
+                    "error": error.to_string()
+                }))
             }
-        }
-        Err(_) => {
-            println!("unknown error when checking directory exists")
-        }
-    }
+        };
 
-    while let Some(field) = multipart.next_field().await.unwrap() {
-        let name = field.name().unwrap().to_string();
-        let data = field.bytes().await.unwrap();
-        let tenant_file_name = format!("{}:{}", tenant, name);
+        let kind = field.name().unwrap_or_default().to_string();
+        let file_name = field.file_name().unwrap_or("N/A").to_string();
 
-        let file_result = File::create(format!("uploads/{}", tenant_file_name)).await;
+        let data = match field.bytes().await {
+            Ok(data) => data,
+            Err(error) => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "message": "Failed to read upload.",
+                    "error": error.to_string()
+                }))
+            }
+        };
 
-        match file_result {
-            Ok(mut file) => {
-                let write_result = file.write_all(&data).await;
+        if let Err(message) = validate_tenant_asset(&kind, &data) {
+            return Json(json!({
+                "status": StatusCode::BAD_REQUEST.as_u16(),
+                "kind": kind,
+                "message": message
+            }));
+        }
 
-                match write_result {
-                    Ok(_) => {
-                        files_written = true;
-                    }
-                    Err(_) => {
-                        files_written = false;
-                    }
-                }
+        let variants = match tokio::task::spawn_blocking(move || process_tenant_image(&data)).await
+        {
+            Ok(Ok(variants)) => variants,
+            Ok(Err(message)) => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "kind": kind,
+                    "message": message
+                }))
             }
-            Err(_) => {
-                files_written = false;
+            Err(error) => {
+                return Json(json!({
+                    "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    "message": "Failed to process upload.",
+                    "error": error.to_string()
+                }))
             }
         };
+
+        match store_tenant_asset(&pool, tenant_id, &kind, &file_name, &variants).await {
+            Ok(stored) => assets.extend(stored),
+            Err(error) => {
+                return Json(json!({
+                    "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    "message": "Failed to store upload.",
+                    "error": error.to_string()
+                }))
+            }
+        }
     }
 
-    if files_written && directory_created_or_found {
-        Json(json!({
-            "status": StatusCode::OK.as_u16()
-        }))
-    } else {
-        Json(json!({
-            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16()
-        }))
+    if assets.is_empty() {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "No files were uploaded."
+        }));
     }
+
+    Json(json!({
+        "status": StatusCode::OK.as_u16(),
+        "results": assets
+    }))
 }
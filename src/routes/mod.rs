@@ -8,4 +8,5 @@ pub mod spanning;
 pub mod statistics;
 pub mod table;
 pub mod tenants;
+pub mod veeam;
 pub mod vsa;
@@ -2,13 +2,17 @@ use axum::{extract::Query, response::IntoResponse, Json};
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tokio::fs::read_dir;
+
+use crate::functions::storage::blob_store::{blob_file_name, blob_store};
 
 pub async fn index(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
     let tenant = &params[0].1;
 
     // List all of the .json files from the reports directory
-    let mut dir = read_dir("reports").await.unwrap();
+    let keys = blob_store()
+        .list("reports")
+        .await
+        .expect("Failed to list reports from the blob store.");
 
     #[derive(Debug, Serialize, Deserialize)]
     pub struct Report {
@@ -19,15 +23,9 @@ pub async fn index(Query(params): Query<Vec<(String, String)>>) -> impl IntoResp
 
     let mut found_reports: Vec<Report> = Vec::new();
 
-    while let Ok(Some(entry)) = dir.next_entry().await {
-        if entry
-            .file_name()
-            .to_str()
-            .unwrap()
-            .to_string()
-            .contains(tenant)
-        {
-            let mut file_name = entry.file_name().to_str().unwrap().to_string();
+    for key in keys {
+        if blob_file_name(&key).contains(tenant) {
+            let mut file_name = blob_file_name(&key).to_string();
             file_name = file_name.replace(".json", "");
 
             let file_name_split: Vec<&str> = file_name.split("-").collect();
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fs::File, path::Path};
+use std::collections::HashMap;
 
 use axum::{
     extract::{Query, State},
@@ -10,11 +10,17 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::PgPool;
-use tokio::fs::{create_dir, try_exists};
 
 use crate::{
-    functions::cybercns::assets::{
-        CyberCompanyRef, CyberHost, CyberSecurityReportCard, CyberSecurityReportCardEvidence,
+    functions::{
+        assets::coverage_gaps::coverage_gaps,
+        cybercns::assets::{
+            CyberCompanyRef, CyberHost, CyberSecurityReportCard, CyberSecurityReportCardEvidence,
+        },
+        os::end_of_life::{end_of_life_machines, DEFAULT_END_OF_LIFE_WINDOW_DAYS},
+        rocketcyber::incident_metrics::incident_metrics,
+        storage::blob_store::blob_store,
+        vsa::{agents_os::vsa_os_statistics, ticket_metrics::ticket_metrics},
     },
     models::{
         rocketcyber::{account::RocketAccount, incident::RocketIncident},
@@ -225,69 +231,25 @@ pub async fn index(
     // Statistics
     // VSA
 
-    let vsa_statistics_result =
-        sqlx::query!(
-            "SELECT organization_name, anti_virus, os_name FROM vsa_agents WHERE similarity(LOWER(organization_name), LOWER($1)) >= 0.6;",
-            tenant
-        )
-            .fetch_all(&pool)
-            .await
-            .expect("Failed to get vsa organization names from postgres.");
-
-    let mut agents_count = 0;
-    let mut agents_with_av_count = 0;
-    let mut win11_agents_count = 0;
-    let mut win10_agents_count = 0;
-    let mut win7_agents_count = 0;
-
-    for record in vsa_statistics_result {
-        let anti_virus = record.anti_virus.unwrap_or(false);
-        let os_name = record.os_name.unwrap_or("Unknown".to_string());
-
-        agents_count += 1;
-
-        if anti_virus {
-            agents_with_av_count += 1;
-        }
-
-        // Write a check to see if os_name is windows 11/10/7 else nothing
-        if os_name.contains("7601") {
-            win7_agents_count += 1;
-        }
-
-        // Check if os_name contains any of the windows 10 build numbers
-        if os_name.contains("10240")
-            || os_name.contains("10586")
-            || os_name.contains("14393")
-            || os_name.contains("15063")
-            || os_name.contains("16299")
-            || os_name.contains("17134")
-            || os_name.contains("17763")
-            || os_name.contains("18362")
-            || os_name.contains("18363")
-            || os_name.contains("19041")
-            || os_name.contains("19042")
-            || os_name.contains("19043")
-            || os_name.contains("19044")
-            || os_name.contains("19045")
-        {
-            win10_agents_count += 1;
-        }
-
-        // Check if os_name contains any of the windows 11 build numbers
-        if os_name.contains("22000") || os_name.contains("22621") {
-            win11_agents_count += 1;
-        }
-    }
+    let vsa_os_statistics_result = vsa_os_statistics(&pool, tenant)
+        .await
+        .expect("Failed to get vsa statistics from postgres.");
 
     let vsa_statistics = json!({
-        "agents": agents_count,
-        "agents_with_anti_virus": agents_with_av_count,
-        "win11_agents": win11_agents_count,
-        "win10_agents": win10_agents_count,
-        "win7_agents": win7_agents_count
+        "agents": vsa_os_statistics_result.agents,
+        "agents_with_anti_virus": vsa_os_statistics_result.agents_with_anti_virus,
+        "win11_agents": vsa_os_statistics_result.win11_agents,
+        "win10_agents": vsa_os_statistics_result.win10_agents,
+        "win7_agents": vsa_os_statistics_result.win7_agents,
+        "server_agents": vsa_os_statistics_result.server_agents,
+        "operating_systems": vsa_os_statistics_result.by_product
     });
 
+    // End of Life Operating Systems
+    let end_of_life_os = end_of_life_machines(&pool, tenant, DEFAULT_END_OF_LIFE_WINDOW_DAYS)
+        .await
+        .expect("Failed to get end of life operating systems from postgres.");
+
     // VSA Patching
     let current_date = Utc::now();
 
@@ -421,6 +383,11 @@ pub async fn index(
         "results": patch_statistics_results_array
     });
 
+    // VSA Tickets
+    let vsa_tickets_statistics = ticket_metrics(&pool, tenant)
+        .await
+        .expect("Failed to get vsa ticket statistics from postgres.");
+
     // Rocket Cyber
     let account = sqlx::query_as!(
         RocketAccount,
@@ -463,49 +430,51 @@ pub async fn index(
             .await
             .expect("Failed to get total results from postgres.");
 
+            let incidents = incident_metrics(&pool, account.account_id)
+                .await
+                .expect("Failed to get rocketcyber incident metrics from postgres.");
+
             json!({
                 "status": StatusCode::OK.as_u16(),
                 "tenant": tenant,
                 "total_agents": total_agents,
-                "total_incidents": total_incidents
+                "total_incidents": total_incidents,
+                "incidents": incidents
             })
         }
         Err(_) => json!({
             "status": StatusCode::OK.as_u16(),
             "tenant": tenant,
             "total_agents": 0,
-            "total_incidents": 0
+            "total_incidents": 0,
+            "incidents": null
         }),
     };
 
-    // Create the reports directory if it doesn't exist.
-    let directory_exists = try_exists("reports").await;
-
-    match directory_exists {
-        Ok(directory) => {
-            if directory {
-                println!("directory found");
-            } else {
-                println!("directory not found. creating");
-
-                let create_dir_result = create_dir("reports").await;
-
-                match create_dir_result {
-                    Ok(_) => {
-                        println!("directory created");
-                    }
-                    Err(_) => {
-                        println!("failed to create directory");
-                    }
-                }
-            }
-        }
-        Err(_) => {
-            println!("unknown error when checking directory exists")
-        }
-    }
-
-    let reports_dir = Path::new("reports");
+    // Coverage Gaps
+    let tenant_id = sqlx::query_scalar!(
+        r#"
+            SELECT
+                id
+            FROM tenants
+            WHERE similarity(LOWER(tenant_name), LOWER($1)) >= 0.6
+            ORDER BY similarity(LOWER(tenant_name), LOWER($1)) DESC
+            LIMIT 1;
+        "#,
+        tenant
+    )
+    .fetch_optional(&pool)
+    .await
+    .expect("Failed to get tenant from postgres.");
+
+    let coverage_gaps_result = match tenant_id {
+        Some(tenant_id) => Some(
+            coverage_gaps(&pool, tenant_id)
+                .await
+                .expect("Failed to get coverage gaps from postgres."),
+        ),
+        None => None,
+    };
 
     // Create the current date and time variable.
     let current_date_time = Local::now();
@@ -517,25 +486,25 @@ pub async fn index(
         current_date_time.format("%Y-%m-%d-%H-%M-%S")
     );
 
-    // Create the file path.
-    let file_path = reports_dir.join(file_name.clone());
-
-    // Create the file.
-    let mut file = File::create(&file_path).expect("Failed to create the file.");
-
-    // Write the data to the file.
-    match serde_json::to_writer(
-        &mut file,
-        &json!({
-            "vsa": vsa_table,
-            "vsa_statistics": vsa_statistics,
-            "vsa_patching": patch_results_array,
-            "vsa_patching_statistics": vsa_patching_statistics,
-            "rocketcyber": incidents_result,
-            "rocketcyber_statistics": rocketcyber_statistics,
-            "cybercns": assets_result,
-        }),
-    ) {
+    // Serialize the report and write it to the blob store.
+    let report = serde_json::to_vec(&json!({
+        "vsa": vsa_table,
+        "vsa_statistics": vsa_statistics,
+        "vsa_patching": patch_results_array,
+        "vsa_patching_statistics": vsa_patching_statistics,
+        "vsa_tickets_statistics": vsa_tickets_statistics,
+        "end_of_life_os": end_of_life_os,
+        "rocketcyber": incidents_result,
+        "rocketcyber_statistics": rocketcyber_statistics,
+        "cybercns": assets_result,
+        "coverage_gaps": coverage_gaps_result,
+    }))
+    .expect("Failed to serialize the report.");
+
+    match blob_store()
+        .put(&format!("reports/{}", file_name), report, "application/json")
+        .await
+    {
         Ok(_) => Json(json!({ "status": StatusCode::OK.as_u16(), "report-filename": file_name })),
         Err(_) => Json(
             json!({ "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(), "error": "Failed to write the data to the file."}),
@@ -1,14 +1,17 @@
 use axum::{extract::Query, response::IntoResponse, Json};
 use reqwest::StatusCode;
 use serde_json::json;
-use tokio::fs::remove_file;
+
+use crate::functions::storage::blob_store::blob_store;
 
 pub async fn delete_report(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
     let file_name = &params[0].1;
 
     println!("{:?}", file_name);
 
-    let delete_file_result = remove_file(format!("reports/{}.json", file_name)).await;
+    let delete_file_result = blob_store()
+        .delete(&format!("reports/{}.json", file_name))
+        .await;
 
     println!("{:?}", delete_file_result);
 
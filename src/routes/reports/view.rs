@@ -1,26 +1,31 @@
 use axum::{extract::Query, response::IntoResponse, Json};
 use reqwest::StatusCode;
 use serde_json::json;
-use tokio::fs::read_dir;
+
+use crate::functions::storage::blob_store::blob_store;
 
 pub async fn index(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
     let file_name = &params[0].1;
 
-    // find the report in the reports directory between the start and end date
-    let mut dir = read_dir("reports").await.unwrap();
-    let mut file_path = String::new();
-
-    while let Ok(Some(entry)) = dir.next_entry().await {
-        if entry.file_name().to_str() == Some(file_name) {
-            file_path = entry.path().to_str().unwrap().to_string();
-        }
-    }
+    // only look the report up directly inside the reports directory
+    let file = if file_name.contains('/') {
+        None
+    } else {
+        blob_store()
+            .get(&format!("reports/{}", file_name))
+            .await
+            .unwrap_or(None)
+    };
 
-    // read the file
-    let file = tokio::fs::read_to_string(file_path).await.unwrap();
+    let Some(file) = file else {
+        return Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "message": "File not found."
+        }));
+    };
 
     // conver the file to json
-    let file: serde_json::Value = serde_json::from_str(&file).unwrap();
+    let file: serde_json::Value = serde_json::from_slice(&file).unwrap();
 
     // return the file
     Json(json!({
@@ -1,10 +1,15 @@
-use axum::{extract::State, response::IntoResponse, Json};
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
 use reqwest::StatusCode;
 use serde_json::json;
 use sqlx::PgPool;
 
 use crate::{
-    functions::rocketcyber::incidents::incidents, models::rocketcyber::incident::RocketIncident,
+    functions::rocketcyber::incidents_sync::sync_incidents,
+    models::rocketcyber::{incident::RocketIncident, incident_event::RocketIncidentEvent},
 };
 
 pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
@@ -17,44 +22,90 @@ pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
     Json(incidents)
 }
 
-pub async fn import(State(pool): State<PgPool>) -> impl IntoResponse {
-    let incidents = incidents()
-        .await
-        .expect("Failed to get rocketcyber incidents from rocketcyber api.");
-
-    sqlx::query_as!(RocketIncident, "TRUNCATE rocketcyber_incidents;")
-        .execute(&pool)
-        .await
-        .expect("Failed to remove old incidents from postgres database.");
-
-    let mut inserted = 0;
-
-    for incident in incidents {
-        let incident_id = incident.id.unwrap();
-
-        sqlx::query_as!(
-            RocketIncident,
-            "INSERT INTO rocketcyber_incidents VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10);",
-            incident_id,
-            incident.title.unwrap(),
-            incident.description.unwrap(),
-            incident.remediation.unwrap(),
-            incident.resolved_at,
-            incident.published_at,
-            incident.created_at,
-            incident.status.unwrap(),
-            incident.account_id.unwrap(),
-            incident.event_count.unwrap()
-        )
-        .execute(&pool)
-        .await
-        .expect("Failed to insert rocketcyber incident into postgres database.");
-
-        inserted += 1;
-    }
+pub async fn view(Path(incident_id): Path<i64>, State(pool): State<PgPool>) -> impl IntoResponse {
+    let incident = sqlx::query_as!(
+        RocketIncident,
+        "SELECT * FROM rocketcyber_incidents WHERE id = $1;",
+        incident_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .expect("Failed to get rocketcyber incident from postgres.");
+
+    let Some(incident) = incident else {
+        return Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "incident_id": incident_id,
+            "message": "Incident not found."
+        }));
+    };
+
+    // Events without an agent id are matched to an agent of the same account by hostname instead.
+    let events = sqlx::query_as!(
+        RocketIncidentEvent,
+        r#"
+            SELECT
+                e.id,
+                e.incident_id,
+                e.account_id,
+                COALESCE(e.agent_id, a.id) AS agent_id,
+                e.hostname,
+                e.app,
+                e.detection,
+                e.verdict,
+                e.details,
+                e.created_at,
+                a.operating_system AS "operating_system?",
+                a.agent_version AS "agent_version?"
+            FROM rocketcyber_incident_events AS e
+            LEFT JOIN LATERAL (
+                SELECT id, operating_system, agent_version
+                FROM rocketcyber_agents
+                WHERE id = e.agent_id
+                OR (
+                    e.agent_id IS NULL
+                    AND customer_id = e.account_id
+                    AND LOWER(hostname) = LOWER(e.hostname)
+                )
+                ORDER BY created_at DESC NULLS LAST
+                LIMIT 1
+            ) AS a ON true
+            WHERE e.incident_id = $1
+            ORDER BY e.created_at, e.id;
+        "#,
+        incident_id
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get rocketcyber incident events from postgres.");
+
+    let mut hostnames: Vec<&str> = events.iter().map(|event| event.hostname.as_str()).collect();
+
+    hostnames.sort_unstable();
+    hostnames.dedup();
 
     Json(json!({
         "status": StatusCode::OK.as_u16(),
-        "inserted": inserted
+        "incident": incident,
+        "hostnames": hostnames,
+        "events": events
     }))
 }
+
+pub async fn import(State(pool): State<PgPool>) -> impl IntoResponse {
+    match sync_incidents(&pool).await {
+        Ok(result) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "accounts": result.accounts,
+            "inserted": result.inserted,
+            "updated": result.updated,
+            "skipped": result.skipped,
+            "events": result.events
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to sync rocketcyber incidents.",
+            "error": error.to_string()
+        })),
+    }
+}
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use axum::{
-    extract::State,
+    extract::{DefaultBodyLimit, State},
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
@@ -11,10 +11,15 @@ use dotenv::dotenv;
 use serde_json::json;
 use sqlx::{postgres::PgPoolOptions, PgPool};
 
+use crate::functions::tenant_assets::tenant_asset_store::MAX_TENANT_ASSET_BYTES;
+
 use super::{
-    cybercns, logos, reports, rocketcyber, scans, spanning, statistics, table, tenants, vsa,
+    cybercns, logos, reports, rocketcyber, scans, spanning, statistics, table, tenants, veeam, vsa,
 };
 
+/// Room for multipart boundaries and headers around an upload of the largest accepted size.
+const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+
 pub async fn router() -> Router {
     dotenv().ok();
 
@@ -41,12 +46,33 @@ pub async fn router() -> Router {
                         .delete(tenants::remove::delete_tenant),
                 )
                 .route("/smart", get(tenants::find::smart_find))
+                .route("/:id/assets", get(tenants::assets::index))
+                .route("/:id/backup-health", get(tenants::backup_health::index))
+                .route("/:id/coverage-gaps", get(tenants::coverage_gaps::index))
+                .route(
+                    "/:id/vulnerability-priorities",
+                    get(tenants::vulnerability_priorities::index),
+                )
+                .route(
+                    "/:id/external-scan-hostnames/:hostname_id/scan",
+                    post(tenants::external_scan_hostname::scan::index),
+                )
+                .route("/:id/logo", get(tenants::logo::index))
+                .route(
+                    "/spanning-platform",
+                    get(tenants::spanning_platform::find::index)
+                        .post(tenants::spanning_platform::add::add_tenant_spanning_platform)
+                        .delete(
+                            tenants::spanning_platform::remove::remove_tenant_spanning_platform,
+                        ),
+                )
                 .route(
                     "/external-scan-hostname",
                     get(tenants::external_scan_hostname::find::index)
                         .post(
                             tenants::external_scan_hostname::add::add_tenant_external_scan_hostname,
                         )
+                        .put(tenants::external_scan_hostname::update::update_external_scan_hostname)
                         .delete(
                             tenants::external_scan_hostname::remove::remove_external_scan_hostname,
                         ),
@@ -57,7 +83,12 @@ pub async fn router() -> Router {
             Router::new()
                 .route("/", get(logos::find::index).delete(logos::remove::index))
                 .route("/view", get(logos::find::get_file))
-                .route("/upload", post(logos::upload::index)),
+                .route(
+                    "/upload",
+                    post(logos::upload::index).layer(DefaultBodyLimit::max(
+                        MAX_TENANT_ASSET_BYTES + MULTIPART_OVERHEAD_BYTES,
+                    )),
+                ),
         )
         .nest(
             "/reports",
@@ -73,20 +104,28 @@ pub async fn router() -> Router {
             "/scans",
             Router::new()
                 .route("/", get(scans::find::index))
-                .route("/view", get(scans::view::index)),
+                .route("/diff", get(scans::diff::index))
+                .route("/view", get(scans::view::index))
+                .route("/raw", get(scans::view::raw))
+                .route("/runs", get(scans::runs::index).delete(scans::runs::cancel)),
         )
         .nest(
             "/statistics",
             Router::new()
                 .route("/vsa", get(statistics::vsa::index))
                 .route("/vsa-patching", get(statistics::vsa_patching::index))
-                .route("/rocket-cyber", get(statistics::rocket_cyber::index)),
+                .route("/vsa-tickets", get(statistics::vsa_tickets::index))
+                .route("/end-of-life-os", get(statistics::end_of_life_os::index))
+                .route("/rocket-cyber", get(statistics::rocket_cyber::index))
+                .route("/veeam", get(statistics::veeam::index))
+                .route("/veeam-jobs", get(statistics::veeam_jobs::index)),
         )
         .nest(
             "/table",
             Router::new()
                 .route("/vsa", get(table::vsa::index))
                 .route("/vsa/patching", get(table::vsa_patching::index))
+                .route("/vsa-tickets", get(table::vsa_tickets::index))
                 .route("/cns", get(table::cns_assets::index))
                 .route(
                     "/cns-vulnerabilities",
@@ -97,7 +136,11 @@ pub async fn router() -> Router {
                     "/rocket-cyber-agents",
                     get(table::rocket_cyber_agents::index),
                 )
-                .route("/spanning-backups", get(table::spanning_backups::index)),
+                .route("/spanning-backups", get(table::spanning_backups::index))
+                .route("/veeam-agents", get(table::veeam_agents::index))
+                .route("/veeam-agents-jobs", get(table::veeam_agents_jobs::index))
+                .route("/veeam-servers", get(table::veeam_servers::index))
+                .route("/veeam-servers-jobs", get(table::veeam_servers_jobs::index)),
         )
         .nest(
             "/vsa",
@@ -116,6 +159,10 @@ pub async fn router() -> Router {
                 .route(
                     "/patches",
                     get(vsa::patches::index).post(vsa::patches::import),
+                )
+                .route(
+                    "/tickets",
+                    get(vsa::tickets::index).post(vsa::tickets::import),
                 ),
         )
         .nest(
@@ -132,6 +179,18 @@ pub async fn router() -> Router {
                 .route(
                     "/vulnerabilities",
                     get(cybercns::vulnerabilities::index).post(cybercns::vulnerabilities::import),
+                )
+                .route(
+                    "/vulnerabilities/remediation",
+                    get(cybercns::vulnerabilities::remediation),
+                )
+                .route(
+                    "/vulnerabilities/kev",
+                    post(cybercns::vulnerabilities::import_kev),
+                )
+                .route(
+                    "/vulnerabilities/epss",
+                    post(cybercns::vulnerabilities::import_epss),
                 ),
         )
         .nest(
@@ -148,14 +207,26 @@ pub async fn router() -> Router {
                 .route(
                     "/incidents",
                     get(rocketcyber::incidents::index).post(rocketcyber::incidents::import),
-                ),
+                )
+                .route("/incidents/:id", get(rocketcyber::incidents::view)),
         )
         .nest(
             "/spanning",
-            Router::new().route(
-                "/backups",
-                get(spanning::spanning_backups::index).post(spanning::spanning_backups::import),
-            ),
+            Router::new()
+                .route(
+                    "/backups",
+                    get(spanning::spanning_backups::index).post(spanning::spanning_backups::import),
+                )
+                .route("/history", get(spanning::spanning_history::index)),
+        )
+        .nest(
+            "/veeam",
+            Router::new()
+                .route("/agents", get(veeam::veeam_agents::index))
+                .route("/agents/jobs", get(veeam::veeam_agents_jobs::index))
+                .route("/jobs/sessions", get(veeam::veeam_job_sessions::index))
+                .route("/servers", get(veeam::veeam_servers::index))
+                .route("/servers/jobs", get(veeam::veeam_servers_jobs::index)),
         )
         .fallback(fallback)
         .with_state(pool)
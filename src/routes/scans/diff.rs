@@ -0,0 +1,78 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::NaiveDate;
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::functions::cybercns::external_scan_diff::diff_external_scans;
+
+/// Changes on a host between the scans at `from` and `to` (`YYYY-MM-DD`), optionally for one `tenant_id`.
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let Some(host) = param("host").filter(|host| !host.trim().is_empty()) else {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "A host is required."
+        }));
+    };
+
+    let tenant_id = match param("tenant_id").map(|tenant_id| tenant_id.parse::<i32>()) {
+        Some(Ok(tenant_id)) => Some(tenant_id),
+        Some(Err(_)) => {
+            return Json(json!({
+                "status": StatusCode::BAD_REQUEST.as_u16(),
+                "message": "tenant_id must be a number."
+            }))
+        }
+        None => None,
+    };
+
+    let mut dates: Vec<Option<NaiveDate>> = Vec::new();
+
+    for name in ["from", "to"] {
+        match param(name).map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d")) {
+            Some(Ok(date)) => dates.push(Some(date)),
+            Some(Err(_)) => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "message": format!("{} must be a date formatted as YYYY-MM-DD.", name)
+                }))
+            }
+            None => dates.push(None),
+        }
+    }
+
+    match diff_external_scans(&pool, tenant_id, host.trim(), dates[0], dates[1]).await {
+        Ok(Some(diff)) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "total_opened_ports": diff.opened_ports.len(),
+            "total_new_vulnerabilities": diff.new_vulnerabilities.len(),
+            "total_new_tls_issues": diff.new_tls_issues.len(),
+            "total_resolved_findings": diff.resolved_findings.len(),
+            "data": diff
+        })),
+        Ok(None) => Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "host": host,
+            "message": "No scans found for this host."
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to compare scans.",
+            "error": error.to_string()
+        })),
+    }
+}
@@ -1,55 +1,62 @@
-use axum::{extract::Query, response::IntoResponse, Json};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::NaiveDate;
 use reqwest::StatusCode;
-use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tokio::fs::read_dir;
-
-pub async fn index(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
-    let tenant = &params[0].1;
-
-    // List all of the .json files from the reports directory
-    let mut dir = read_dir("scans").await.unwrap();
-
-    #[derive(Debug, Serialize, Deserialize)]
-    pub struct Scan {
-        pub tenant: String,
-        pub date: String,
-        pub time: String,
-        pub host_name: String,
-        pub file_name: String,
-    }
-
-    let mut found_scans: Vec<Scan> = Vec::new();
-
-    while let Ok(Some(entry)) = dir.next_entry().await {
-        if entry
-            .file_name()
-            .to_str()
-            .unwrap()
-            .to_string()
-            .contains(tenant)
-        {
-            let mut file_name = entry.file_name().to_str().unwrap().to_string();
-            file_name = file_name.replace(".json", "");
-
-            let file_name_split: Vec<&str> = file_name.split("-").collect();
-            let tenant = file_name_split[1].to_string();
-            let host_name = file_name_split[2].to_string();
-            let date = file_name_split[3..6].join("-").to_string();
-            let time = file_name_split[6..9].join(":").to_string();
-
-            found_scans.push(Scan {
-                tenant,
-                date,
-                time,
-                host_name,
-                file_name,
-            });
+use sqlx::PgPool;
+
+use crate::functions::cybercns::external_scan_store::external_scans;
+
+/// Scans filtered by `tenant_id`, `host` and a `from`/`to` date range (`YYYY-MM-DD`, inclusive).
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let tenant_id = match param("tenant_id").map(|tenant_id| tenant_id.parse::<i32>()) {
+        Some(Ok(tenant_id)) => Some(tenant_id),
+        Some(Err(_)) => {
+            return Json(json!({
+                "status": StatusCode::BAD_REQUEST.as_u16(),
+                "message": "tenant_id must be a number."
+            }))
+        }
+        None => None,
+    };
+
+    let mut dates: Vec<Option<NaiveDate>> = Vec::new();
+
+    for name in ["from", "to"] {
+        match param(name).map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d")) {
+            Some(Ok(date)) => dates.push(Some(date)),
+            Some(Err(_)) => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "message": format!("{} must be a date formatted as YYYY-MM-DD.", name)
+                }))
+            }
+            None => dates.push(None),
         }
     }
 
-    Json(json!({
-        "status": StatusCode::OK.as_u16(),
-        "scans": found_scans
-    }))
+    match external_scans(&pool, tenant_id, param("host"), dates[0], dates[1]).await {
+        Ok(scans) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "scans": scans
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to get scans.",
+            "error": error.to_string()
+        })),
+    }
 }
@@ -1,2 +1,4 @@
+pub mod diff;
 pub mod find;
+pub mod runs;
 pub mod view;
\ No newline at end of file
@@ -0,0 +1,68 @@
+use axum::{extract::Query, response::IntoResponse, Json};
+use reqwest::StatusCode;
+use serde_json::json;
+
+use crate::functions::cybercns::external_scan_runner::{external_scan_runner, ExternalScanStatus};
+
+/// Queued, running and recently finished external scans, newest first, or the run with `id`.
+pub async fn index(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
+    if let Some((_, id)) = params.iter().find(|(key, _)| key == "id") {
+        return match id
+            .parse::<u64>()
+            .ok()
+            .and_then(|id| external_scan_runner().get(id))
+        {
+            Some(run) => Json(json!({
+                "status": StatusCode::OK.as_u16(),
+                "data": run
+            })),
+            None => Json(json!({
+                "status": StatusCode::NOT_FOUND.as_u16(),
+                "id": id,
+                "message": "Scan run not found."
+            })),
+        };
+    }
+
+    let runs = external_scan_runner().runs();
+    let active = runs.iter().filter(|run| !run.status.is_finished()).count();
+
+    Json(json!({
+        "status": StatusCode::OK.as_u16(),
+        "total": runs.len(),
+        "active": active,
+        "data": runs
+    }))
+}
+
+/// Cancel a queued or running scan by run `id`.
+pub async fn cancel(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
+    let Some(id) = params
+        .iter()
+        .find(|(key, _)| key == "id")
+        .and_then(|(_, value)| value.parse::<u64>().ok())
+    else {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "A numeric run id is required."
+        }));
+    };
+
+    match external_scan_runner().cancel(id) {
+        Some(run) if run.status == ExternalScanStatus::Cancelled => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "message": "Scan cancelled.",
+            "data": run
+        })),
+        Some(run) => Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "The scan already finished.",
+            "data": run
+        })),
+        None => Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "id": id,
+            "message": "Scan run not found."
+        })),
+    }
+}
@@ -1,30 +1,107 @@
-use axum::{extract::Query, response::IntoResponse, Json};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
 use reqwest::StatusCode;
 use serde_json::json;
-use tokio::fs::read_dir;
+use sqlx::PgPool;
 
-pub async fn index(Query(params): Query<Vec<(String, String)>>) -> impl IntoResponse {
-    let file_name = &params[0].1;
+use crate::{
+    functions::{
+        cybercns::external_scan_store::external_scan_findings, storage::blob_store::blob_store,
+    },
+    models::cybercns::external_scan::ExternalScan,
+};
 
-    // find the report in the reports directory between the start and end date
-    let mut dir = read_dir("scans").await.unwrap();
-    let mut file_path = String::new();
+async fn find_scan(
+    pool: &PgPool,
+    params: &[(String, String)],
+) -> Result<ExternalScan, Json<serde_json::Value>> {
+    let Some(id) = params
+        .iter()
+        .find(|(key, _)| key == "id")
+        .and_then(|(_, value)| value.parse::<i32>().ok())
+    else {
+        return Err(Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "A numeric scan id is required."
+        })));
+    };
 
-    while let Ok(Some(entry)) = dir.next_entry().await {
-        if entry.file_name().to_str() == Some(file_name) {
-            file_path = entry.path().to_str().unwrap().to_string();
-        }
+    let scan = sqlx::query_as!(
+        ExternalScan,
+        "SELECT * FROM external_scans WHERE id = $1;",
+        id
+    )
+    .fetch_optional(pool)
+    .await
+    .expect("Failed to get scan from postgres.");
+
+    scan.ok_or_else(|| {
+        Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "id": id,
+            "message": "Scan not found."
+        }))
+    })
+}
+
+/// A scan with its findings.
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let scan = match find_scan(&pool, &params).await {
+        Ok(scan) => scan,
+        Err(response) => return response,
+    };
+
+    match external_scan_findings(&pool, scan.id).await {
+        Ok(findings) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "data": {
+                "scan": scan,
+                "findings": findings
+            }
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to get scan findings.",
+            "error": error.to_string()
+        })),
     }
+}
 
-    // read the file
-    let file = tokio::fs::read_to_string(file_path).await.unwrap();
+/// The raw CyberCNS results attached to a scan.
+pub async fn raw(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let scan = match find_scan(&pool, &params).await {
+        Ok(scan) => scan,
+        Err(response) => return response,
+    };
 
-    // conver the file to json
-    let file: serde_json::Value = serde_json::from_str(&file).unwrap();
+    let raw = match &scan.raw_key {
+        Some(raw_key) => blob_store().get(raw_key).await,
+        None => Ok(None),
+    };
 
-    // return the file
-    Json(json!({
-        "status": StatusCode::OK.as_u16(),
-        "data": file
-    }))
+    match raw {
+        Ok(Some(raw)) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "data": serde_json::from_slice::<serde_json::Value>(&raw).unwrap_or_default()
+        })),
+        Ok(None) => Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "id": scan.id,
+            "message": "Raw scan results not found."
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to get raw scan results.",
+            "error": error.to_string()
+        })),
+    }
 }
@@ -1,11 +1,14 @@
 use axum::{extract::State, response::IntoResponse, Json};
+use chrono::{DateTime, Utc};
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::PgPool;
 
 use crate::{
-    functions::spanning::spanning_backups::{self, spanning_backups, SpanningBackupData},
+    functions::spanning::{
+        spanning_backups::spanning_backups, spanning_store::store_spanning_backups,
+    },
     models::spanning::spanning::{SpanningBackupSummary, SpanningUser},
 };
 
@@ -54,6 +57,10 @@ pub struct SpanningUserBackup {
     pub is_deleted: Option<bool>,
     pub backup_summary: Option<Vec<SpanningSummary>>,
     pub company_name: Option<String>,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    pub unassigned_at: Option<DateTime<Utc>>,
+    pub platform: Option<String>,
 }
 
 pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
@@ -107,6 +114,10 @@ pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
             is_deleted: Some(backup.is_deleted),
             backup_summary: Some(spanning_summaries),
             company_name: Some(backup.company_name),
+            first_seen: Some(backup.first_seen),
+            last_seen: Some(backup.last_seen),
+            unassigned_at: backup.unassigned_at,
+            platform: Some(backup.platform),
         })
     }
 
@@ -116,102 +127,25 @@ pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
 pub async fn import(State(pool): State<PgPool>) -> impl IntoResponse {
     let backups = spanning_backups().await;
 
-    let mut skipped = 0;
-    let mut inserted = 0;
-
-    match backups {
-        Ok(backups) => {
-            for backup in backups {
-                let existing_backup = sqlx::query_as!(
-                    SpanningUser,
-                    "SELECT * FROM spanning_backups WHERE email = $1;",
-                    backup.email
-                )
-                .fetch_optional(&pool)
-                .await
-                .expect("Failed to get user from postgres.");
-
-                match existing_backup {
-                    Some(_) => {
-                        skipped += 1;
-                    }
-                    None => {
-                        let result = sqlx::query!(
-                            "INSERT INTO spanning_backups (user_principal_name, user_display_name, email, ms_id, assigned, is_admin, is_deleted, company_name) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id;",
-                            backup.user_principal_name.unwrap_or("N/A".to_string()),
-                            backup.user_display_name.unwrap_or("N/A".to_string()),
-                            backup.email.unwrap_or("N/A".to_string()),
-                            backup.ms_id.unwrap_or("N/A".to_string()),
-                            backup.assigned.unwrap_or(false),
-                            backup.is_admin.unwrap_or(false),
-                            backup.is_deleted.unwrap_or(false),
-                            backup.company_name.unwrap_or("N/A".to_string())
-                        )
-                        .fetch_one(&pool)
-                        .await;
-
-                        match result {
-                            Ok(result) => {
-                                for backup_summary in backup.backup_summary.unwrap() {
-                                    let backup_summary_backup = backup_summary.backup.unwrap_or(
-                                        spanning_backups::SpanningBackup {
-                                            total: Some("0".to_string()),
-                                            partial: Some(0),
-                                            failed: Some(0),
-                                            successful: Some(0),
-                                            data: Some(SpanningBackupData {
-                                                created: Some(0),
-                                                deleted: Some(0),
-                                                failed: Some(0),
-                                                total: Some(0),
-                                                attempts: Some(0),
-                                            }),
-                                        },
-                                    );
-                                    let backup_data =
-                                        backup_summary_backup.data.unwrap_or(SpanningBackupData {
-                                            created: Some(0),
-                                            deleted: Some(0),
-                                            failed: Some(0),
-                                            total: Some(0),
-                                            attempts: Some(0),
-                                        });
-
-                                    sqlx::query_as!(
-                                        SpanningBackupSummary,
-                                        "INSERT INTO spanning_backups_summaries (backup, date, backup_type, total, partial, failed, successful, data_created, data_deleted, data_failed, data_total, data_attempts) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12);",
-                                        result.id,
-                                        backup_summary.date.unwrap_or("N/A".to_string()),
-                                        backup_summary.backup_type.unwrap_or("N/A".to_string()),
-                                        backup_summary_backup.total.unwrap_or("0".to_string()),
-                                        backup_summary_backup.partial.unwrap_or(0),
-                                        backup_summary_backup.failed.unwrap_or(0),
-                                        backup_summary_backup.successful.unwrap_or(0),
-                                        backup_data.created.unwrap_or(0),
-                                        backup_data.deleted.unwrap_or(0),
-                                        backup_data.failed.unwrap_or(0),
-                                        backup_data.total.unwrap_or(0),
-                                        backup_data.attempts.unwrap_or(0)
-                                    )
-                                    .execute(&pool)
-                                    .await
-                                    .expect("Failed to insert backup summary into postgres database.");
-                                }
-
-                                inserted += 1;
-                            }
-                            Err(error) => println!("Failed to insert Spanning backup: {}", error),
-                        }
-                    }
-                }
-            }
-        }
-        Err(error) => println!("Failed to sync Spanning backups: {}", error),
+    let result = match backups {
+        Ok(backups) => store_spanning_backups(&pool, backups).await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(result) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "inserted": result.inserted,
+            "updated": result.updated,
+            "unassigned": result.unassigned,
+            "summaries_inserted": result.summaries_inserted,
+            "summaries_updated": result.summaries_updated,
+            "skipped": result.skipped
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to sync Spanning backups.",
+            "error": error.to_string()
+        })),
     }
-
-    Json(json!({
-        "status": StatusCode::OK.as_u16(),
-        "inserted": inserted,
-        "skipped": skipped
-    }))
 }
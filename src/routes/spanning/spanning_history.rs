@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{Duration, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::functions::spanning::spanning_backups::parse_spanning_platform;
+
+/// How far back the history goes when no `days` parameter is given.
+const DEFAULT_HISTORY_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SpanningHistoryDay {
+    pub date: String,
+    pub backup_type: String,
+    pub users: i64,
+    pub successful: i64,
+    pub partial: i64,
+    pub failed: i64,
+    pub data_total: i64,
+    pub data_failed: i64,
+}
+
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let tenant = &params[0].1;
+
+    let email = params
+        .iter()
+        .find(|(key, _)| key == "email")
+        .map(|(_, value)| value.clone());
+
+    let platform = params
+        .iter()
+        .find(|(key, _)| key == "platform")
+        .and_then(|(_, value)| parse_spanning_platform(value));
+
+    let days = params
+        .iter()
+        .find(|(key, _)| key == "days")
+        .and_then(|(_, value)| value.parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_HISTORY_DAYS);
+
+    let since = (Utc::now() - Duration::days(days))
+        .format("%Y-%m-%d")
+        .to_string();
+
+    // Summary dates are stored as the ISO strings Spanning returns, so the day prefix sorts correctly.
+    let history = sqlx::query_as!(
+        SpanningHistoryDay,
+        r#"
+            SELECT
+                LEFT(s.date, 10) AS "date!",
+                s.backup_type,
+                COUNT(DISTINCT s.backup) AS "users!",
+                SUM(s.successful)::BIGINT AS "successful!",
+                SUM(s.partial)::BIGINT AS "partial!",
+                SUM(s.failed)::BIGINT AS "failed!",
+                SUM(s.data_total)::BIGINT AS "data_total!",
+                SUM(s.data_failed)::BIGINT AS "data_failed!"
+            FROM spanning_backups_summaries AS s
+            INNER JOIN spanning_backups AS b ON b.id = s.backup
+            WHERE similarity(LOWER(b.company_name), LOWER($1)) >= 0.6
+            AND LEFT(s.date, 10) >= $2
+            AND ($3::TEXT IS NULL OR LOWER(b.email) = LOWER($3))
+            AND ($4::TEXT IS NULL OR b.platform = $4)
+            GROUP BY LEFT(s.date, 10), s.backup_type
+            ORDER BY LEFT(s.date, 10), s.backup_type;
+        "#,
+        tenant,
+        since,
+        email,
+        platform
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get spanning backup history from postgres.");
+
+    let unassigned_users = sqlx::query!(
+        r#"
+            SELECT email, user_display_name, unassigned_at AS "unassigned_at!"
+            FROM spanning_backups
+            WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6 AND unassigned_at IS NOT NULL
+            ORDER BY unassigned_at DESC;
+        "#,
+        tenant
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get unassigned spanning users from postgres.");
+
+    let unassigned_users: Vec<_> = unassigned_users
+        .into_iter()
+        .map(|user| {
+            json!({
+                "email": user.email,
+                "user_display_name": user.user_display_name,
+                "unassigned_at": user.unassigned_at
+            })
+        })
+        .collect();
+
+    Json(json!({
+        "status": StatusCode::OK.as_u16(),
+        "tenant": tenant,
+        "days": days,
+        "platform": platform,
+        "results": history,
+        "unassigned_users": unassigned_users
+    }))
+}
@@ -0,0 +1,39 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::functions::os::end_of_life::{end_of_life_machines, DEFAULT_END_OF_LIFE_WINDOW_DAYS};
+
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let tenant = &params[0].1;
+
+    let days = params
+        .iter()
+        .find(|(key, _)| key == "days")
+        .and_then(|(_, value)| value.parse::<i64>().ok())
+        .filter(|days| *days >= 0)
+        .unwrap_or(DEFAULT_END_OF_LIFE_WINDOW_DAYS);
+
+    match end_of_life_machines(&pool, tenant, days).await {
+        Ok(report) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "tenant": tenant,
+            "days": report.window_days,
+            "unsupported": report.unsupported,
+            "nearing_end_of_support": report.nearing_end_of_support,
+            "results": report.machines
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": format!("Failed to get end of life operating systems: {}", error)
+        })),
+    }
+}
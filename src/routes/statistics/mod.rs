@@ -1,3 +1,7 @@
+pub mod end_of_life_os;
 pub mod rocket_cyber;
+pub mod veeam;
+pub mod veeam_jobs;
 pub mod vsa;
 pub mod vsa_patching;
+pub mod vsa_tickets;
@@ -7,7 +7,10 @@ use reqwest::StatusCode;
 use serde_json::json;
 use sqlx::PgPool;
 
-use crate::models::rocketcyber::account::RocketAccount;
+use crate::{
+    functions::rocketcyber::incident_metrics::incident_metrics,
+    models::rocketcyber::account::RocketAccount,
+};
 
 pub async fn index(
     Query(params): Query<Vec<(String, String)>>,
@@ -56,18 +59,24 @@ pub async fn index(
             .await
             .expect("Failed to get total results from postgres.");
 
+            let incidents = incident_metrics(&pool, account.account_id)
+                .await
+                .expect("Failed to get rocketcyber incident metrics from postgres.");
+
             Json(json!({
                 "status": StatusCode::OK.as_u16(),
                 "tenant": tenant,
                 "total_agents": total_agents,
-                "total_incidents": total_incidents
+                "total_incidents": total_incidents,
+                "incidents": incidents
             }))
         }
         Err(_) => Json(json!({
             "status": StatusCode::OK.as_u16(),
             "tenant": tenant,
             "total_agents": 0,
-            "total_incidents": 0
+            "total_incidents": 0,
+            "incidents": null
         })),
     }
 }
@@ -0,0 +1,130 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VeeamJobStatusCount {
+    pub succeeded: i64,
+    pub warning: i64,
+    pub failed: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VeeamAgentLastSuccess {
+    pub agent_uid: String,
+    /// Not unique, agents in different sites can share a hostname.
+    pub agent_name: String,
+    pub last_successful_run: Option<DateTime<Utc>>,
+}
+
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let tenant = &params[0].1;
+
+    let agent_job_statuses = sqlx::query_scalar!(
+        "SELECT status FROM veeam_agents_jobs WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6;",
+        tenant
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam agent jobs from postgres.");
+
+    let server_job_statuses = sqlx::query_scalar!(
+        "SELECT status FROM veeam_servers_jobs WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6;",
+        tenant
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam server jobs from postgres.");
+
+    let agent_jobs = count_job_statuses(&agent_job_statuses);
+    let server_jobs = count_job_statuses(&server_job_statuses);
+
+    let last_successful_runs = sqlx::query_as!(
+        VeeamAgentLastSuccess,
+        r#"
+            SELECT
+                a.instance_uid AS agent_uid,
+                a.name AS agent_name,
+                MAX(j.last_run) FILTER (WHERE LOWER(j.status) = 'success') AS last_successful_run
+            FROM veeam_agents AS a
+            LEFT JOIN veeam_agents_jobs AS j ON j.backup_agent_uid = a.instance_uid
+            WHERE similarity(LOWER(a.company_name), LOWER($1)) >= 0.6
+            GROUP BY a.instance_uid, a.name
+            ORDER BY a.name, a.instance_uid;
+        "#,
+        tenant
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam agent last successful runs from postgres.");
+
+    let total_backed_up_size = sqlx::query_scalar!(
+        r#"
+            SELECT
+                COALESCE(SUM(backed_up_size), 0)::BIGINT AS "total!"
+            FROM veeam_agents_jobs
+            WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6;
+        "#,
+        tenant
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("Failed to get total results from postgres.");
+
+    let total_transferred_data = sqlx::query_scalar!(
+        r#"
+            SELECT
+                COALESCE(SUM(transferred_data), 0)::BIGINT AS "total!"
+            FROM veeam_servers_jobs
+            WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6;
+        "#,
+        tenant
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("Failed to get total results from postgres.");
+
+    Json(json!({
+        "status": StatusCode::OK.as_u16(),
+        "tenant": tenant,
+        "jobs": VeeamJobStatusCount {
+            succeeded: agent_jobs.succeeded + server_jobs.succeeded,
+            warning: agent_jobs.warning + server_jobs.warning,
+            failed: agent_jobs.failed + server_jobs.failed,
+        },
+        "agent_jobs": agent_jobs,
+        "server_jobs": server_jobs,
+        "last_successful_runs": last_successful_runs,
+        "total_backed_up_size": total_backed_up_size,
+        "total_transferred_data": total_transferred_data
+    }))
+}
+
+fn count_job_statuses(statuses: &[String]) -> VeeamJobStatusCount {
+    let mut count = VeeamJobStatusCount {
+        succeeded: 0,
+        warning: 0,
+        failed: 0,
+    };
+
+    for status in statuses {
+        match status.to_lowercase().as_str() {
+            "success" => count.succeeded += 1,
+            "warning" => count.warning += 1,
+            "failed" => count.failed += 1,
+            _ => {}
+        }
+    }
+
+    count
+}
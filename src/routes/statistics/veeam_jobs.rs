@@ -0,0 +1,128 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+
+/// How many days a job may go without a successful session before it is reported as stale.
+const DEFAULT_STALE_DAYS: i64 = 7;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VeeamJobSessionSummary {
+    pub job_kind: String,
+    pub job_uid: String,
+    pub job_name: String,
+    pub company_name: String,
+    /// Every session, including ones still running or that have not run.
+    pub total_sessions: i64,
+    /// Sessions that ended in success, warning or failure, the success rate is over these.
+    pub finished_sessions: i64,
+    pub successful_sessions: i64,
+    pub warning_sessions: i64,
+    pub failed_sessions: i64,
+    pub consecutive_failures: i64,
+    pub last_run: DateTime<Utc>,
+    pub last_successful_run: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct VeeamJobStatistics {
+    #[serde(flatten)]
+    pub summary: VeeamJobSessionSummary,
+    pub success_rate: f64,
+    pub no_success_in_days: bool,
+}
+
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let tenant = &params[0].1;
+
+    let days = params
+        .iter()
+        .find(|(key, _)| key == "days")
+        .and_then(|(_, value)| value.parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_STALE_DAYS);
+
+    // A failure streak is every failed session after the most recent successful or warning one.
+    let summaries = sqlx::query_as!(
+        VeeamJobSessionSummary,
+        r#"
+            SELECT
+                s.job_kind,
+                s.job_uid,
+                MAX(s.job_name) AS "job_name!",
+                MAX(s.company_name) AS "company_name!",
+                COUNT(*) AS "total_sessions!",
+                COUNT(*) FILTER (WHERE LOWER(s.status) IN ('success', 'warning', 'failed')) AS "finished_sessions!",
+                COUNT(*) FILTER (WHERE LOWER(s.status) = 'success') AS "successful_sessions!",
+                COUNT(*) FILTER (WHERE LOWER(s.status) = 'warning') AS "warning_sessions!",
+                COUNT(*) FILTER (WHERE LOWER(s.status) = 'failed') AS "failed_sessions!",
+                COUNT(*) FILTER (
+                    WHERE LOWER(s.status) = 'failed'
+                    AND (l.last_completed_run IS NULL OR s.last_run > l.last_completed_run)
+                ) AS "consecutive_failures!",
+                MAX(s.last_run) AS "last_run!",
+                MAX(s.last_run) FILTER (WHERE LOWER(s.status) = 'success') AS last_successful_run
+            FROM veeam_job_sessions AS s
+            LEFT JOIN (
+                SELECT job_kind, job_uid, MAX(last_run) AS last_completed_run
+                FROM veeam_job_sessions
+                WHERE LOWER(status) IN ('success', 'warning')
+                GROUP BY job_kind, job_uid
+            ) AS l ON l.job_kind = s.job_kind AND l.job_uid = s.job_uid
+            WHERE similarity(LOWER(s.company_name), LOWER($1)) >= 0.6
+            GROUP BY s.job_kind, s.job_uid
+            ORDER BY "job_name!";
+        "#,
+        tenant
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam job sessions from postgres.");
+
+    let stale_before = Utc::now() - Duration::days(days);
+
+    let jobs: Vec<VeeamJobStatistics> = summaries
+        .into_iter()
+        .map(|summary| {
+            let success_rate = if summary.finished_sessions > 0 {
+                summary.successful_sessions as f64 / summary.finished_sessions as f64 * 100.0
+            } else {
+                0.0
+            };
+
+            let no_success_in_days = summary
+                .last_successful_run
+                .is_none_or(|last_successful_run| last_successful_run < stale_before);
+
+            VeeamJobStatistics {
+                summary,
+                success_rate,
+                no_success_in_days,
+            }
+        })
+        .collect();
+
+    let failing_jobs = jobs
+        .iter()
+        .filter(|job| job.summary.consecutive_failures > 0)
+        .count();
+    let stale_jobs = jobs.iter().filter(|job| job.no_success_in_days).count();
+
+    Json(json!({
+        "status": StatusCode::OK.as_u16(),
+        "tenant": tenant,
+        "days": days,
+        "failing_jobs": failing_jobs,
+        "stale_jobs": stale_jobs,
+        "jobs": jobs
+    }))
+}
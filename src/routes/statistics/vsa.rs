@@ -7,94 +7,27 @@ use reqwest::StatusCode;
 use serde_json::json;
 use sqlx::PgPool;
 
+use crate::functions::vsa::agents_os::vsa_os_statistics;
+
 pub async fn index(
     Query(params): Query<Vec<(String, String)>>,
     State(pool): State<PgPool>,
 ) -> impl IntoResponse {
     let tenant = &params[0].1;
 
-    let vsa_organizations_result =
-        sqlx::query!(
-            "SELECT organization_name, anti_virus, os_name FROM vsa_agents WHERE similarity(LOWER(organization_name), LOWER($1)) >= 0.6;",
-            tenant
-        )
-            .fetch_all(&pool)
-            .await
-            .expect("Failed to get vsa organization names from postgres.");
-
-    let mut agents_count = 0;
-    let mut agents_with_av_count = 0;
-    let mut win11_agents_count = 0;
-    let mut win10_agents_count = 0;
-    let mut win7_agents_count = 0;
-    let mut server_agents_count = 0;
-
-    for record in vsa_organizations_result {
-        let anti_virus = record.anti_virus.unwrap_or(false);
-        let os_name = record.os_name.unwrap_or("Unknown".to_string());
-
-        agents_count += 1;
-
-        if anti_virus {
-            agents_with_av_count += 1;
-        }
-
-        // Write a check to see if os_name is windows 11/10/7 else nothing
-        if os_name.contains("7601") {
-            if !os_name.to_lowercase().contains("server") {
-                win7_agents_count += 1;
-            }
-        }
-
-        // Check if os_name contains any of the windows 10 build numbers
-        if os_name.contains("10240")
-            || os_name.contains("10586")
-            || os_name.contains("14393")
-            || os_name.contains("15063")
-            || os_name.contains("16299")
-            || os_name.contains("17134")
-            || os_name.contains("17763")
-            || os_name.contains("18362")
-            || os_name.contains("18363")
-            || os_name.contains("19041")
-            || os_name.contains("19042")
-            || os_name.contains("19043")
-            || os_name.contains("19044")
-            || os_name.contains("19045")
-        {
-            if !os_name.to_lowercase().contains("server") {
-                win10_agents_count += 1;
-            }
-        }
-
-        // Check if os_name contains any of the windows 11 build numbers
-        if os_name.contains("22000") || os_name.contains("22621") || os_name.contains("22631") {
-            if !os_name.to_lowercase().contains("server") {
-                win11_agents_count += 1;
-            }
-        }
-
-        // Check if os_name is an os name for a server os
-        if os_name.to_lowercase().contains("server")
-            || os_name.to_lowercase().contains("ubuntu")
-            || os_name.to_lowercase().contains("debian")
-            || os_name.to_lowercase().contains("red hat")
-            || os_name.to_lowercase().contains("centos")
-            || os_name.to_lowercase().contains("suse")
-            || os_name.to_lowercase().contains("oracle")
-        {
-            server_agents_count += 1;
-        }
-    }
+    let statistics = vsa_os_statistics(&pool, tenant)
+        .await
+        .expect("Failed to get vsa statistics from postgres.");
 
     Json(json!({
         "status": StatusCode::OK.as_u16(),
         "tenant": tenant,
-        "agents": agents_count,
-        "agents_with_anti_virus": agents_with_av_count,
-        "win11_agents": win11_agents_count,
-        "win10_agents": win10_agents_count,
-        "win7_agents": win7_agents_count,
-        "server_agents": server_agents_count
+        "agents": statistics.agents,
+        "agents_with_anti_virus": statistics.agents_with_anti_virus,
+        "win11_agents": statistics.win11_agents,
+        "win10_agents": statistics.win10_agents,
+        "win7_agents": statistics.win7_agents,
+        "server_agents": statistics.server_agents,
+        "operating_systems": statistics.by_product
     }))
 }
@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::functions::vsa::ticket_metrics::ticket_metrics;
+
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let tenant = &params[0].1;
+
+    match ticket_metrics(&pool, tenant).await {
+        Ok(metrics) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "tenant": tenant,
+            "total_tickets": metrics.total,
+            "open_tickets": metrics.open,
+            "closed_tickets": metrics.closed,
+            "mean_resolution_hours": metrics.mean_resolution_hours,
+            "by_month": metrics.by_month,
+            "by_priority": metrics.by_priority,
+            "by_category": metrics.by_category
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": format!("Failed to get vsa ticket statistics: {}", error)
+        })),
+    }
+}
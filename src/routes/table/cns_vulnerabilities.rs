@@ -45,6 +45,7 @@ pub async fn index(
             LEFT JOIN cybercns_hosts AS h ON a.host = h.id
             LEFT JOIN cybercns_companies AS c ON a.company = c.id
             WHERE similarity(LOWER(c.name), LOWER($1)) >= 0.6
+            AND v.resolved_at IS NULL
             ORDER BY h.host_name;
         "#,
         tenant
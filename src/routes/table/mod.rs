@@ -4,4 +4,9 @@ pub mod rocket_cyber;
 pub mod rocket_cyber_agents;
 pub mod vsa;
 pub mod vsa_patching;
-pub mod spanning_backups;
\ No newline at end of file
+pub mod vsa_tickets;
+pub mod spanning_backups;
+pub mod veeam_agents;
+pub mod veeam_agents_jobs;
+pub mod veeam_servers;
+pub mod veeam_servers_jobs;
@@ -3,12 +3,16 @@ use axum::{
     response::IntoResponse,
     Json,
 };
+use chrono::{DateTime, Utc};
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::PgPool;
 
-use crate::models::spanning::spanning::{SpanningBackupSummary, SpanningUser};
+use crate::{
+    functions::spanning::spanning_backups::parse_spanning_platform,
+    models::spanning::spanning::{SpanningBackupSummary, SpanningUser},
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Tenant {
@@ -55,6 +59,10 @@ pub struct SpanningUserBackup {
     pub is_deleted: Option<bool>,
     pub backup_summary: Option<Vec<SpanningSummary>>,
     pub company_name: Option<String>,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    pub unassigned_at: Option<DateTime<Utc>>,
+    pub platform: Option<String>,
 }
 
 pub async fn index(
@@ -63,10 +71,15 @@ pub async fn index(
 ) -> impl IntoResponse {
     let tenant = &params[0].1;
 
+    let platform = params
+        .iter()
+        .find(|(key, _)| key == "platform")
+        .and_then(|(_, value)| parse_spanning_platform(value));
+
     let mut spanning_backups: Vec<SpanningUserBackup> = Vec::new();
 
     let backups: Vec<SpanningUser> =
-        sqlx::query_as!(SpanningUser, "SELECT * FROM spanning_backups WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6 ORDER BY email;", tenant)
+        sqlx::query_as!(SpanningUser, "SELECT * FROM spanning_backups WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6 AND ($2::TEXT IS NULL OR platform = $2) ORDER BY platform, email;", tenant, platform)
             .fetch_all(&pool)
             .await
             .expect("Failed to get vsa agents from postgres.");
@@ -113,12 +126,17 @@ pub async fn index(
             is_deleted: Some(backup.is_deleted),
             backup_summary: Some(spanning_summaries),
             company_name: Some(backup.company_name),
+            first_seen: Some(backup.first_seen),
+            last_seen: Some(backup.last_seen),
+            unassigned_at: backup.unassigned_at,
+            platform: Some(backup.platform),
         })
     }
 
     Json(json!({
         "status": StatusCode::OK.as_u16(),
         "tenant": tenant,
+        "platform": platform,
         "results": spanning_backups,
     }))
 }
@@ -0,0 +1,32 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::models::veeam::veeam_server::VeeamServer;
+
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let tenant = &params[0].1;
+
+    let veeam_servers_result = sqlx::query_as!(
+        VeeamServer,
+        "SELECT * FROM veeam_servers WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6 ORDER BY name;",
+        tenant
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam servers from postgres.");
+
+    Json(json!({
+        "status": StatusCode::OK.as_u16(),
+        "tenant": tenant,
+        "results": veeam_servers_result,
+    }))
+}
@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::models::vsa::tickets::VsaTicket;
+
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let tenant = &params[0].1;
+
+    let vsa_tickets_result = sqlx::query_as!(
+        VsaTicket,
+        r#"
+            SELECT
+                *
+            FROM vsa_tickets
+            WHERE similarity(LOWER(organization), LOWER($1)) >= 0.6
+            ORDER BY creation_date_time DESC;
+        "#,
+        tenant
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get vsa tickets from postgres.");
+
+    Json(json!({
+        "status": StatusCode::OK.as_u16(),
+        "tenant": tenant,
+        "results": vsa_tickets_result,
+    }))
+}
@@ -7,19 +7,37 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::PgPool;
 
+use crate::functions::spanning::spanning_backups::{
+    parse_spanning_region, DEFAULT_SPANNING_REGION, SPANNING_REGIONS,
+};
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Tenant {
     tenant_name: String,
     vsa_name: Option<String>,
     cyber_cns_name: Option<String>,
     rocket_cyber_name: Option<String>,
+    spanning_region: Option<String>,
 }
 
 pub async fn add_tenant(
     State(pool): State<PgPool>,
     Json(payload): Json<Tenant>,
 ) -> impl IntoResponse {
-    let created_tenant_result = sqlx::query!("INSERT INTO tenants (tenant_name, vsa_name, cyber_cns_name, rocket_cyber_name) VALUES ($1, $2, $3, $4);", payload.tenant_name, payload.vsa_name, payload.cyber_cns_name, payload.rocket_cyber_name).execute(&pool).await;
+    let spanning_region = match payload.spanning_region.as_deref() {
+        Some(spanning_region) => match parse_spanning_region(spanning_region) {
+            Some(spanning_region) => spanning_region,
+            None => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "message": format!("Invalid Spanning region. Expected one of: {}.", SPANNING_REGIONS.join(", "))
+                }))
+            }
+        },
+        None => DEFAULT_SPANNING_REGION,
+    };
+
+    let created_tenant_result = sqlx::query!("INSERT INTO tenants (tenant_name, vsa_name, cyber_cns_name, rocket_cyber_name, spanning_region) VALUES ($1, $2, $3, $4, $5);", payload.tenant_name, payload.vsa_name, payload.cyber_cns_name, payload.rocket_cyber_name, spanning_region).execute(&pool).await;
 
     match created_tenant_result {
         Ok(_) => Json(json!({
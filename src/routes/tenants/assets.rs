@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::{
+    functions::assets::correlate::ASSET_SOURCES,
+    models::inventory::{asset::Asset, asset_link::AssetLink},
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TenantAsset {
+    pub id: i32,
+    pub hostname: String,
+    pub display_name: String,
+    pub serial_number: Option<String>,
+    pub ip_address: Option<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub coverage: HashMap<String, bool>,
+    pub links: Vec<AssetLink>,
+}
+
+pub async fn index(Path(tenant_id): Path<i32>, State(pool): State<PgPool>) -> impl IntoResponse {
+    let tenant = sqlx::query!(
+        "SELECT id, tenant_name FROM tenants WHERE id = $1;",
+        tenant_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .expect("Failed to get tenant from postgres.");
+
+    let Some(tenant) = tenant else {
+        return Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "tenant_id": tenant_id,
+            "message": "Tenant not found."
+        }));
+    };
+
+    let assets = sqlx::query_as!(
+        Asset,
+        "SELECT * FROM assets WHERE tenant_id = $1 ORDER BY hostname;",
+        tenant.id
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get assets from postgres.");
+
+    let links = sqlx::query_as!(
+        AssetLink,
+        r#"
+            SELECT link.*
+            FROM asset_links AS link
+            INNER JOIN assets AS asset ON asset.id = link.asset_id
+            WHERE asset.tenant_id = $1
+            ORDER BY link.source, link.source_name;
+        "#,
+        tenant.id
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get asset links from postgres.");
+
+    let mut links_by_asset: HashMap<i32, Vec<AssetLink>> = HashMap::new();
+
+    for link in links {
+        links_by_asset.entry(link.asset_id).or_default().push(link);
+    }
+
+    let mut covered: HashMap<String, i64> = ASSET_SOURCES
+        .iter()
+        .map(|source| (source.to_string(), 0))
+        .collect();
+
+    let results: Vec<TenantAsset> = assets
+        .into_iter()
+        .map(|asset| {
+            let links = links_by_asset.remove(&asset.id).unwrap_or_default();
+
+            let coverage: HashMap<String, bool> = ASSET_SOURCES
+                .iter()
+                .map(|source| {
+                    (
+                        source.to_string(),
+                        links.iter().any(|link| link.source == *source),
+                    )
+                })
+                .collect();
+
+            for (source, is_covered) in &coverage {
+                if *is_covered {
+                    *covered.entry(source.clone()).or_default() += 1;
+                }
+            }
+
+            TenantAsset {
+                id: asset.id,
+                hostname: asset.hostname,
+                display_name: asset.display_name,
+                serial_number: asset.serial_number,
+                ip_address: asset.ip_address,
+                first_seen: asset.first_seen,
+                last_seen: asset.last_seen,
+                coverage,
+                links,
+            }
+        })
+        .collect();
+
+    Json(json!({
+        "status": StatusCode::OK.as_u16(),
+        "tenant": tenant.tenant_name,
+        "total_assets": results.len(),
+        "covered": covered,
+        "results": results
+    }))
+}
@@ -0,0 +1,412 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::models::spanning::spanning::{SpanningBackupSummary, SpanningUser};
+
+/// Only sessions and summaries newer than this are used for failure ratios.
+const BACKUP_HEALTH_WINDOW_DAYS: i64 = 30;
+/// Last successful backup older than this turns an entity amber.
+const AMBER_AFTER_HOURS: i64 = 26;
+/// Last successful backup older than this turns an entity red.
+const RED_AFTER_HOURS: i64 = 72;
+/// Failure ratio (0.0 - 1.0) at or above which an entity turns amber.
+const AMBER_FAILURE_RATIO: f64 = 0.1;
+/// Failure ratio (0.0 - 1.0) at or above which an entity turns red.
+const RED_FAILURE_RATIO: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupHealthStatus {
+    Red,
+    Amber,
+    Green,
+}
+
+/// Whether an entity is backed up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protection {
+    Protected,
+    /// Should be backed up but is not, such as a Veeam agent without an enabled job.
+    Unprotected,
+    /// Not backed up by choice, such as a Spanning user without a license assigned.
+    Unassigned,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BackupHealthEntity {
+    pub source: String,
+    pub kind: String,
+    pub name: String,
+    pub protected: bool,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_success_age_hours: Option<i64>,
+    pub successful: i64,
+    pub warning: i64,
+    pub failed: i64,
+    pub failure_ratio: f64,
+    pub status: BackupHealthStatus,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct BackupHealthSummary {
+    pub protected: i64,
+    pub unprotected: i64,
+    pub red: i64,
+    pub amber: i64,
+    pub green: i64,
+}
+
+impl BackupHealthSummary {
+    fn count(&mut self, entity: &BackupHealthEntity) {
+        if entity.protected {
+            self.protected += 1;
+        } else {
+            self.unprotected += 1;
+        }
+
+        match entity.status {
+            BackupHealthStatus::Red => self.red += 1,
+            BackupHealthStatus::Amber => self.amber += 1,
+            BackupHealthStatus::Green => self.green += 1,
+        }
+    }
+}
+
+pub async fn index(Path(tenant_id): Path<i32>, State(pool): State<PgPool>) -> impl IntoResponse {
+    let tenant = sqlx::query!(
+        "SELECT id, tenant_name FROM tenants WHERE id = $1;",
+        tenant_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .expect("Failed to get tenant from postgres.");
+
+    let Some(tenant) = tenant else {
+        return Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "tenant_id": tenant_id,
+            "message": "Tenant not found."
+        }));
+    };
+
+    let now = Utc::now();
+    let window_start = now - Duration::days(BACKUP_HEALTH_WINDOW_DAYS);
+
+    let mut entities: Vec<BackupHealthEntity> = Vec::new();
+
+    let veeam_agents = sqlx::query!(
+        r#"
+            SELECT
+                a.name AS "name!",
+                COUNT(DISTINCT j.id) FILTER (WHERE j.is_enabled) AS "enabled_jobs!",
+                COUNT(DISTINCT j.id) FILTER (WHERE LOWER(j.status) = 'failed') AS "failing_jobs!",
+                COUNT(DISTINCT j.id) AS "jobs!",
+                GREATEST(
+                    MAX(j.last_run) FILTER (WHERE LOWER(j.status) = 'success'),
+                    MAX(s.last_run) FILTER (WHERE LOWER(s.status) = 'success')
+                ) AS last_success,
+                COUNT(s.id) FILTER (WHERE LOWER(s.status) = 'success') AS "successful!",
+                COUNT(s.id) FILTER (WHERE LOWER(s.status) = 'warning') AS "warning!",
+                COUNT(s.id) FILTER (WHERE LOWER(s.status) = 'failed') AS "failed!"
+            FROM veeam_agents AS a
+            LEFT JOIN veeam_agents_jobs AS j ON j.backup_agent_uid = a.instance_uid
+            LEFT JOIN veeam_job_sessions AS s
+                ON s.job_kind = 'agent' AND s.job_uid = j.instance_uid AND s.last_run >= $2
+            WHERE similarity(LOWER(a.company_name), LOWER($1)) >= 0.6
+            GROUP BY a.id, a.name
+            ORDER BY a.name;
+        "#,
+        tenant.tenant_name,
+        window_start
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam agents from postgres.");
+
+    for agent in veeam_agents {
+        let failure_ratio = ratio(
+            agent.failed,
+            agent.successful + agent.warning + agent.failed,
+        )
+        .unwrap_or_else(|| ratio(agent.failing_jobs, agent.jobs).unwrap_or(0.0));
+
+        entities.push(health_entity(
+            "veeam",
+            "agent",
+            agent.name,
+            protection(agent.enabled_jobs > 0),
+            agent.last_success,
+            (agent.successful, agent.warning, agent.failed),
+            failure_ratio,
+            now,
+        ));
+    }
+
+    let veeam_server_jobs = sqlx::query!(
+        r#"
+            SELECT
+                j.name,
+                j.is_enabled,
+                LOWER(j.status) = 'failed' AS "is_failing!",
+                GREATEST(
+                    CASE WHEN LOWER(j.status) = 'success' THEN j.last_run END,
+                    MAX(s.last_run) FILTER (WHERE LOWER(s.status) = 'success')
+                ) AS last_success,
+                COUNT(s.id) FILTER (WHERE LOWER(s.status) = 'success') AS "successful!",
+                COUNT(s.id) FILTER (WHERE LOWER(s.status) = 'warning') AS "warning!",
+                COUNT(s.id) FILTER (WHERE LOWER(s.status) = 'failed') AS "failed!"
+            FROM veeam_servers_jobs AS j
+            LEFT JOIN veeam_job_sessions AS s
+                ON s.job_kind = 'server' AND s.job_uid = j.instance_uid AND s.last_run >= $2
+            WHERE similarity(LOWER(j.company_name), LOWER($1)) >= 0.6
+            GROUP BY j.id
+            ORDER BY j.name;
+        "#,
+        tenant.tenant_name,
+        window_start
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam server jobs from postgres.");
+
+    for job in veeam_server_jobs {
+        let failure_ratio = ratio(job.failed, job.successful + job.warning + job.failed)
+            .unwrap_or(if job.is_failing { 1.0 } else { 0.0 });
+
+        entities.push(health_entity(
+            "veeam",
+            "server_job",
+            job.name,
+            protection(job.is_enabled),
+            job.last_success,
+            (job.successful, job.warning, job.failed),
+            failure_ratio,
+            now,
+        ));
+    }
+
+    let spanning_users = sqlx::query_as!(
+        SpanningUser,
+        "SELECT * FROM spanning_backups WHERE similarity(LOWER(company_name), LOWER($1)) >= 0.6 ORDER BY email;",
+        tenant.tenant_name
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get spanning backups from postgres.");
+
+    let spanning_summaries = sqlx::query_as!(
+        SpanningBackupSummary,
+        r#"
+            SELECT s.*
+            FROM spanning_backups_summaries AS s
+            INNER JOIN spanning_backups AS b ON b.id = s.backup
+            WHERE similarity(LOWER(b.company_name), LOWER($1)) >= 0.6;
+        "#,
+        tenant.tenant_name
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get spanning backup summaries from postgres.");
+
+    let mut summaries_map: HashMap<i32, Vec<SpanningBackupSummary>> = HashMap::new();
+
+    for summary in spanning_summaries {
+        summaries_map
+            .entry(summary.backup)
+            .or_default()
+            .push(summary);
+    }
+
+    // Deleted users are gone from the tenant, they are not a backup gap.
+    for user in spanning_users.into_iter().filter(|user| !user.is_deleted) {
+        let mut last_success: Option<DateTime<Utc>> = None;
+        let (mut successful, mut partial, mut failed) = (0, 0, 0);
+
+        for summary in summaries_map.get(&user.id).into_iter().flatten() {
+            let Some(date) = parse_spanning_date(&summary.date) else {
+                continue;
+            };
+
+            if summary.successful > 0 && last_success.is_none_or(|last| date > last) {
+                last_success = Some(date);
+            }
+
+            if date >= window_start {
+                successful += summary.successful;
+                partial += summary.partial;
+                failed += summary.failed;
+            }
+        }
+
+        let failure_ratio = ratio(failed, successful + partial + failed).unwrap_or(0.0);
+
+        entities.push(health_entity(
+            "spanning",
+            &format!("{}_user", user.platform),
+            user.email,
+            if user.assigned {
+                Protection::Protected
+            } else {
+                Protection::Unassigned
+            },
+            last_success,
+            (successful, partial, failed),
+            failure_ratio,
+            now,
+        ));
+    }
+
+    let mut summary = BackupHealthSummary::default();
+    // Per entity kind, which splits Spanning users by platform (o365, google_workspace, salesforce).
+    let mut kinds: HashMap<String, BackupHealthSummary> = HashMap::new();
+
+    for entity in &entities {
+        summary.count(entity);
+        kinds.entry(entity.kind.clone()).or_default().count(entity);
+    }
+
+    let status = if summary.red > 0 {
+        BackupHealthStatus::Red
+    } else if summary.amber > 0 {
+        BackupHealthStatus::Amber
+    } else {
+        BackupHealthStatus::Green
+    };
+
+    Json(json!({
+        "status": StatusCode::OK.as_u16(),
+        "tenant_id": tenant.id,
+        "tenant": tenant.tenant_name,
+        "health": status,
+        "summary": summary,
+        "kinds": kinds,
+        "results": entities
+    }))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn health_entity(
+    source: &str,
+    kind: &str,
+    name: String,
+    protection: Protection,
+    last_success: Option<DateTime<Utc>>,
+    (successful, warning, failed): (i64, i64, i64),
+    failure_ratio: f64,
+    now: DateTime<Utc>,
+) -> BackupHealthEntity {
+    let last_success_age_hours = last_success.map(|last_success| (now - last_success).num_hours());
+
+    BackupHealthEntity {
+        source: source.to_string(),
+        kind: kind.to_string(),
+        name,
+        protected: protection == Protection::Protected,
+        last_success,
+        last_success_age_hours,
+        successful,
+        warning,
+        failed,
+        failure_ratio,
+        status: rag_status(protection, last_success_age_hours, failure_ratio),
+    }
+}
+
+fn protection(protected: bool) -> Protection {
+    if protected {
+        Protection::Protected
+    } else {
+        Protection::Unprotected
+    }
+}
+
+/// Unassigned entities are amber so they are reviewed, without turning the whole tenant red.
+fn rag_status(
+    protection: Protection,
+    last_success_age_hours: Option<i64>,
+    failure_ratio: f64,
+) -> BackupHealthStatus {
+    match last_success_age_hours {
+        _ if protection == Protection::Unprotected => BackupHealthStatus::Red,
+        _ if protection == Protection::Unassigned => BackupHealthStatus::Amber,
+        None => BackupHealthStatus::Red,
+        Some(age) if age > RED_AFTER_HOURS || failure_ratio >= RED_FAILURE_RATIO => {
+            BackupHealthStatus::Red
+        }
+        Some(age) if age > AMBER_AFTER_HOURS || failure_ratio >= AMBER_FAILURE_RATIO => {
+            BackupHealthStatus::Amber
+        }
+        Some(_) => BackupHealthStatus::Green,
+    }
+}
+
+fn ratio(part: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| part as f64 / total as f64)
+}
+
+fn parse_spanning_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+                .map(|date| date.and_utc())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_entities_by_protection_age_and_failures() {
+        assert_eq!(
+            rag_status(Protection::Protected, Some(2), 0.0),
+            BackupHealthStatus::Green
+        );
+        assert_eq!(
+            rag_status(Protection::Protected, Some(AMBER_AFTER_HOURS + 1), 0.0),
+            BackupHealthStatus::Amber
+        );
+        assert_eq!(
+            rag_status(Protection::Protected, Some(2), AMBER_FAILURE_RATIO),
+            BackupHealthStatus::Amber
+        );
+        assert_eq!(
+            rag_status(Protection::Protected, Some(RED_AFTER_HOURS + 1), 0.0),
+            BackupHealthStatus::Red
+        );
+        assert_eq!(
+            rag_status(Protection::Protected, Some(2), RED_FAILURE_RATIO),
+            BackupHealthStatus::Red
+        );
+        assert_eq!(
+            rag_status(Protection::Protected, None, 0.0),
+            BackupHealthStatus::Red
+        );
+        assert_eq!(
+            rag_status(Protection::Unprotected, Some(2), 0.0),
+            BackupHealthStatus::Red
+        );
+        assert_eq!(
+            rag_status(Protection::Unassigned, None, 0.0),
+            BackupHealthStatus::Amber
+        );
+        assert_eq!(
+            rag_status(Protection::Unassigned, Some(RED_AFTER_HOURS + 1), 1.0),
+            BackupHealthStatus::Amber
+        );
+    }
+}
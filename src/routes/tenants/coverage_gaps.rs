@@ -0,0 +1,44 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::functions::assets::coverage_gaps::coverage_gaps;
+
+pub async fn index(Path(tenant_id): Path<i32>, State(pool): State<PgPool>) -> impl IntoResponse {
+    let tenant = sqlx::query!(
+        "SELECT id, tenant_name FROM tenants WHERE id = $1;",
+        tenant_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .expect("Failed to get tenant from postgres.");
+
+    let Some(tenant) = tenant else {
+        return Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "tenant_id": tenant_id,
+            "message": "Tenant not found."
+        }));
+    };
+
+    match coverage_gaps(&pool, tenant.id).await {
+        Ok(gaps) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "tenant": tenant.tenant_name,
+            "total_missing_edr": gaps.missing_edr.len(),
+            "total_missing_vulnerability_scanning": gaps.missing_vulnerability_scanning.len(),
+            "total_missing_backup": gaps.missing_backup.len(),
+            "total_unmanaged": gaps.unmanaged.len(),
+            "results": gaps
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": format!("Failed to get coverage gaps: {}", error)
+        })),
+    }
+}
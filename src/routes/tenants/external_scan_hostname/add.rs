@@ -4,54 +4,109 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::PgPool;
 
+use crate::{
+    functions::cybercns::{
+        external_scan::{
+            parse_external_scan_frequency, DEFAULT_EXTERNAL_SCAN_FREQUENCY,
+            EXTERNAL_SCAN_FREQUENCIES,
+        },
+        external_scan_target::parse_external_scan_target,
+    },
+    models::tenant::tenant_external_scan_hostname::TenantExternalScanHostname,
+};
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AddExternalScanHostNamePayload {
-    pub tenant: String,
+    pub tenant_id: i32,
+    /// Hostname, IP address or CIDR range.
     pub host_name: String,
+    pub enabled: Option<bool>,
+    pub scan_frequency: Option<String>,
 }
 
 pub async fn add_tenant_external_scan_hostname(
     State(pool): State<PgPool>,
     Json(payload): Json<AddExternalScanHostNamePayload>,
 ) -> impl IntoResponse {
-    let tenant_result = sqlx::query!(
-        r#"
-        SELECT id FROM tenants WHERE similarity(LOWER(tenants.tenant_name), LOWER($1)) >= 0.6
-    "#,
-        payload.tenant
-    )
-    .fetch_one(&pool)
-    .await;
+    let scan_frequency = match payload.scan_frequency.as_deref() {
+        Some(scan_frequency) => match parse_external_scan_frequency(scan_frequency) {
+            Some(scan_frequency) => scan_frequency,
+            None => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "message": format!("Invalid scan frequency. Expected one of: {}.", EXTERNAL_SCAN_FREQUENCIES.join(", "))
+                }))
+            }
+        },
+        None => DEFAULT_EXTERNAL_SCAN_FREQUENCY,
+    };
 
-    match tenant_result {
-        Ok(tenant) => {
-            let created_tenant_result = sqlx::query!(
-                r#"
-                    INSERT INTO
-                        tenants_external_scan_host_names (tenant_id, host_name) 
-                    VALUES ($1, $2);
-                "#,
-                tenant.id,
-                payload.host_name
-            )
-            .execute(&pool)
+    let target = match parse_external_scan_target(&payload.host_name) {
+        Ok(target) => target,
+        Err(message) => {
+            return Json(json!({
+                "status": StatusCode::BAD_REQUEST.as_u16(),
+                "host_name": payload.host_name,
+                "message": message
+            }))
+        }
+    };
+
+    let tenant_result =
+        sqlx::query_scalar!("SELECT id FROM tenants WHERE id = $1;", payload.tenant_id)
+            .fetch_optional(&pool)
             .await;
 
-            match created_tenant_result {
-                Ok(_) => Json(json!({
-                    "status": StatusCode::OK.as_u16(),
-                    "message": "Created a new hostname."
-                })),
-                Err(err) => Json(json!({
-                    "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                    "message": "Failed to create new hostname.",
-                    "error": err.to_string()
-                })),
-            }
+    match tenant_result {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return Json(json!({
+                "status": StatusCode::NOT_FOUND.as_u16(),
+                "tenant_id": payload.tenant_id,
+                "message": "Tenant not found."
+            }))
         }
-        Err(_) => Json(json!({
+        Err(_) => {
+            return Json(json!({
+                "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                "message": "Failed to find tenant in database."
+            }))
+        }
+    }
+
+    let created_hostname_result = sqlx::query_as!(
+        TenantExternalScanHostname,
+        r#"
+            INSERT INTO
+                tenants_external_scan_host_names (tenant_id, host_name, target_kind, enabled, scan_frequency)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (tenant_id, host_name) DO NOTHING
+            RETURNING *;
+        "#,
+        payload.tenant_id,
+        target.to_string(),
+        target.kind(),
+        payload.enabled.unwrap_or(true),
+        scan_frequency
+    )
+    .fetch_optional(&pool)
+    .await;
+
+    match created_hostname_result {
+        Ok(Some(hostname)) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "message": "Created a new hostname.",
+            "data": hostname
+        })),
+        Ok(None) => Json(json!({
+            "status": StatusCode::CONFLICT.as_u16(),
+            "host_name": target.to_string(),
+            "message": "The tenant already has this hostname."
+        })),
+        Err(err) => Json(json!({
             "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-            "message": "Failed to find tenant in database."
+            "message": "Failed to create new hostname.",
+            "error": err.to_string()
         })),
     }
 }
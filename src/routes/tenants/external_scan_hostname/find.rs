@@ -9,25 +9,50 @@ use sqlx::PgPool;
 
 use crate::models::tenant::tenant_external_scan_hostname::TenantExternalScanHostname;
 
+/// External scan hostnames of the tenant with `tenant_id`, or whose name matches `tenant`.
 pub async fn index(
     Query(params): Query<Vec<(String, String)>>,
     State(pool): State<PgPool>,
 ) -> impl IntoResponse {
-    let tenant = &params[0].1;
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let tenant_id = match param("tenant_id").map(|tenant_id| tenant_id.parse::<i32>()) {
+        Some(Ok(tenant_id)) => Some(tenant_id),
+        Some(Err(_)) => {
+            return Json(json!({
+                "status": StatusCode::BAD_REQUEST.as_u16(),
+                "message": "tenant_id must be a number."
+            }))
+        }
+        None => None,
+    };
+    let tenant = param("tenant");
+
+    if tenant_id.is_none() && tenant.is_none() {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "A tenant_id or tenant name is required."
+        }));
+    }
 
     let hostnames_result = sqlx::query_as!(
         TenantExternalScanHostname,
         r#"
-            SELECT
-                e.id as id,
-                e.tenant_id as tenant_id,
-                e.host_name as host_name
+            SELECT e.*
             FROM tenants_external_scan_host_names AS e
             LEFT JOIN
                 tenants AS t ON e.tenant_id = t.id
             WHERE
-                similarity(LOWER(t.tenant_name), LOWER($1)) >= 0.6
+                ($1::INTEGER IS NULL OR e.tenant_id = $1)
+                AND ($2::TEXT IS NULL OR similarity(LOWER(t.tenant_name), LOWER($2)) >= 0.6)
+            ORDER BY e.tenant_id, e.host_name
         "#,
+        tenant_id,
         tenant
     )
     .fetch_all(&pool)
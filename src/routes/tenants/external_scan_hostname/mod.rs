@@ -1,3 +1,5 @@
 pub mod add;
 pub mod find;
 pub mod remove;
+pub mod scan;
+pub mod update;
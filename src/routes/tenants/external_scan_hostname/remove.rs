@@ -7,27 +7,60 @@ use reqwest::StatusCode;
 use serde_json::json;
 use sqlx::PgPool;
 
+/// Delete a hostname by `id`, or by `host_name` for every tenant or only the one with `tenant_id`.
 pub async fn remove_external_scan_hostname(
     Query(params): Query<Vec<(String, String)>>,
     State(pool): State<PgPool>,
 ) -> impl IntoResponse {
-    let host_name = &params[0].1;
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let id = param("id").and_then(|id| id.parse::<i32>().ok());
+    let tenant_id = param("tenant_id").and_then(|tenant_id| tenant_id.parse::<i32>().ok());
+    let host_name =
+        param("host_name").map(|host_name| host_name.trim().trim_end_matches('.').to_lowercase());
+
+    if id.is_none() && host_name.is_none() {
+        return Json(json!({
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+            "message": "A numeric id or a host_name is required."
+        }));
+    }
 
     let delete_hostname_result = sqlx::query!(
-        "DELETE FROM tenants_external_scan_host_names WHERE host_name = $1",
-        host_name
+        r#"
+            DELETE FROM tenants_external_scan_host_names
+            WHERE ($1::INTEGER IS NULL OR id = $1)
+            AND ($2::TEXT IS NULL OR host_name = $2)
+            AND ($3::INTEGER IS NULL OR tenant_id = $3);
+        "#,
+        id,
+        host_name,
+        tenant_id
     )
     .execute(&pool)
     .await;
 
     match delete_hostname_result {
+        Ok(result) if result.rows_affected() == 0 => Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "id": id,
+            "host_name": host_name,
+            "message": "Hostname not found."
+        })),
         Ok(_) => Json(json!({
             "status": StatusCode::OK.as_u16(),
+            "id": id,
             "host_name": host_name,
             "message": "Deleted hostname."
         })),
         Err(err) => Json(json!({
             "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "id": id,
             "host_name": host_name,
             "message": "Failed to delete hostname.",
             "error": err.to_string()
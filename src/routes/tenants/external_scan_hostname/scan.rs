@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::functions::cybercns::{
+    external_scan::external_scan_hostname, external_scan_runner::external_scan_runner,
+};
+
+/**
+Scan one of the tenant's hostnames now, through the same runner as the daily scans.
+
+The returned run `id` can be polled at `/scans/runs?id=`, its `scan_id` is set once the results
+are stored. A hostname already being scanned is not queued again, its current run is returned.
+*/
+pub async fn index(
+    Path((tenant_id, hostname_id)): Path<(i32, i32)>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let hostname = match external_scan_hostname(&pool, tenant_id, hostname_id).await {
+        Ok(Some(hostname)) => hostname,
+        Ok(None) => {
+            return Json(json!({
+                "status": StatusCode::NOT_FOUND.as_u16(),
+                "tenant_id": tenant_id,
+                "hostname_id": hostname_id,
+                "message": "Hostname not found for this tenant."
+            }))
+        }
+        Err(error) => {
+            return Json(json!({
+                "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                "message": "Failed to get hostname.",
+                "error": error.to_string()
+            }))
+        }
+    };
+
+    let host_name = hostname.host_name.clone();
+
+    let run = match external_scan_runner().queue(&pool, hostname).await {
+        Ok(run) => run,
+        Err(message) => {
+            return Json(json!({
+                "status": StatusCode::BAD_REQUEST.as_u16(),
+                "host_name": host_name,
+                "message": message
+            }))
+        }
+    };
+
+    Json(json!({
+        "status": StatusCode::ACCEPTED.as_u16(),
+        "message": "Scan queued.",
+        "data": run
+    }))
+}
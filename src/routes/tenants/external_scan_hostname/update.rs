@@ -0,0 +1,72 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::{
+    functions::cybercns::external_scan::{
+        parse_external_scan_frequency, EXTERNAL_SCAN_FREQUENCIES,
+    },
+    models::tenant::tenant_external_scan_hostname::TenantExternalScanHostname,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateExternalScanHostNamePayload {
+    pub id: i32,
+    pub enabled: Option<bool>,
+    pub scan_frequency: Option<String>,
+}
+
+/// Enable or disable a hostname or change how often it is scanned, fields left out are kept.
+pub async fn update_external_scan_hostname(
+    State(pool): State<PgPool>,
+    Json(payload): Json<UpdateExternalScanHostNamePayload>,
+) -> impl IntoResponse {
+    let scan_frequency = match payload.scan_frequency.as_deref() {
+        Some(scan_frequency) => match parse_external_scan_frequency(scan_frequency) {
+            Some(scan_frequency) => Some(scan_frequency),
+            None => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "message": format!("Invalid scan frequency. Expected one of: {}.", EXTERNAL_SCAN_FREQUENCIES.join(", "))
+                }))
+            }
+        },
+        None => None,
+    };
+
+    let updated_hostname_result = sqlx::query_as!(
+        TenantExternalScanHostname,
+        r#"
+            UPDATE tenants_external_scan_host_names SET
+                enabled = COALESCE($2, enabled),
+                scan_frequency = COALESCE($3, scan_frequency)
+            WHERE id = $1
+            RETURNING *;
+        "#,
+        payload.id,
+        payload.enabled,
+        scan_frequency
+    )
+    .fetch_optional(&pool)
+    .await;
+
+    match updated_hostname_result {
+        Ok(Some(hostname)) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "message": "Updated hostname.",
+            "data": hostname
+        })),
+        Ok(None) => Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "id": payload.id,
+            "message": "Hostname not found."
+        })),
+        Err(err) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to update hostname.",
+            "error": err.to_string()
+        })),
+    }
+}
@@ -15,6 +15,7 @@ struct Tenant {
     spanning_key: Option<String>,
     veeam_url: Option<String>,
     veeam_key: Option<String>,
+    spanning_region: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -26,6 +27,7 @@ struct TaggedTenant {
     tenant_rocket_name: Option<String>,
     tenant_spanning_name: Option<String>,
     tenant_spanning_key: Option<String>,
+    tenant_spanning_region: String,
     tenant_veeam_url: Option<String>,
     tenant_veeam_key: Option<String>,
     tenant_tags: Vec<String>,
@@ -71,6 +73,7 @@ pub async fn all(State(pool): State<PgPool>) -> impl IntoResponse {
                     tenant_rocket_name: tenant.rocket_cyber_name,
                     tenant_spanning_name: tenant.spanning_name,
                     tenant_spanning_key: tenant.spanning_key,
+                    tenant_spanning_region: tenant.spanning_region,
                     tenant_veeam_url: tenant.veeam_url,
                     tenant_veeam_key: tenant.veeam_key,
                     tenant_tags,
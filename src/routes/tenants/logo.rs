@@ -0,0 +1,135 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+use crate::{
+    functions::tenant_assets::tenant_asset_store::TENANT_LOGO_KIND,
+    models::tenant::tenant_asset::TenantAsset, routes::logos::find::tenant_asset_response,
+};
+
+/**
+The logo variant to serve for a display `size`.
+
+The smallest processed variant at least `size` pixels on its longest side, else the largest
+processed variant. Assets stored before images were processed have no dimensions and are only
+chosen when there is nothing else.
+*/
+fn select_logo(logos: Vec<TenantAsset>, size: Option<i32>) -> Option<TenantAsset> {
+    let mut variants: Vec<(i32, TenantAsset)> = Vec::new();
+    let mut unprocessed: Option<TenantAsset> = None;
+
+    for logo in logos {
+        match (logo.width, logo.height) {
+            (Some(width), Some(height)) => variants.push((width.max(height), logo)),
+            _ => unprocessed = Some(logo),
+        }
+    }
+
+    variants.sort_by_key(|(longest_side, _)| *longest_side);
+
+    let covering = size.and_then(|size| {
+        variants
+            .iter()
+            .position(|(longest_side, _)| *longest_side >= size)
+    });
+
+    match covering {
+        Some(index) => Some(variants.swap_remove(index).1),
+        None => variants.pop().map(|(_, logo)| logo).or(unprocessed),
+    }
+}
+
+/**
+The tenant's logo.
+
+`size` is the longest side in pixels the logo will be displayed at. The smallest variant at least
+that large is returned, or the largest variant when none is (or when `size` is not given).
+*/
+pub async fn index(
+    Path(tenant_id): Path<i32>,
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let size = match params.iter().find(|(key, _)| key == "size") {
+        Some((_, size)) => match size.parse::<i32>() {
+            Ok(size) if size > 0 => Some(size),
+            _ => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    "size must be a positive number of pixels.".to_string(),
+                ))
+            }
+        },
+        None => None,
+    };
+
+    let logos = sqlx::query_as!(
+        TenantAsset,
+        "SELECT * FROM tenant_assets WHERE tenant_id = $1 AND kind = $2;",
+        tenant_id,
+        TENANT_LOGO_KIND
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+    match select_logo(logos, size) {
+        Some(logo) => tenant_asset_response(logo, &headers).await,
+        None => Err((StatusCode::NOT_FOUND, "Logo not found.".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn logo(variant: &str, dimensions: Option<(i32, i32)>) -> TenantAsset {
+        TenantAsset {
+            id: 0,
+            tenant_id: 1,
+            kind: TENANT_LOGO_KIND.to_string(),
+            file_name: "logo.png".to_string(),
+            mime_type: "image/png".to_string(),
+            size: 0,
+            sha256: String::new(),
+            created_at: Utc::now(),
+            variant: variant.to_string(),
+            width: dimensions.map(|(width, _)| width),
+            height: dimensions.map(|(_, height)| height),
+        }
+    }
+
+    fn selected(logos: Vec<TenantAsset>, size: Option<i32>) -> Option<String> {
+        select_logo(logos, size).map(|logo| logo.variant)
+    }
+
+    #[test]
+    fn selects_the_smallest_variant_covering_the_size() {
+        let logos = || {
+            vec![
+                logo("full", Some((1024, 512))),
+                logo("original", None),
+                logo("thumbnail", Some((128, 256))),
+            ]
+        };
+
+        assert_eq!(selected(logos(), Some(64)), Some("thumbnail".to_string()));
+        assert_eq!(selected(logos(), Some(256)), Some("thumbnail".to_string()));
+        assert_eq!(selected(logos(), Some(257)), Some("full".to_string()));
+        assert_eq!(selected(logos(), Some(4096)), Some("full".to_string()));
+        assert_eq!(selected(logos(), None), Some("full".to_string()));
+
+        assert_eq!(
+            selected(vec![logo("original", None)], Some(64)),
+            Some("original".to_string())
+        );
+        assert_eq!(selected(Vec::new(), None), None);
+    }
+}
@@ -1,5 +1,11 @@
+pub mod assets;
 pub mod add;
+pub mod backup_health;
+pub mod coverage_gaps;
 pub mod external_scan_hostname;
 pub mod find;
+pub mod logo;
 pub mod remove;
+pub mod spanning_platform;
 pub mod update;
+pub mod vulnerability_priorities;
@@ -0,0 +1,62 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::functions::spanning::spanning_backups::{
+    parse_spanning_platform, O365_SPANNING_PLATFORM,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AddSpanningPlatformPayload {
+    pub tenant_id: i32,
+    pub platform: String,
+    pub spanning_name: String,
+    pub spanning_key: String,
+}
+
+pub async fn add_tenant_spanning_platform(
+    State(pool): State<PgPool>,
+    Json(payload): Json<AddSpanningPlatformPayload>,
+) -> impl IntoResponse {
+    // Microsoft 365 credentials live on the tenant itself alongside its region.
+    let platform = match parse_spanning_platform(&payload.platform) {
+        Some(platform) if platform != O365_SPANNING_PLATFORM => platform,
+        _ => {
+            return Json(json!({
+                "status": StatusCode::BAD_REQUEST.as_u16(),
+                "message": "Invalid Spanning platform. Expected one of: google_workspace, salesforce."
+            }))
+        }
+    };
+
+    let created_platform_result = sqlx::query!(
+        r#"
+            INSERT INTO
+                tenants_spanning_platforms (tenant_id, platform, spanning_name, spanning_key)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (tenant_id, platform) DO UPDATE SET
+                spanning_name = EXCLUDED.spanning_name,
+                spanning_key = EXCLUDED.spanning_key;
+        "#,
+        payload.tenant_id,
+        platform,
+        payload.spanning_name,
+        payload.spanning_key
+    )
+    .execute(&pool)
+    .await;
+
+    match created_platform_result {
+        Ok(_) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "message": "Saved spanning platform. Its backups are not synced yet, only Microsoft 365 is supported."
+        })),
+        Err(err) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to save spanning platform.",
+            "error": err.to_string()
+        })),
+    }
+}
@@ -0,0 +1,49 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::models::tenant::tenant_spanning_platform::TenantSpanningPlatform;
+
+pub async fn index(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let tenant = &params[0].1;
+
+    let platforms_result = sqlx::query_as!(
+        TenantSpanningPlatform,
+        r#"
+            SELECT
+                p.id,
+                p.tenant_id,
+                p.platform,
+                p.spanning_name,
+                p.spanning_key
+            FROM tenants_spanning_platforms AS p
+            LEFT JOIN
+                tenants AS t ON p.tenant_id = t.id
+            WHERE
+                similarity(LOWER(t.tenant_name), LOWER($1)) >= 0.6
+            ORDER BY p.platform
+        "#,
+        tenant
+    )
+    .fetch_all(&pool)
+    .await;
+
+    match platforms_result {
+        Ok(platforms) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "results": platforms
+        })),
+        Err(_) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": "Failed to find spanning platforms from database."
+        })),
+    }
+}
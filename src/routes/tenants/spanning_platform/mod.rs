@@ -0,0 +1,3 @@
+pub mod add;
+pub mod find;
+pub mod remove;
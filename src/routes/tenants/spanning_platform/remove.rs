@@ -0,0 +1,36 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+pub async fn remove_tenant_spanning_platform(
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let platform_id = &params[0].1.parse::<i32>().unwrap_or_default();
+
+    let delete_platform_result = sqlx::query!(
+        "DELETE FROM tenants_spanning_platforms WHERE id = $1",
+        platform_id
+    )
+    .execute(&pool)
+    .await;
+
+    match delete_platform_result {
+        Ok(_) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "platform_id": platform_id,
+            "message": "Deleted spanning platform."
+        })),
+        Err(err) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "platform_id": platform_id,
+            "message": "Failed to delete spanning platform.",
+            "error": err.to_string()
+        })),
+    }
+}
@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::PgPool;
 
+use crate::functions::spanning::spanning_backups::{parse_spanning_region, SPANNING_REGIONS};
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Tenant {
     tenant_id: i32,
@@ -16,6 +18,7 @@ pub struct Tenant {
     rocket_cyber_name: Option<String>,
     spanning_name: Option<String>,
     spanning_key: Option<String>,
+    spanning_region: Option<String>,
     veeam_url: Option<String>,
     veeam_key: Option<String>,
 }
@@ -24,9 +27,22 @@ pub async fn update_tenant(
     State(pool): State<PgPool>,
     Json(payload): Json<Tenant>,
 ) -> impl IntoResponse {
+    let spanning_region = match payload.spanning_region.as_deref() {
+        Some(spanning_region) => match parse_spanning_region(spanning_region) {
+            Some(spanning_region) => Some(spanning_region.to_string()),
+            None => {
+                return Json(json!({
+                    "status": StatusCode::BAD_REQUEST.as_u16(),
+                    "message": format!("Invalid Spanning region. Expected one of: {}.", SPANNING_REGIONS.join(", "))
+                }))
+            }
+        },
+        None => None,
+    };
+
     let updated_tenant_result =
         sqlx::query!(
-            "UPDATE tenants SET vsa_name = $1, cyber_cns_name = $2, rocket_cyber_name = $3, tenant_name = $4, spanning_name = $5, spanning_key = $6, veeam_url = $7, veeam_key = $8 WHERE id = $9;",
+            "UPDATE tenants SET vsa_name = $1, cyber_cns_name = $2, rocket_cyber_name = $3, tenant_name = $4, spanning_name = $5, spanning_key = $6, veeam_url = $7, veeam_key = $8, spanning_region = COALESCE($10, spanning_region) WHERE id = $9;",
             payload.vsa_name,
             payload.cyber_cns_name,
             payload.rocket_cyber_name,
@@ -35,7 +51,8 @@ pub async fn update_tenant(
             payload.spanning_key,
             payload.veeam_url,
             payload.veeam_key,
-            payload.tenant_id
+            payload.tenant_id,
+            spanning_region
         )
         .execute(&pool)
         .await;
@@ -52,6 +69,7 @@ pub async fn update_tenant(
                 rocket_cyber_name: payload.rocket_cyber_name,
                 spanning_name: payload.spanning_name,
                 spanning_key: payload.spanning_key,
+                spanning_region,
                 veeam_url: payload.veeam_url,
                 veeam_key: payload.veeam_key,
             },
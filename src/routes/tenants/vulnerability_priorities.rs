@@ -0,0 +1,75 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::functions::cybercns::vulnerability_priorities::vulnerability_priorities;
+
+const DEFAULT_VULNERABILITY_PRIORITIES: usize = 20;
+const MAX_VULNERABILITY_PRIORITIES: usize = 500;
+
+/// The tenant's open vulnerabilities to fix first, `limit` of them (20 by default, at most 500).
+pub async fn index(
+    Path(tenant_id): Path<i32>,
+    Query(params): Query<Vec<(String, String)>>,
+    State(pool): State<PgPool>,
+) -> impl IntoResponse {
+    let limit = match params
+        .iter()
+        .find(|(key, _)| key == "limit")
+        .map(|(_, value)| value.parse::<usize>())
+    {
+        Some(Ok(limit)) if limit > 0 => limit.min(MAX_VULNERABILITY_PRIORITIES),
+        Some(_) => {
+            return Json(json!({
+                "status": StatusCode::BAD_REQUEST.as_u16(),
+                "message": "limit must be a positive number."
+            }))
+        }
+        None => DEFAULT_VULNERABILITY_PRIORITIES,
+    };
+
+    let tenant = sqlx::query!(
+        "SELECT id, tenant_name FROM tenants WHERE id = $1;",
+        tenant_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .expect("Failed to get tenant from postgres.");
+
+    let Some(tenant) = tenant else {
+        return Json(json!({
+            "status": StatusCode::NOT_FOUND.as_u16(),
+            "tenant_id": tenant_id,
+            "message": "Tenant not found."
+        }));
+    };
+
+    match vulnerability_priorities(&pool, tenant.id).await {
+        Ok(mut priorities) => {
+            let total = priorities.len();
+            let known_exploited = priorities
+                .iter()
+                .filter(|priority| priority.known_exploited)
+                .count();
+
+            priorities.truncate(limit);
+
+            Json(json!({
+                "status": StatusCode::OK.as_u16(),
+                "tenant": tenant.tenant_name,
+                "total": total,
+                "total_known_exploited": known_exploited,
+                "results": priorities
+            }))
+        }
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": format!("Failed to get vulnerability priorities: {}", error)
+        })),
+    }
+}
@@ -0,0 +1,5 @@
+pub mod veeam_agents;
+pub mod veeam_agents_jobs;
+pub mod veeam_job_sessions;
+pub mod veeam_servers;
+pub mod veeam_servers_jobs;
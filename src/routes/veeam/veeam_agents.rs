@@ -0,0 +1,14 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use sqlx::PgPool;
+
+use crate::models::veeam::veeam_agent::VeeamAgent;
+
+pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
+    let agents: Vec<VeeamAgent> =
+        sqlx::query_as!(VeeamAgent, "SELECT * FROM veeam_agents ORDER BY name;")
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to get veeam agents from postgres.");
+
+    Json(agents)
+}
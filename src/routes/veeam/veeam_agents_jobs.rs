@@ -0,0 +1,16 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use sqlx::PgPool;
+
+use crate::models::veeam::veeam_agent_job::VeeamAgentJob;
+
+pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
+    let jobs: Vec<VeeamAgentJob> = sqlx::query_as!(
+        VeeamAgentJob,
+        "SELECT * FROM veeam_agents_jobs ORDER BY name;"
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam agent jobs from postgres.");
+
+    Json(jobs)
+}
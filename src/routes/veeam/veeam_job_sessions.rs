@@ -0,0 +1,16 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use sqlx::PgPool;
+
+use crate::models::veeam::veeam_job_session::VeeamJobSession;
+
+pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
+    let sessions: Vec<VeeamJobSession> = sqlx::query_as!(
+        VeeamJobSession,
+        "SELECT * FROM veeam_job_sessions ORDER BY last_run DESC;"
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam job sessions from postgres.");
+
+    Json(sessions)
+}
@@ -0,0 +1,14 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use sqlx::PgPool;
+
+use crate::models::veeam::veeam_server::VeeamServer;
+
+pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
+    let servers: Vec<VeeamServer> =
+        sqlx::query_as!(VeeamServer, "SELECT * FROM veeam_servers ORDER BY name;")
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to get veeam servers from postgres.");
+
+    Json(servers)
+}
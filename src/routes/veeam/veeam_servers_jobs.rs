@@ -0,0 +1,16 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use sqlx::PgPool;
+
+use crate::models::veeam::veeam_server_job::VeeamServerJob;
+
+pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
+    let jobs: Vec<VeeamServerJob> = sqlx::query_as!(
+        VeeamServerJob,
+        "SELECT * FROM veeam_servers_jobs ORDER BY name;"
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("Failed to get veeam server jobs from postgres.");
+
+    Json(jobs)
+}
@@ -3,7 +3,10 @@ use reqwest::StatusCode;
 use serde_json::json;
 use sqlx::PgPool;
 
-use crate::{functions::vsa::agents::agents, models::vsa::agent::VsaAgent};
+use crate::{
+    functions::vsa::{agents::agents, agents_os::store_agent_os},
+    models::vsa::agent::VsaAgent,
+};
 
 pub async fn index(State(pool): State<PgPool>) -> impl IntoResponse {
     let agents: Vec<VsaAgent> = sqlx::query_as!(VsaAgent, "SELECT * FROM vsa_agents;")
@@ -53,6 +56,17 @@ pub async fn import(State(pool): State<PgPool>) -> impl IntoResponse {
                 inserted += 1;
             }
         }
+
+        if let Err(error) = store_agent_os(
+            &pool,
+            &agent_id,
+            agent.operating_system.as_deref(),
+            agent.operating_system_info.as_deref(),
+        )
+        .await
+        {
+            println!("Failed to classify VSA agent {} OS: {:?}", agent_id, error);
+        }
     }
 
     Json(json!({
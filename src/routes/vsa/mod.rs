@@ -4,3 +4,4 @@ pub mod disks;
 pub mod groups;
 pub mod patches;
 pub mod security_products;
+pub mod tickets;
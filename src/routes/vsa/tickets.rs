@@ -0,0 +1,31 @@
+use axum::{extract::State, response::IntoResponse, Json};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::functions::vsa::{tickets::tickets, tickets_sync::sync_tickets};
+
+pub async fn index() -> impl IntoResponse {
+    match tickets(None).await {
+        Ok(tickets) => Json(json!(tickets)),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": format!("Failed to get VSA tickets: {}", error)
+        })),
+    }
+}
+
+pub async fn import(State(pool): State<PgPool>) -> impl IntoResponse {
+    match sync_tickets(&pool).await {
+        Ok(result) => Json(json!({
+            "status": StatusCode::OK.as_u16(),
+            "inserted": result.inserted,
+            "updated": result.updated,
+            "skipped": result.skipped
+        })),
+        Err(error) => Json(json!({
+            "status": StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            "message": format!("Failed to sync VSA tickets: {}", error)
+        })),
+    }
+}